@@ -0,0 +1,110 @@
+use std::hint::black_box;
+
+use binero::{FixedGrid, Grid, SolverConfig};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+
+// Puzzles are separated by blank lines, each one starting with its size
+const CORPUS: [(&str, &str); 3] = [
+    ("easy", include_str!("corpus/easy.txt")),
+    ("hard", include_str!("corpus/hard.txt")),
+    ("expert", include_str!("corpus/expert.txt")),
+];
+
+fn corpus() -> Vec<(String, Vec<&'static str>)> {
+    CORPUS
+        .iter()
+        .flat_map(|(level, text)| text.split("\n\n").map(move |puzzle| (level, puzzle)))
+        .map(|(level, puzzle)| {
+            let lines: Vec<_> = puzzle.lines().collect();
+            let size = lines[0].trim_start_matches('#').trim();
+
+            (format!("{}/{}", level, size), lines)
+        })
+        .collect()
+}
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+
+    for (name, lines) in corpus() {
+        group.bench_with_input(BenchmarkId::from_parameter(name), &lines, |b, lines| {
+            b.iter(|| Grid::parse(black_box(lines.iter())).unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+fn is_valid(c: &mut Criterion) {
+    let mut group = c.benchmark_group("is_valid");
+
+    // Complete grids exercise every rule
+    for (name, lines) in corpus() {
+        let mut grid = Grid::parse(lines.iter()).unwrap();
+        grid.solve().unwrap();
+
+        group.bench_with_input(BenchmarkId::from_parameter(name), &grid, |b, grid| {
+            b.iter(|| black_box(grid).is_valid())
+        });
+    }
+
+    group.finish();
+}
+
+fn logic(c: &mut Criterion) {
+    let mut group = c.benchmark_group("logic");
+    let config = SolverConfig::new().max_depth(0);
+
+    // Stops at the first guess on puzzles that need one
+    for (name, lines) in corpus() {
+        let grid = Grid::parse(lines.iter()).unwrap();
+
+        group.bench_with_input(BenchmarkId::from_parameter(name), &grid, |b, grid| {
+            b.iter(|| grid.clone().solve_with(&config))
+        });
+    }
+
+    group.finish();
+}
+
+fn solve(c: &mut Criterion) {
+    let mut group = c.benchmark_group("solve");
+
+    for (name, lines) in corpus() {
+        let grid = Grid::parse(lines.iter()).unwrap();
+
+        group.bench_with_input(BenchmarkId::from_parameter(name), &grid, |b, grid| {
+            b.iter(|| grid.clone().solve().unwrap())
+        });
+    }
+
+    group.finish();
+}
+
+fn fixed(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fixed");
+
+    // The same puzzles, solved from a heap grid and from an inline one
+    for (name, lines) in corpus()
+        .into_iter()
+        .filter(|(name, _)| name.ends_with("14x14"))
+    {
+        let grid = Grid::parse(lines.iter()).unwrap();
+        let fixed = FixedGrid::<14, 14>::try_from(&grid).unwrap();
+
+        group.bench_with_input(BenchmarkId::new("grid", &name), &grid, |b, grid| {
+            b.iter(|| grid.clone().solve().unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("fixed", &name), &fixed, |b, fixed| {
+            b.iter(|| {
+                let mut fixed = *fixed;
+                fixed.solve().unwrap()
+            })
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, parse, is_valid, logic, solve, fixed);
+criterion_main!(benches);
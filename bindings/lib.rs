@@ -0,0 +1,3 @@
+// The exported functions all come from the main package, this crate only
+// links them into a shared library
+pub use binero::*;
@@ -0,0 +1,20 @@
+fn main() {
+    // The C header is only generated when the C interface is built, into the
+    // build directory: the copy in include/ is checked against it by the tests
+    #[cfg(feature = "ffi")]
+    {
+        let dir = std::env::var("CARGO_MANIFEST_DIR").expect("cargo sets the manifest directory");
+        let out = std::env::var("OUT_DIR").expect("cargo sets the output directory");
+        let config = cbindgen::Config::from_file("cbindgen.toml").expect("cbindgen.toml is valid");
+
+        println!("cargo:rerun-if-changed=src/ffi.rs");
+        println!("cargo:rerun-if-changed=cbindgen.toml");
+
+        cbindgen::Builder::new()
+            .with_crate(&dir)
+            .with_config(config)
+            .generate()
+            .expect("the C interface can be exported")
+            .write_to_file(format!("{}/binero.h", out));
+    }
+}
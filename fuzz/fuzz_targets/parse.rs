@@ -0,0 +1,17 @@
+#![no_main]
+
+use binero::{Grid, Pack, ParseOptions};
+use libfuzzer_sys::fuzz_target;
+
+// Every reader must turn bad input into an error, never into a panic
+fuzz_target!(|data: &str| {
+    let _ = Grid::parse(data.lines());
+    let _ = Grid::parse_collection(data.lines(), &ParseOptions::default());
+    let _ = Grid::parse_csv(data.lines());
+    let _ = Grid::from_json(data);
+    let _ = Grid::from_line_format(data);
+    let _ = Grid::from_binarypuzzle(data);
+    let _ = Grid::from_janko(data);
+    let _ = Grid::from_compact_string(data);
+    let _ = Pack::from_json(data);
+});
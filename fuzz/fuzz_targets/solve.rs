@@ -0,0 +1,21 @@
+#![no_main]
+
+use binero::{Grid, SolverConfig};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|puzzle: Grid| {
+    // Bounded, so hard puzzles do not pass for hangs
+    let config = SolverConfig::new().max_nodes(10_000);
+    let mut grid = puzzle.clone();
+
+    if grid.solve_with(&config).is_ok() {
+        assert!(puzzle.verify_solution(&grid).is_ok());
+    }
+
+    // Exports of any valid grid read back the same
+    assert_eq!(
+        Grid::from_compact_string(&puzzle.to_compact_string()).unwrap(),
+        puzzle
+    );
+    assert_eq!(Grid::from_json(&puzzle.to_json()).unwrap(), puzzle);
+});
@@ -0,0 +1,66 @@
+#ifndef BINERO_H
+#define BINERO_H
+
+/* Generated by cbindgen from src/ffi.rs, do not edit */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef enum BineroStatus {
+  BINERO_STATUS_OK = 0,
+  BINERO_STATUS_ERROR = 1,
+  BINERO_STATUS_PARSE = 2,
+  BINERO_STATUS_INVALID = 3,
+  BINERO_STATUS_NO_SOLUTION = 4,
+  BINERO_STATUS_LIMIT = 5,
+  BINERO_STATUS_MULTIPLE_SOLUTIONS = 6,
+} BineroStatus;
+
+typedef struct BineroGrid BineroGrid;
+
+// Parse a grid in the text format, to be released with `binero_free`.
+//
+// # Safety
+//
+// `text` must be a valid C string, and `grid` a valid pointer.
+enum BineroStatus binero_parse(const char *text, struct BineroGrid **grid);
+
+// Solve a grid in place, it is left untouched when there is no solution.
+//
+// # Safety
+//
+// `grid` must come from `binero_parse`, and not be freed yet.
+enum BineroStatus binero_solve(struct BineroGrid *grid);
+
+// The value of a cell: 0 or 1, or -1 when it is empty or out of the grid.
+//
+// # Safety
+//
+// `grid` must come from `binero_parse`, and not be freed yet.
+int8_t binero_get(const struct BineroGrid *grid, size_t line, size_t column);
+
+// The grid in the text format, to be released with `binero_free_string`.
+//
+// # Safety
+//
+// `grid` must come from `binero_parse`, and not be freed yet.
+char *binero_to_string(const struct BineroGrid *grid);
+
+// Release a grid, null pointers are ignored.
+//
+// # Safety
+//
+// `grid` must come from `binero_parse`, and not be freed yet.
+void binero_free(struct BineroGrid *grid);
+
+// Release a string, null pointers are ignored.
+//
+// # Safety
+//
+// `s` must come from `binero_to_string`, and not be freed yet.
+void binero_free_string(char *s);
+
+#endif  /* BINERO_H */
@@ -0,0 +1,108 @@
+use crate::cell::Cell;
+use crate::error::ParseError;
+use crate::grid::{Grid, GridCell};
+use crate::parse::ParseOptions;
+
+// Letters stand for runs of empty cells, from a for one to z for 26
+const MAX_RUN: usize = 26;
+
+impl Grid {
+    pub fn to_binarypuzzle(&self) -> String {
+        let mut output = if self.width() == self.height() {
+            format!("{}:", self.width())
+        } else {
+            format!("{}x{}:", self.width(), self.height())
+        };
+        let mut run = 0;
+
+        for cell in self.lines().flat_map(|i| self.line(i)) {
+            let Some(cell) = cell else {
+                run += 1;
+                continue;
+            };
+
+            push_run(&mut output, run);
+            run = 0;
+
+            output.push(match cell {
+                Cell::Zero => '0',
+                Cell::One => '1',
+            });
+        }
+
+        push_run(&mut output, run);
+        output
+    }
+
+    pub fn from_binarypuzzle(s: &str) -> Result<Grid, ParseError> {
+        let (header, data) = s
+            .trim()
+            .split_once(':')
+            .ok_or(ParseError::InvalidEncoding)?;
+
+        let (width, height) = match header.split_once('x') {
+            Some((width, height)) => (width, height),
+            None => (header, header),
+        };
+        let width: usize = width.parse().map_err(|_| ParseError::InvalidEncoding)?;
+        let height: usize = height.parse().map_err(|_| ParseError::InvalidEncoding)?;
+
+        // Runs of empty cells would take far more room than the code
+        ParseOptions::default().check_size(width, height)?;
+
+        let mut cells: Vec<GridCell> = Vec::new();
+
+        for c in data.chars() {
+            match c {
+                'a'..='z' => {
+                    let run = c as usize - 'a' as usize + 1;
+                    cells.extend(std::iter::repeat_n(None, run));
+                }
+                _ => cells.push(Some(Cell::try_from(c)?)),
+            }
+        }
+
+        if width.checked_mul(height) != Some(cells.len()) {
+            return Err(ParseError::InvalidEncoding);
+        }
+
+        Grid::from_cells(cells.chunks(width.max(1)).map(<[_]>::to_vec).collect())
+    }
+}
+
+fn push_run(output: &mut String, mut run: usize) {
+    while run > 0 {
+        let length = run.min(MAX_RUN);
+
+        output.push((b'a' + length as u8 - 1) as char);
+        run -= length;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let grid = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+        let grid = grid.unwrap();
+
+        assert_eq!(grid.to_binarypuzzle(), "4:11a0a0d0b1a0");
+        assert_eq!(Grid::from_binarypuzzle("4:11a0a0d0b1a0").unwrap(), grid);
+
+        let grid = Grid::parse(vec!["- - - - - - - -"; 6].into_iter()).unwrap();
+        assert_eq!(grid.to_binarypuzzle(), "8x6:zv");
+        assert_eq!(Grid::from_binarypuzzle("8x6:zv").unwrap(), grid);
+    }
+
+    #[test]
+    fn invalid_strings() {
+        assert!(Grid::from_binarypuzzle("").is_err());
+        assert!(Grid::from_binarypuzzle("11a0a0d0b1a0").is_err());
+        assert!(Grid::from_binarypuzzle("4:11a0a0d0b1a").is_err());
+        assert!(Grid::from_binarypuzzle("4:11a0a0d0b1a00").is_err());
+        assert!(Grid::from_binarypuzzle("4:11A0a0d0b1a0").is_err());
+        assert!(Grid::from_binarypuzzle("4x:11a0a0d0b1a0").is_err());
+    }
+}
@@ -0,0 +1,215 @@
+use alloc::vec::Vec;
+
+// One bit for each cell of a lane, the first cell being the lowest bit
+pub(crate) trait LaneBits: Clone + Default + Ord {
+    fn bit(&self, k: usize) -> bool;
+    fn set_bit(&mut self, k: usize, value: bool);
+    fn count_ones(&self) -> usize;
+    fn is_zero(&self) -> bool;
+    fn and(&self, other: &Self) -> Self;
+    fn or(&self, other: &Self) -> Self;
+    fn and_not(&self, other: &Self) -> Self;
+    // Moves every bit to a lower cell, dropping the first ones
+    fn shr(&self, n: usize) -> Self;
+    fn shl(&self, n: usize) -> Self;
+    // Only the bits of the first cells
+    fn truncate(&self, size: usize) -> Self;
+
+    fn count_below(&self, k: usize) -> usize {
+        self.truncate(k).count_ones()
+    }
+}
+
+// Lanes of fixed-size grids fit in a single word
+impl LaneBits for u64 {
+    fn bit(&self, k: usize) -> bool {
+        k < 64 && (self >> k) & 1 != 0
+    }
+
+    fn set_bit(&mut self, k: usize, value: bool) {
+        *self = (*self & !(1 << k)) | (u64::from(value) << k);
+    }
+
+    fn count_ones(&self) -> usize {
+        u64::count_ones(*self) as usize
+    }
+
+    fn is_zero(&self) -> bool {
+        *self == 0
+    }
+
+    fn and(&self, other: &Self) -> Self {
+        self & other
+    }
+
+    fn or(&self, other: &Self) -> Self {
+        self | other
+    }
+
+    fn and_not(&self, other: &Self) -> Self {
+        self & !other
+    }
+
+    fn shr(&self, n: usize) -> Self {
+        u32::try_from(n).map_or(0, |n| self.checked_shr(n).unwrap_or(0))
+    }
+
+    fn shl(&self, n: usize) -> Self {
+        u32::try_from(n).map_or(0, |n| self.checked_shl(n).unwrap_or(0))
+    }
+
+    fn truncate(&self, size: usize) -> Self {
+        self & u64::MAX.shr(64 - size.min(64))
+    }
+}
+
+// Lanes of any length: the first word is kept inline, so that lanes of up to
+// 64 cells never allocate, and the others follow without trailing zeros
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct BitSet {
+    low: u64,
+    high: Vec<u64>,
+}
+
+impl BitSet {
+    fn from_words<I>(words: I) -> Self
+    where
+        I: IntoIterator<Item = u64>,
+    {
+        let mut words = words.into_iter();
+        let mut bits = Self {
+            low: words.next().unwrap_or(0),
+            high: words.collect(),
+        };
+
+        bits.trim();
+        bits
+    }
+
+    fn word(&self, w: usize) -> u64 {
+        match w {
+            0 => self.low,
+            _ => self.high.get(w - 1).copied().unwrap_or(0),
+        }
+    }
+
+    fn words(&self) -> usize {
+        1 + self.high.len()
+    }
+
+    fn trim(&mut self) {
+        while self.high.last() == Some(&0) {
+            self.high.pop();
+        }
+    }
+
+    fn zip<F>(&self, other: &Self, f: F) -> Self
+    where
+        F: Fn(u64, u64) -> u64,
+    {
+        let words = self.words().max(other.words());
+        Self::from_words((0..words).map(|w| f(self.word(w), other.word(w))))
+    }
+}
+
+impl LaneBits for BitSet {
+    fn bit(&self, k: usize) -> bool {
+        self.word(k / 64).bit(k % 64)
+    }
+
+    fn set_bit(&mut self, k: usize, value: bool) {
+        let w = k / 64;
+
+        if w == 0 {
+            self.low.set_bit(k, value);
+            return;
+        }
+
+        if self.high.len() < w {
+            self.high.resize(w, 0);
+        }
+
+        self.high[w - 1].set_bit(k % 64, value);
+        self.trim();
+    }
+
+    fn count_ones(&self) -> usize {
+        (0..self.words())
+            .map(|w| self.word(w).count_ones() as usize)
+            .sum()
+    }
+
+    fn is_zero(&self) -> bool {
+        self.low == 0 && self.high.is_empty()
+    }
+
+    fn and(&self, other: &Self) -> Self {
+        self.zip(other, |a, b| a & b)
+    }
+
+    fn or(&self, other: &Self) -> Self {
+        self.zip(other, |a, b| a | b)
+    }
+
+    fn and_not(&self, other: &Self) -> Self {
+        self.zip(other, |a, b| a & !b)
+    }
+
+    fn shr(&self, n: usize) -> Self {
+        let (skip, shift) = (n / 64, n % 64);
+        let words = self.words().saturating_sub(skip);
+
+        Self::from_words((0..words).map(|w| {
+            let (low, high) = (self.word(w + skip), self.word(w + skip + 1));
+            low.shr(shift) | high.shl(64 - shift)
+        }))
+    }
+
+    fn shl(&self, n: usize) -> Self {
+        let (skip, shift) = (n / 64, n % 64);
+        let words = self.words() + skip + 1;
+
+        Self::from_words((0..words).map(|w| {
+            let word = |w: Option<usize>| w.map_or(0, |w| self.word(w));
+            let (low, high) = (word(w.checked_sub(skip + 1)), word(w.checked_sub(skip)));
+            high.shl(shift) | low.shr(64 - shift)
+        }))
+    }
+
+    fn truncate(&self, size: usize) -> Self {
+        let words = size.div_ceil(64);
+
+        Self::from_words((0..words).map(|w| self.word(w).truncate(size - 64 * w)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_lanes() {
+        let mut bits = BitSet::default();
+        bits.set_bit(3, true);
+        bits.set_bit(70, true);
+        bits.set_bit(130, true);
+        assert_eq!(bits.count_ones(), 3);
+        assert!(bits.bit(70) && !bits.bit(71));
+
+        // Shifts carry bits across words
+        let shifted = bits.shl(61);
+        assert!(shifted.bit(64) && shifted.bit(131) && shifted.bit(191));
+        assert_eq!(shifted.shr(61), bits);
+        assert_eq!(bits.shr(68).count_ones(), 2);
+        assert!(bits.shr(68).bit(2));
+
+        assert_eq!(bits.truncate(70).count_ones(), 1);
+        assert_eq!(bits.count_below(131), 3);
+
+        // Clearing the last bits gives the same value as never setting them
+        bits.set_bit(130, false);
+        bits.set_bit(70, false);
+        assert_eq!(bits, BitSet::from_words([8]));
+        assert!(bits.and_not(&BitSet::from_words([8, 0, 1])).is_zero());
+    }
+}
@@ -0,0 +1,135 @@
+use core::ops;
+
+use crate::cell::Cell;
+use crate::error::{Rule, Violation};
+use crate::grid::{Grid, GridCell, Histogram};
+use crate::index::{Index, Lane};
+use crate::rules::Rules;
+use crate::sign::Sign;
+
+// What the solver needs from a grid, whatever the way its cells are stored
+pub(crate) trait Board: Clone + Send + Sync + ops::Index<Index, Output = GridCell> {
+    fn width(&self) -> usize;
+
+    fn height(&self) -> usize;
+
+    fn line_count(&self, i: usize) -> Histogram;
+
+    fn column_count(&self, j: usize) -> Histogram;
+
+    fn is_valid(&self) -> Result<(), Violation>;
+
+    fn check_move(&self, idx: Index, cell: Cell) -> Result<(), Rule>;
+
+    // Only grids follow other rules than the standard ones
+    fn rules(&self) -> Rules {
+        Rules::default()
+    }
+
+    // Only grids read from Binairo+ puzzles have signs
+    fn sign(&self, _a: Index, _b: Index) -> Option<Sign> {
+        None
+    }
+
+    // Only grids can be split into regions
+    fn region_cells(&self, _idx: Index) -> &[Index] {
+        &[]
+    }
+
+    // Only grids can have holes, or margin clues setting the counts of lanes
+    fn max_count(&self, lane: Lane, cell: Cell) -> usize {
+        let size = match lane {
+            Lane::Line(_) => self.width(),
+            Lane::Column(_) => self.height(),
+        };
+
+        self.rules().max_count(size, cell)
+    }
+
+    fn target(&self, _lane: Lane) -> Option<usize> {
+        None
+    }
+
+    fn is_hole(&self, _idx: Index) -> bool {
+        false
+    }
+
+    // Tell whether the cell changed
+    fn set(&mut self, idx: Index, cell: GridCell) -> bool;
+
+    fn to_grid(&self) -> Grid;
+
+    fn lines(&self) -> ops::Range<usize> {
+        0..self.height()
+    }
+
+    fn columns(&self) -> ops::Range<usize> {
+        0..self.width()
+    }
+
+    fn empty_cells(&self) -> impl Iterator<Item = Index> + '_ {
+        self.lines().flat_map(move |i| {
+            self.columns()
+                .map(move |j| Index(i, j))
+                .filter(move |idx| self[*idx].is_none() && !self.is_hole(*idx))
+        })
+    }
+}
+
+impl Board for Grid {
+    fn width(&self) -> usize {
+        Grid::width(self)
+    }
+
+    fn height(&self) -> usize {
+        Grid::height(self)
+    }
+
+    fn line_count(&self, i: usize) -> Histogram {
+        self.line_counts[i]
+    }
+
+    fn column_count(&self, j: usize) -> Histogram {
+        self.column_counts[j]
+    }
+
+    fn is_valid(&self) -> Result<(), Violation> {
+        Grid::is_valid(self)
+    }
+
+    fn check_move(&self, idx: Index, cell: Cell) -> Result<(), Rule> {
+        Grid::check_move(self, idx, cell)
+    }
+
+    fn rules(&self) -> Rules {
+        Grid::rules(self)
+    }
+
+    fn sign(&self, a: Index, b: Index) -> Option<Sign> {
+        Grid::sign(self, a, b)
+    }
+
+    fn region_cells(&self, idx: Index) -> &[Index] {
+        Grid::region_cells(self, idx)
+    }
+
+    fn max_count(&self, lane: Lane, cell: Cell) -> usize {
+        Grid::lane_max(self, lane, cell)
+    }
+
+    fn target(&self, lane: Lane) -> Option<usize> {
+        Grid::target(self, lane)
+    }
+
+    fn is_hole(&self, idx: Index) -> bool {
+        Grid::is_hole(self, idx)
+    }
+
+    fn set(&mut self, idx: Index, cell: GridCell) -> bool {
+        Grid::set(self, idx, cell)
+    }
+
+    fn to_grid(&self) -> Grid {
+        self.clone()
+    }
+}
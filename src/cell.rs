@@ -1,7 +1,7 @@
-use std::fmt;
-use std::ops;
+use core::fmt;
+use core::ops;
 
-use crate::error::GridError;
+use crate::error::ParseError;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Cell {
@@ -9,9 +9,73 @@ pub enum Cell {
     One,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CellState {
+    Given,
+    Derived,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct CellSet(u8);
+
 impl Cell {
     pub fn iter() -> impl Iterator<Item = Cell> {
-        vec![Self::Zero, Self::One].into_iter()
+        [Self::Zero, Self::One].into_iter()
+    }
+}
+
+impl CellSet {
+    pub fn all() -> Self {
+        Cell::iter().collect()
+    }
+
+    pub fn contains(&self, cell: Cell) -> bool {
+        self.0 & Self::bit(cell) != 0
+    }
+
+    pub fn insert(&mut self, cell: Cell) {
+        self.0 |= Self::bit(cell);
+    }
+
+    pub fn remove(&mut self, cell: Cell) {
+        self.0 &= !Self::bit(cell);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn single(&self) -> Option<Cell> {
+        let mut cells = self.iter();
+
+        cells.next().filter(|_| cells.next().is_none())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Cell> + '_ {
+        Cell::iter().filter(|cell| self.contains(*cell))
+    }
+
+    fn bit(cell: Cell) -> u8 {
+        1 << (cell as u8)
+    }
+}
+
+impl FromIterator<Cell> for CellSet {
+    fn from_iter<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = Cell>,
+    {
+        let mut set = Self::default();
+
+        for cell in iter {
+            set.insert(cell);
+        }
+
+        set
     }
 }
 
@@ -35,13 +99,13 @@ impl ops::Not for Cell {
 }
 
 impl TryFrom<char> for Cell {
-    type Error = GridError;
+    type Error = ParseError;
 
     fn try_from(c: char) -> Result<Self, Self::Error> {
         match c {
             '0' => Ok(Self::Zero),
             '1' => Ok(Self::One),
-            _ => Err(GridError::InvalidChar(c)),
+            _ => Err(ParseError::invalid_char(c)),
         }
     }
 }
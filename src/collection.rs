@@ -0,0 +1,93 @@
+use crate::error::ParseError;
+use crate::grid::Grid;
+use crate::parse::{self, ParseOptions};
+
+impl Grid {
+    // Several grids separated by blank lines or `== name ==` headers, with
+    // the name given by their header if any. Each grid is parsed on its own,
+    // so one mistake does not hide the other grids.
+    pub fn parse_collection<I, S>(
+        lines: I,
+        options: &ParseOptions,
+    ) -> Vec<(Option<String>, Result<Grid, ParseError>)>
+    where
+        I: Iterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut chunks: Vec<(Option<String>, Vec<String>)> = vec![(None, Vec::new())];
+
+        for line in parse::split_lines(lines) {
+            let line = line.as_str();
+
+            if let Some(name) = header(line) {
+                chunks.push((Some(name.to_string()), Vec::new()));
+            } else if line.trim().is_empty() {
+                chunks.push((None, Vec::new()));
+            } else if let Some((_, chunk)) = chunks.last_mut() {
+                chunk.push(line.to_string());
+            }
+        }
+
+        let grids: Vec<_> = chunks
+            .into_iter()
+            // Blank lines in a row, or comments alone, do not make a grid
+            .filter(|(name, chunk)| {
+                name.is_some()
+                    || chunk
+                        .iter()
+                        .any(|line| !options.strip_comment(line).trim().is_empty())
+            })
+            .map(|(name, chunk)| (name, Grid::parse_with(chunk.iter(), options)))
+            .collect();
+
+        // Without any grid, the collection is as wrong as an empty grid
+        if grids.is_empty() {
+            return vec![(None, Err(ParseError::EmptyGrid))];
+        }
+
+        grids
+    }
+}
+
+fn header(line: &str) -> Option<&str> {
+    let name = line.trim().strip_prefix("==")?.strip_suffix("==")?;
+    Some(name.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collection() {
+        let input = vec![
+            "# two puzzles",
+            "",
+            "1 1 - 0",
+            "- 0 - -",
+            "- - 0 -",
+            "- 1 - 0",
+            "",
+            "",
+            "== second ==",
+            "- -",
+            "# comment",
+            "0 -",
+            "== empty ==",
+        ];
+
+        let grids = Grid::parse_collection(input.iter(), &ParseOptions::default());
+        assert_eq!(grids.len(), 3);
+        assert_eq!(grids[0].0, None);
+        assert_eq!(grids[0].1.as_ref().unwrap().width(), 4);
+        assert_eq!(grids[1].0.as_deref(), Some("second"));
+        assert_eq!(grids[1].1.as_ref().unwrap().width(), 2);
+
+        // Named grids must not be empty
+        assert_eq!(grids[2].0.as_deref(), Some("empty"));
+        assert!(matches!(grids[2].1, Err(ParseError::EmptyGrid)));
+
+        let grids = Grid::parse_collection(["", "# nothing"].iter(), &ParseOptions::default());
+        assert!(matches!(grids[..], [(None, Err(ParseError::EmptyGrid))]));
+    }
+}
@@ -0,0 +1,163 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::cell::Cell;
+use crate::error::ParseError;
+use crate::grid::{Grid, GridCell};
+use crate::index::Index;
+use crate::parse::ParseOptions;
+
+// URL-safe base64 alphabet, without padding
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+// Three cells in base 3 fit in the first 27 symbols, the remaining ones
+// encode runs of fully empty groups
+const GROUP_SIZE: usize = 3;
+const GROUP_VALUES: usize = 27;
+const MIN_RUN: usize = 2;
+const MAX_RUN: usize = ALPHABET.len() - GROUP_VALUES + MIN_RUN - 1;
+
+impl Grid {
+    pub fn to_compact_string(&self) -> String {
+        let cells: Vec<_> = (0..self.height())
+            .flat_map(|i| (0..self.width()).map(move |j| Index(i, j)))
+            .map(|idx| match self[idx] {
+                None => 0,
+                Some(Cell::Zero) => 1,
+                Some(Cell::One) => 2,
+            })
+            .collect();
+
+        // Pack cells by groups, padding the last one with empty cells
+        let groups: Vec<usize> = cells
+            .chunks(GROUP_SIZE)
+            .map(|chunk| (0..GROUP_SIZE).fold(0, |acc, k| acc * 3 + chunk.get(k).unwrap_or(&0)))
+            .collect();
+
+        let mut output = format!("{}x{}.", self.width(), self.height());
+        let mut k = 0;
+
+        while k < groups.len() {
+            // Count consecutive empty groups
+            let run = groups[k..]
+                .iter()
+                .take(MAX_RUN)
+                .take_while(|group| **group == 0)
+                .count();
+
+            if run >= MIN_RUN {
+                output.push(ALPHABET[GROUP_VALUES + run - MIN_RUN] as char);
+                k += run;
+            } else {
+                output.push(ALPHABET[groups[k]] as char);
+                k += 1;
+            }
+        }
+
+        output
+    }
+
+    pub fn from_compact_string(s: &str) -> Result<Grid, ParseError> {
+        let (header, data) = s
+            .trim()
+            .split_once('.')
+            .ok_or(ParseError::InvalidEncoding)?;
+        let (width, height) = header.split_once('x').ok_or(ParseError::InvalidEncoding)?;
+
+        let width: usize = width.parse().map_err(|_| ParseError::InvalidEncoding)?;
+        let height: usize = height.parse().map_err(|_| ParseError::InvalidEncoding)?;
+
+        // Runs of empty cells would take far more room than the code
+        ParseOptions::default().check_size(width, height)?;
+        let size = width
+            .checked_mul(height)
+            .ok_or(ParseError::InvalidEncoding)?;
+
+        // Unpack groups of cells
+        let mut cells = Vec::new();
+
+        for c in data.bytes() {
+            let value = ALPHABET
+                .iter()
+                .position(|x| *x == c)
+                .ok_or(ParseError::invalid_char(c as char))?;
+
+            if value < GROUP_VALUES {
+                cells.extend(
+                    (0..GROUP_SIZE)
+                        .rev()
+                        .map(|k| value / 3usize.pow(k as u32) % 3),
+                );
+            } else {
+                let run = value - GROUP_VALUES + MIN_RUN;
+                cells.extend(core::iter::repeat_n(0, run * GROUP_SIZE));
+            }
+
+            if cells.len() > size + GROUP_SIZE {
+                return Err(ParseError::InvalidEncoding);
+            }
+        }
+
+        // Only padding is allowed after the last cell
+        if cells.len() < size || cells[size..].iter().any(|value| *value != 0) {
+            return Err(ParseError::InvalidEncoding);
+        }
+
+        let cells: Vec<GridCell> = cells[..size]
+            .iter()
+            .map(|value| match value {
+                1 => Some(Cell::Zero),
+                2 => Some(Cell::One),
+                _ => None,
+            })
+            .collect();
+
+        Grid::from_cells(cells.chunks(width.max(1)).map(<[_]>::to_vec).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let input = vec![
+            "- 1 1 - 1 - - - - - - - 1 -",
+            "- - - - - - 1 - - - - 0 - -",
+            "1 - - - 0 0 - 0 0 - 1 - - -",
+            "- 0 0 - - - - - - - - - - 1",
+            "- 0 - - - 0 - - 0 - - - - -",
+            "- - - - - 0 - - - - 1 1 - -",
+            "0 - - - - - - - - - 1 - - -",
+            "- 0 - - 1 - 0 - 0 - - 0 - -",
+            "1 - - - - - - - 0 - - - 1 -",
+            "- - 1 1 - - - - - 1 - - - -",
+            "- 0 - - - - - - - - - - - 1",
+            "1 - - 0 - 1 - - 0 - - - - 1",
+            "- - - - - - 0 - 0 0 - - - -",
+            "- - - - - 1 - - - - - 1 - -",
+        ];
+
+        let grid = Grid::parse(input.into_iter()).unwrap();
+        let encoded = grid.to_compact_string();
+
+        assert!(encoded.starts_with("14x14."));
+        assert!(encoded.len() < 14 * 14 / 2);
+        assert_eq!(Grid::from_compact_string(&encoded).unwrap(), grid);
+    }
+
+    #[test]
+    fn invalid_strings() {
+        assert!(Grid::from_compact_string("").is_err());
+        assert!(Grid::from_compact_string("4x4").is_err());
+        assert!(Grid::from_compact_string("4x4.AAAA").is_err());
+        assert!(Grid::from_compact_string("4x4.!").is_err());
+        assert!(Grid::from_compact_string("3x4.b").is_err());
+        assert!(matches!(
+            Grid::from_compact_string("100000x100000.z"),
+            Err(ParseError::TooLarge { .. })
+        ));
+    }
+}
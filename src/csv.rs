@@ -0,0 +1,95 @@
+use crate::cell::Cell;
+use crate::error::ParseError;
+use crate::grid::{Grid, GridCell};
+
+impl Grid {
+    pub fn parse_csv<I, S>(lines: I) -> Result<Grid, ParseError>
+    where
+        I: Iterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut cells: Vec<Vec<GridCell>> = Vec::new();
+
+        for (n, line) in lines.enumerate() {
+            let line = line.as_ref().trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            // Spreadsheets use semicolons where the comma is a decimal separator
+            let separator = if line.contains(';') { ';' } else { ',' };
+
+            let vec = line
+                .split(separator)
+                .map(|field| {
+                    let mut chars = field.trim().chars();
+
+                    match (chars.next(), chars.next()) {
+                        (None | Some('?'), None) => Ok(None),
+                        (Some(c), None) => Cell::try_from(c).map(Some),
+                        _ => Err(ParseError::InvalidEncoding),
+                    }
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if let Some(first) = cells.first() {
+                if vec.len() != first.len() {
+                    return Err(ParseError::WidthMismatch {
+                        line: n + 1,
+                        expected: first.len(),
+                        found: vec.len(),
+                    });
+                }
+            }
+
+            cells.push(vec);
+        }
+
+        Self::from_cells(cells)
+    }
+
+    pub fn to_csv(&self) -> String {
+        // Empty cells are empty fields
+        self.lines()
+            .map(|i| {
+                self.line(i)
+                    .map(|cell| match cell {
+                        Some(Cell::Zero) => "0",
+                        Some(Cell::One) => "1",
+                        None => "",
+                    })
+                    .collect::<Vec<_>>()
+                    .join(",")
+            })
+            .map(|line| line + "\n")
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export() {
+        let grid = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+
+        assert_eq!(grid.unwrap().to_csv(), "1,1,,0\n,0,,\n,,0,\n,1,,0\n");
+    }
+
+    #[test]
+    fn import() {
+        let grid = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+        let grid = grid.unwrap();
+
+        assert_eq!(Grid::parse_csv(grid.to_csv().lines()).unwrap(), grid);
+
+        let input = vec!["1;1;?;0", " ; 0 ; ; ", "", "?;?;0;?", ";1;;0"];
+        assert_eq!(Grid::parse_csv(input.into_iter()).unwrap(), grid);
+
+        assert!(Grid::parse_csv(vec!["1,1,,0", ",0,"].into_iter()).is_err());
+        assert!(Grid::parse_csv(vec!["1,x", "0,1"].into_iter()).is_err());
+        assert!(Grid::parse_csv(vec!["1,10", "0,1"].into_iter()).is_err());
+    }
+}
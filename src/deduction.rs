@@ -0,0 +1,183 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::cell::Cell;
+use crate::error::SolveError;
+use crate::grid::Grid;
+use crate::index::{Index, Lane};
+use crate::solver::{Solver, SolverConfig};
+use crate::technique::Technique;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Deduction {
+    pub(crate) index: Index,
+    pub(crate) value: Cell,
+    pub(crate) technique: Technique,
+    pub(crate) lane: Lane,
+    pub(crate) premises: Vec<Index>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Step {
+    Deduction(Deduction),
+    Guess(Index, Cell),
+    Backtrack,
+}
+
+impl Deduction {
+    pub fn index(&self) -> Index {
+        self.index
+    }
+
+    pub fn value(&self) -> Cell {
+        self.value
+    }
+
+    pub fn technique(&self) -> Technique {
+        self.technique
+    }
+
+    // Lane where the technique applies
+    pub fn lane(&self) -> Lane {
+        self.lane
+    }
+
+    // Filled cells the deduction relies on
+    pub fn premises(&self) -> &[Index] {
+        &self.premises
+    }
+
+    // Sentence giving the value of the cell and why, such as
+    // "r1c3 = 0 because r1c1 and r1c2 are both 1"
+    pub fn explain(&self) -> String {
+        let value = self.value;
+        let reason = match (self.technique, &self.premises[..]) {
+            (Technique::Adjacency, [first, second]) => format!(
+                "{} and {} are both {}",
+                cell_name(*first),
+                cell_name(*second),
+                !value
+            ),
+            (Technique::Adjacency, [others @ .., last]) if !others.is_empty() => {
+                let others: Vec<_> = others.iter().map(|idx| cell_name(*idx)).collect();
+
+                format!(
+                    "{} and {} are all {}",
+                    others.join(", "),
+                    cell_name(*last),
+                    !value
+                )
+            }
+            (Technique::Balance, _) => {
+                format!("{} has all its {}s", lane_name(self.lane), !value)
+            }
+            (Technique::Uniqueness, [first, ..]) => {
+                let other = match self.lane {
+                    Lane::Line(_) => Lane::Line(first.0),
+                    Lane::Column(_) => Lane::Column(first.1),
+                };
+
+                format!("{} would repeat {}", lane_name(self.lane), lane_name(other))
+            }
+            (Technique::UniqueCompletion, [first, ..]) => {
+                let other = match self.lane {
+                    Lane::Line(_) => Lane::Line(first.0),
+                    Lane::Column(_) => Lane::Column(first.1),
+                };
+
+                format!(
+                    "{} could otherwise only be completed as a repeat of {}",
+                    lane_name(self.lane),
+                    lane_name(other)
+                )
+            }
+            (Technique::Crossing, _) => {
+                format!(
+                    "the lanes crossing {} leave it no other completion",
+                    lane_name(self.lane)
+                )
+            }
+            (Technique::Lookahead, _) => {
+                format!("a {} there leads to a contradiction", !value)
+            }
+            (Technique::DeadlyPattern, [first, second, third]) => format!(
+                "a {} there would let it swap values with {}, {} and {}, which would \
+                 give the puzzle a second solution",
+                !value,
+                cell_name(*first),
+                cell_name(*second),
+                cell_name(*third)
+            ),
+            (Technique::Sign, [other]) => {
+                format!("of the sign between it and {}", cell_name(*other))
+            }
+            _ => format!(
+                "the remaining {}s of {} cannot go there",
+                !value,
+                lane_name(self.lane)
+            ),
+        };
+
+        format!("{} = {} because {}", cell_name(self.index), value, reason)
+    }
+}
+
+fn cell_name(idx: Index) -> String {
+    format!("r{}c{}", idx.0 + 1, idx.1 + 1)
+}
+
+fn lane_name(lane: Lane) -> String {
+    match lane {
+        Lane::Line(i) => format!("line {}", i + 1),
+        Lane::Column(j) => format!("column {}", j + 1),
+    }
+}
+
+impl Grid {
+    pub fn explain(&self) -> Result<Vec<Step>, SolveError> {
+        self.is_valid().map_err(SolveError::InvalidGrid)?;
+
+        let config = SolverConfig::default();
+        let mut grid = self.clone();
+        let mut solver = Solver::new(&mut grid, &config).with_log();
+
+        solver.search()?;
+        Ok(solver.into_log())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explanations() {
+        let grid = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+        let steps = grid.unwrap().explain().unwrap();
+
+        assert_eq!(steps.len(), 9);
+        assert_eq!(
+            steps[0],
+            Step::Deduction(Deduction {
+                index: Index(0, 2),
+                value: Cell::Zero,
+                technique: Technique::Adjacency,
+                lane: Lane::Line(0),
+                premises: vec![Index(0, 0), Index(0, 1)],
+            })
+        );
+
+        let Step::Deduction(deduction) = &steps[0] else {
+            unreachable!()
+        };
+        assert_eq!(
+            deduction.explain(),
+            "r1c3 = 0 because r1c1 and r1c2 are both 1"
+        );
+
+        // Nothing can be deduced on an empty grid
+        let grid = Grid::parse(vec!["- - - -"; 4].into_iter()).unwrap();
+        assert!(matches!(grid.explain().unwrap()[0], Step::Guess(..)));
+    }
+}
@@ -0,0 +1,73 @@
+use alloc::vec::Vec;
+
+use crate::cell::Cell;
+use crate::error::VerificationError;
+use crate::grid::Grid;
+use crate::index::Index;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Difference {
+    // Both grids are filled, with different values
+    Mismatch(Index, Cell, Cell),
+    // Only the first grid is filled
+    Removed(Index, Cell),
+    // Only the second grid is filled
+    Added(Index, Cell),
+}
+
+impl Difference {
+    pub fn index(&self) -> Index {
+        match self {
+            Self::Mismatch(idx, ..) | Self::Removed(idx, _) | Self::Added(idx, _) => *idx,
+        }
+    }
+}
+
+impl Grid {
+    pub fn diff(&self, other: &Grid) -> Result<Vec<Difference>, VerificationError> {
+        if other.width() != self.width() || other.height() != self.height() {
+            return Err(VerificationError::DimensionMismatch);
+        }
+
+        let differences = self
+            .lines()
+            .flat_map(|i| self.columns().map(move |j| Index(i, j)))
+            .filter_map(|idx| match (self[idx], other[idx]) {
+                (Some(old), Some(new)) if old != new => Some(Difference::Mismatch(idx, old, new)),
+                (Some(old), None) => Some(Difference::Removed(idx, old)),
+                (None, Some(new)) => Some(Difference::Added(idx, new)),
+                _ => None,
+            })
+            .collect();
+
+        Ok(differences)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn differences() {
+        let first = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+        let second = Grid::parse(vec!["1 1 0 0", "- 0 - -", "- - 1 -", "- - - 0"].into_iter());
+        let (first, second) = (first.unwrap(), second.unwrap());
+
+        assert_eq!(first.diff(&first).unwrap(), vec![]);
+        assert_eq!(
+            first.diff(&second).unwrap(),
+            vec![
+                Difference::Added(Index(0, 2), Cell::Zero),
+                Difference::Mismatch(Index(2, 2), Cell::Zero, Cell::One),
+                Difference::Removed(Index(3, 1), Cell::One),
+            ]
+        );
+
+        let other = Grid::parse(vec!["- - - - - -"; 4].into_iter()).unwrap();
+        assert!(matches!(
+            first.diff(&other),
+            Err(VerificationError::DimensionMismatch)
+        ));
+    }
+}
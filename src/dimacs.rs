@@ -0,0 +1,297 @@
+use std::fmt::Write;
+
+use crate::cell::Cell;
+use crate::error::Rule;
+use crate::grid::Grid;
+use crate::index::Lane;
+use crate::sign::Sign;
+
+// Clauses over numbered variables, the way DIMACS writes them
+#[derive(Default)]
+struct Cnf {
+    variables: usize,
+    clauses: Vec<Vec<isize>>,
+}
+
+impl Cnf {
+    fn variable(&mut self) -> isize {
+        self.variables += 1;
+        self.variables as isize
+    }
+
+    // Sequential counter, with one auxiliary variable per literal and count
+    fn at_most(&mut self, literals: &[isize], k: usize) {
+        let Some((&last, literals)) = literals.split_last() else {
+            return;
+        };
+
+        if k == 0 {
+            self.clauses.push(vec![-last]);
+            self.clauses.extend(literals.iter().map(|&x| vec![-x]));
+            return;
+        }
+
+        // counts[j] is true when more than j of the literals so far are true
+        let mut counts: Vec<isize> = Vec::new();
+
+        for &x in literals {
+            let next: Vec<isize> = (0..k).map(|_| self.variable()).collect();
+
+            self.clauses.push(vec![-x, next[0]]);
+            for (j, &count) in next.iter().enumerate() {
+                if let Some(&previous) = counts.get(j) {
+                    self.clauses.push(vec![-previous, count]);
+                }
+
+                match j.checked_sub(1).map(|j| counts.get(j)) {
+                    Some(Some(&previous)) => self.clauses.push(vec![-x, -previous, count]),
+                    Some(None) => self.clauses.push(vec![-count]),
+                    None => {}
+                }
+            }
+            if let Some(&count) = counts.get(k - 1) {
+                self.clauses.push(vec![-x, -count]);
+            }
+
+            counts = next;
+        }
+
+        if let Some(&count) = counts.get(k - 1) {
+            self.clauses.push(vec![-last, -count]);
+        }
+    }
+
+    // At least one position where both lanes differ
+    fn distinct(&mut self, first: &[isize], second: &[isize]) {
+        let mut differences = Vec::new();
+
+        for (&a, &b) in first.iter().zip(second) {
+            let d = self.variable();
+
+            self.clauses.push(vec![-d, a, b]);
+            self.clauses.push(vec![-d, -a, -b]);
+            differences.push(d);
+        }
+
+        self.clauses.push(differences);
+    }
+}
+
+impl Grid {
+    // Each cell is a variable, true for a one, numbered line by line from 1
+    pub fn to_dimacs(&self) -> String {
+        let (width, height) = (self.width(), self.height());
+        let mut cnf = Cnf::default();
+
+        let cells: Vec<Vec<isize>> = self
+            .lines()
+            .map(|_| self.columns().map(|_| cnf.variable()).collect())
+            .collect();
+        let columns: Vec<Vec<isize>> = self
+            .columns()
+            .map(|j| cells.iter().map(|line| line[j]).collect())
+            .collect();
+
+        for (idx, cell) in self.givens() {
+            let x = cells[idx.0][idx.1];
+
+            cnf.clauses.push(match cell {
+                Cell::Zero => vec![-x],
+                Cell::One => vec![x],
+            });
+        }
+
+        // Holes are left false
+        let holes: Vec<isize> = self.holes().map(|idx| cells[idx.0][idx.1]).collect();
+
+        for x in &holes {
+            cnf.clauses.push(vec![-x]);
+        }
+
+        for (a, b, sign) in self.signs() {
+            let (x, y) = (cells[a.0][a.1], cells[b.0][b.1]);
+
+            match sign {
+                Sign::Equal => cnf.clauses.extend([vec![x, -y], vec![-x, y]]),
+                Sign::Different => cnf.clauses.extend([vec![x, y], vec![-x, -y]]),
+            }
+        }
+
+        let rules = self.rules();
+        let diagonal_rules = rules.diagonal_rules();
+        let diagonals: Vec<Vec<isize>> = rules
+            .diagonal_cells(width, height)
+            .iter()
+            .map(|diagonal| diagonal.iter().map(|idx| cells[idx.0][idx.1]).collect())
+            .collect();
+
+        // Lines and columns count their cells as the grid does, margin clues
+        // included
+        let lines = cells.iter().zip(self.lines().map(Lane::Line));
+        let lanes = lines.chain(columns.iter().zip(self.columns().map(Lane::Column)));
+        let lanes = lanes.map(|(lane, k)| (lane, rules, Some(k)));
+        let diagonals = diagonals.iter().map(|lane| (lane, diagonal_rules, None));
+
+        for (lane, rules, k) in lanes.chain(diagonals) {
+            // Runs go on across holes, which the counts leave out too
+            let lane: Vec<isize> = lane
+                .iter()
+                .copied()
+                .filter(|x| !holes.contains(x))
+                .collect();
+
+            // Cyclic lanes also have the runs going over their end
+            let limit = rules.run_limit();
+            let starts = if !rules.enforces(Rule::Adjacency) {
+                0
+            } else if rules.wraps(lane.len()) {
+                lane.len()
+            } else {
+                lane.len().saturating_sub(limit)
+            };
+
+            for k in 0..starts {
+                let run: Vec<isize> = (0..=limit).map(|m| lane[(k + m) % lane.len()]).collect();
+
+                cnf.clauses.push(run.clone());
+                cnf.clauses.push(run.iter().map(|x| -x).collect());
+            }
+
+            // As many ones as zeros, or as the margin clue asks for
+            let target = k.and_then(|k| self.target(k));
+
+            if !rules.enforces(Rule::Balance) && target.is_none() {
+                continue;
+            }
+
+            let max_count = |cell| match k {
+                Some(k) => self.lane_max(k, cell),
+                None => rules.max_count(lane.len(), cell),
+            };
+
+            let negated: Vec<isize> = lane.iter().map(|x| -x).collect();
+            cnf.at_most(&lane, max_count(Cell::One));
+            cnf.at_most(&negated, max_count(Cell::Zero));
+        }
+
+        // Regions hold their share of each value, like lanes
+        for (_, region) in self.regions().filter(|_| rules.enforces(Rule::Balance)) {
+            let region: Vec<isize> = region
+                .iter()
+                .filter(|idx| !self.is_hole(**idx))
+                .map(|idx| cells[idx.0][idx.1])
+                .collect();
+            let negated: Vec<isize> = region.iter().map(|x| -x).collect();
+
+            cnf.at_most(&region, rules.max_count(region.len(), Cell::One));
+            cnf.at_most(&negated, rules.max_count(region.len(), Cell::Zero));
+        }
+
+        let unique = [
+            (&cells, rules.is_unique(Lane::Line(0))),
+            (&columns, rules.is_unique(Lane::Column(0))),
+        ];
+
+        for lanes in unique
+            .into_iter()
+            .filter_map(|(lanes, unique)| unique.then_some(lanes))
+        {
+            for (k, first) in lanes.iter().enumerate() {
+                for second in &lanes[k + 1..] {
+                    cnf.distinct(first, second);
+                }
+            }
+        }
+
+        let mut dimacs = String::new();
+
+        // Writing to a string cannot fail
+        let _ = writeln!(dimacs, "c binero {}x{}", width, height);
+        let _ = writeln!(dimacs, "p cnf {} {}", cnf.variables, cnf.clauses.len());
+        for clause in &cnf.clauses {
+            for literal in clause {
+                let _ = write!(dimacs, "{} ", literal);
+            }
+            dimacs.push_str("0\n");
+        }
+
+        dimacs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The cells of all assignments satisfying the clauses
+    fn models(dimacs: &str, cells: usize) -> Vec<Vec<bool>> {
+        let mut lines = dimacs.lines().filter(|line| !line.starts_with('c'));
+        let header: Vec<&str> = lines.next().unwrap().split_whitespace().collect();
+        let variables: usize = header[2].parse().unwrap();
+
+        let clauses: Vec<Vec<isize>> = lines
+            .map(|line| {
+                let literals = line.split_whitespace().map(|x| x.parse().unwrap());
+                literals.filter(|x| *x != 0).collect()
+            })
+            .collect();
+        assert_eq!(clauses.len(), header[3].parse::<usize>().unwrap());
+
+        let mut models: Vec<Vec<bool>> = (0..1u64 << variables)
+            .filter(|bits| {
+                clauses.iter().all(|clause| {
+                    clause.iter().any(|&x| {
+                        let value = bits >> (x.unsigned_abs() - 1) & 1 == 1;
+                        value == (x > 0)
+                    })
+                })
+            })
+            .map(|bits| (0..cells).map(|k| bits >> k & 1 == 1).collect())
+            .collect();
+
+        models.sort();
+        models.dedup();
+        models
+    }
+
+    #[test]
+    fn at_most() {
+        for n in 1..=4 {
+            for k in 0..=n {
+                let mut cnf = Cnf::default();
+                let literals: Vec<isize> = (0..n).map(|_| cnf.variable()).collect();
+                cnf.at_most(&literals, k);
+
+                let mut dimacs = format!("p cnf {} {}\n", cnf.variables, cnf.clauses.len());
+                for clause in &cnf.clauses {
+                    let clause: Vec<String> = clause.iter().map(|x| x.to_string()).collect();
+                    dimacs += &format!("{} 0\n", clause.join(" "));
+                }
+
+                let expected = (0..1u32 << n).filter(|bits| bits.count_ones() as usize <= k);
+                assert_eq!(models(&dimacs, n).len(), expected.count());
+            }
+        }
+    }
+
+    #[test]
+    fn export() {
+        let grid = Grid::parse(vec!["- -", "- -"].into_iter()).unwrap();
+        let dimacs = grid.to_dimacs();
+
+        assert!(dimacs.starts_with("c binero 2x2\np cnf "));
+        assert_eq!(
+            models(&dimacs, 4),
+            vec![
+                vec![false, true, true, false],
+                vec![true, false, false, true]
+            ]
+        );
+
+        let grid = Grid::parse(vec!["1 -", "- -"].into_iter()).unwrap();
+        let dimacs = grid.to_dimacs();
+
+        assert!(dimacs.contains("\n1 0\n"));
+        assert_eq!(models(&dimacs, 4), vec![vec![true, false, false, true]]);
+    }
+}
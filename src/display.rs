@@ -0,0 +1,422 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::cell::{Cell, CellState};
+use crate::grid::Grid;
+use crate::index::{Index, Lane};
+
+const RED: &str = "\x1b[31m";
+const CYAN: &str = "\x1b[36m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Clone, Debug)]
+pub struct DisplayOptions {
+    zero: char,
+    one: char,
+    empty: char,
+    hole: char,
+    separator: String,
+    headers: bool,
+    color: bool,
+    borders: bool,
+    blocks: Option<usize>,
+    marks: bool,
+}
+
+impl DisplayOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn zero(mut self, c: char) -> Self {
+        self.zero = c;
+        self
+    }
+
+    pub fn one(mut self, c: char) -> Self {
+        self.one = c;
+        self
+    }
+
+    pub fn empty(mut self, c: char) -> Self {
+        self.empty = c;
+        self
+    }
+
+    pub fn hole(mut self, c: char) -> Self {
+        self.hole = c;
+        self
+    }
+
+    pub fn separator<S>(mut self, separator: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.separator = separator.into();
+        self
+    }
+
+    pub fn headers(mut self, headers: bool) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    pub fn color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    pub fn borders(mut self, borders: bool) -> Self {
+        self.borders = borders;
+        self
+    }
+
+    pub fn blocks(mut self, size: usize) -> Self {
+        self.blocks = (size > 0).then_some(size);
+        self
+    }
+
+    // Empty cells whose marks leave a single value show it as a small digit
+    pub fn marks(mut self, marks: bool) -> Self {
+        self.marks = marks;
+        self
+    }
+}
+
+impl Default for DisplayOptions {
+    fn default() -> Self {
+        Self {
+            zero: '0',
+            one: '1',
+            empty: '-',
+            hole: '*',
+            separator: String::from(" "),
+            headers: false,
+            color: false,
+            borders: false,
+            blocks: None,
+            marks: false,
+        }
+    }
+}
+
+pub struct GridDisplay<'a> {
+    grid: &'a Grid,
+    options: DisplayOptions,
+}
+
+impl Grid {
+    pub fn display_with(&self, options: DisplayOptions) -> GridDisplay<'_> {
+        GridDisplay {
+            grid: self,
+            options,
+        }
+    }
+}
+
+impl GridDisplay<'_> {
+    fn write_cell(&self, fmt: &mut fmt::Formatter, idx: Index, width: usize) -> fmt::Result {
+        let (grid, options) = (self.grid, &self.options);
+
+        let mark = grid.marks(idx).and_then(|marks| marks.single());
+
+        let c = match grid[idx] {
+            Some(Cell::Zero) => options.zero,
+            Some(Cell::One) => options.one,
+            None if grid.is_hole(idx) => options.hole,
+            None if !options.marks => options.empty,
+            None => match mark {
+                Some(Cell::Zero) => '₀',
+                Some(Cell::One) => '₁',
+                None => options.empty,
+            },
+        };
+
+        // Cells breaking a rule in red, cells filled by the solver in cyan
+        let color = match grid.state(idx) {
+            _ if !options.color => None,
+            Some(_) if grid.is_conflict(idx) => Some(RED),
+            Some(CellState::Derived) => Some(CYAN),
+            _ => None,
+        };
+
+        match color {
+            Some(color) => write!(fmt, "{}{:>3$}{}", color, c, RESET, width),
+            None => write!(fmt, "{:>1$}", c, width),
+        }
+    }
+
+    // Signs between a line and the next one, under the cells they join, over
+    // a blank copy of a line of cells
+    fn sign_line<F>(&self, i: usize, mut line: Vec<char>, position: F) -> Option<String>
+    where
+        F: Fn(usize) -> usize,
+    {
+        let grid = self.grid;
+        let mut signs = grid
+            .columns()
+            .filter_map(|j| grid.sign((i, j), (i + 1, j)).map(|sign| (j, sign)))
+            .peekable();
+
+        signs.peek()?;
+
+        for (j, sign) in signs {
+            line[position(j)] = sign.to_string().chars().next().unwrap_or(' ');
+        }
+
+        Some(line.into_iter().collect::<String>().trim_end().to_string())
+    }
+
+    // Box drawing around the grid, and between blocks of cells
+    fn write_board(
+        &self,
+        fmt: &mut fmt::Formatter,
+        cell_width: usize,
+        header_width: usize,
+    ) -> fmt::Result {
+        let (grid, options) = (self.grid, &self.options);
+        let size = options.blocks.unwrap_or(usize::MAX);
+        let margin = if options.headers { header_width + 1 } else { 0 };
+
+        let lines: Vec<_> = grid.lines().collect();
+        let columns: Vec<_> = grid.columns().collect();
+
+        let border = |left, middle: &str, right| {
+            let segments: Vec<_> = columns
+                .chunks(size)
+                .map(|block| "─".repeat(block.len() * (cell_width + 1) + 1))
+                .collect();
+
+            format!("{:margin$}{}{}{}", "", left, segments.join(middle), right)
+        };
+
+        if options.headers {
+            let headers: Vec<_> = columns
+                .chunks(size)
+                .map(|block| {
+                    block
+                        .iter()
+                        .map(|j| format!(" {:>1$}", j + 1, cell_width))
+                        .collect::<String>()
+                })
+                .collect();
+
+            writeln!(fmt, "{:margin$} {}", "", headers.join("  "))?;
+        }
+
+        // A line of cells with blanks for the cells, and where each cell is
+        let mut blank: Vec<char> = " ".repeat(margin).chars().collect();
+        blank.push('│');
+
+        for block in columns.chunks(size) {
+            blank.extend(" ".repeat(block.len() * (cell_width + 1) + 1).chars());
+            blank.push('│');
+        }
+
+        let position = |j: usize| {
+            let before = match j / size {
+                0 => 0,
+                blocks => blocks * (size * (cell_width + 1) + 2),
+            };
+
+            margin + 1 + before + (j % size) * (cell_width + 1) + cell_width
+        };
+
+        writeln!(fmt, "{}", border('┌', "┬", '┐'))?;
+
+        for (k, block) in lines.chunks(size).enumerate() {
+            if k > 0 {
+                writeln!(fmt, "{}", border('├', "┼", '┤'))?;
+            }
+
+            for (n, i) in block.iter().enumerate() {
+                if options.headers {
+                    write!(fmt, "{:>1$} ", i + 1, header_width)?;
+                }
+
+                write!(fmt, "│")?;
+
+                for block in columns.chunks(size) {
+                    for (m, j) in block.iter().enumerate() {
+                        // Signs between blocks give way to the borders
+                        let idx = Index(*i, *j);
+
+                        match idx.checked_col(-1).and_then(|left| grid.sign(left, idx)) {
+                            Some(sign) if m > 0 => write!(fmt, "{}", sign)?,
+                            _ => write!(fmt, " ")?,
+                        }
+
+                        self.write_cell(fmt, idx, cell_width)?;
+                    }
+
+                    write!(fmt, " │")?;
+                }
+
+                writeln!(fmt)?;
+
+                if n + 1 < block.len() {
+                    if let Some(line) = self.sign_line(*i, blank.clone(), position) {
+                        writeln!(fmt, "{}", line)?;
+                    }
+                }
+            }
+        }
+
+        write!(fmt, "{}", border('└', "┴", '┘'))
+    }
+}
+
+impl fmt::Display for GridDisplay<'_> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let (grid, options) = (self.grid, &self.options);
+
+        // Margin clues take the place of the headers, outside of boards
+        let margins = grid.has_targets() && !options.borders;
+        let headers = options.headers || margins;
+        let header = |lane: Lane| match lane {
+            _ if margins => grid
+                .target(lane)
+                .map_or(options.empty.to_string(), |ones| ones.to_string()),
+            Lane::Line(i) => (i + 1).to_string(),
+            Lane::Column(j) => (j + 1).to_string(),
+        };
+
+        // Headers are 1-based, cells are aligned on the widest one
+        let (cell_width, header_width) = if headers {
+            let width = |lane| header(lane).chars().count();
+            (
+                grid.columns()
+                    .map(|j| width(Lane::Column(j)))
+                    .max()
+                    .unwrap_or(1),
+                grid.lines()
+                    .map(|i| width(Lane::Line(i)))
+                    .max()
+                    .unwrap_or(1),
+            )
+        } else {
+            (1, 0)
+        };
+
+        if options.borders {
+            return self.write_board(fmt, cell_width, header_width);
+        }
+
+        if headers {
+            write!(fmt, "{:>1$}", "", header_width)?;
+
+            for j in 0..grid.width() {
+                let column = header(Lane::Column(j));
+                write!(fmt, "{}{:>2$}", options.separator, column, cell_width)?;
+            }
+
+            writeln!(fmt)?;
+        }
+
+        // Signs stand in for the separator, which needs room for them
+        let separator = match options.separator.as_str() {
+            "" if grid.signs().next().is_some() => " ",
+            separator => separator,
+        };
+        let gap = separator.chars().count();
+        let margin = if headers { header_width + gap } else { 0 };
+
+        let position = |j: usize| margin + j * (cell_width + gap) + cell_width - 1;
+        let blank = vec![' '; position(grid.width())];
+
+        for i in 0..grid.height() {
+            if headers {
+                let line = header(Lane::Line(i));
+                write!(fmt, "{:>2$}{}", line, separator, header_width)?;
+            }
+
+            for j in 0..grid.width() {
+                self.write_cell(fmt, Index(i, j), cell_width)?;
+
+                if j < grid.width() - 1 {
+                    match grid.sign((i, j), (i, j + 1)) {
+                        Some(sign) => write!(fmt, "{:^1$}", sign, gap)?,
+                        None => write!(fmt, "{}", separator)?,
+                    }
+                }
+            }
+
+            if i < grid.height() - 1 {
+                writeln!(fmt)?;
+
+                if let Some(line) = self.sign_line(i, blank.clone(), position) {
+                    writeln!(fmt, "{}", line)?;
+                }
+            }
+        }
+
+        // Region labels follow the cells, in the same layout
+        for i in (0..grid.height()).filter(|_| grid.regions().next().is_some()) {
+            write!(fmt, "\n{:>1$}", "", margin)?;
+
+            for j in 0..grid.width() {
+                let label = grid.region((i, j)).unwrap_or(' ');
+                write!(fmt, "{:>1$}", label, cell_width)?;
+
+                if j < grid.width() - 1 {
+                    write!(fmt, "{}", separator)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_display() {
+        let grid = Grid::parse(vec!["1 - 0 -", "- - 1 1"].into_iter()).unwrap();
+
+        assert_eq!(grid.to_string(), "1 - 0 -\n- - 1 1");
+
+        let options = DisplayOptions::new()
+            .zero('O')
+            .one('X')
+            .empty('.')
+            .separator("");
+        assert_eq!(grid.display_with(options).to_string(), "X.O.\n..XX");
+
+        let mut solved = grid.clone();
+        solved.set((1, 0), Some(Cell::Zero));
+        solved.set((1, 1), Some(Cell::One));
+
+        // Filled cells are cyan, the three ones of the second line are red
+        let options = DisplayOptions::new().color(true);
+        assert_eq!(
+            solved.display_with(options).to_string(),
+            "1 - 0 -\n\x1b[36m0\x1b[0m \x1b[31m1\x1b[0m \x1b[31m1\x1b[0m \x1b[31m1\x1b[0m"
+        );
+
+        let options = DisplayOptions::new().headers(true);
+        assert_eq!(
+            grid.display_with(options).to_string(),
+            "  1 2 3 4\n1 1 - 0 -\n2 - - 1 1"
+        );
+
+        let options = DisplayOptions::new().headers(true).borders(true).blocks(2);
+        assert_eq!(
+            grid.display_with(options).to_string(),
+            [
+                "    1 2   3 4",
+                "  ┌─────┬─────┐",
+                "1 │ 1 - │ 0 - │",
+                "2 │ - - │ 1 1 │",
+                "  └─────┴─────┘",
+            ]
+            .join("\n")
+        );
+    }
+}
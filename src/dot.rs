@@ -0,0 +1,107 @@
+use std::fmt::Write;
+
+use crate::deduction::Step;
+use crate::error::SolveError;
+use crate::grid::Grid;
+use crate::index::Index;
+
+impl Grid {
+    // Graphviz graph of the guesses made while solving, each leading to
+    // deeper guesses, a contradiction or the solution
+    pub fn to_search_dot(&self) -> Result<String, SolveError> {
+        let steps = self.explain()?;
+        let mut dot = String::new();
+
+        let _ = writeln!(dot, "digraph search {{");
+        let _ = writeln!(dot, "  node [fontname=\"sans-serif\"];");
+        let _ = writeln!(dot, "  n0 [label=\"start\", shape=circle];");
+
+        // Guesses still standing with their node, and the cells deduced
+        // after each node
+        let mut frames: Vec<(Index, usize)> = Vec::new();
+        let mut deduced = vec![0];
+
+        for step in &steps {
+            let parent = frames.last().map_or(0, |(_, node)| *node);
+
+            match step {
+                Step::Deduction(_) => deduced[parent] += 1,
+                Step::Guess(idx, cell) => {
+                    // Guessing again a cell means trying its other value
+                    if let Some(k) = frames.iter().position(|(guess, _)| guess == idx) {
+                        frames.truncate(k);
+                    }
+
+                    let parent = frames.last().map_or(0, |(_, node)| *node);
+                    let node = deduced.len();
+
+                    let _ = writeln!(
+                        dot,
+                        "  n{} [label=\"r{}c{} = {}\"];",
+                        node,
+                        idx.0 + 1,
+                        idx.1 + 1,
+                        cell
+                    );
+                    let _ = writeln!(dot, "  n{} -> n{};", parent, node);
+
+                    frames.push((*idx, node));
+                    deduced.push(0);
+                }
+                Step::Backtrack => {
+                    let label = format!("contradiction after {} cells", deduced[parent]);
+                    Self::dot_leaf(&mut dot, &mut deduced, parent, &label, "red");
+                    frames.pop();
+                }
+            }
+        }
+
+        let parent = frames.last().map_or(0, |(_, node)| *node);
+        let label = format!("solution after {} cells", deduced[parent]);
+        Self::dot_leaf(&mut dot, &mut deduced, parent, &label, "green");
+
+        let _ = writeln!(dot, "}}");
+        Ok(dot)
+    }
+
+    fn dot_leaf(
+        dot: &mut String,
+        deduced: &mut Vec<usize>,
+        parent: usize,
+        label: &str,
+        color: &str,
+    ) {
+        let node = deduced.len();
+        deduced.push(0);
+
+        let _ = writeln!(
+            dot,
+            "  n{} [label=\"{}\", shape=box, color={}];",
+            node, label, color
+        );
+        let _ = writeln!(dot, "  n{} -> n{};", parent, node);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export() {
+        let grid = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+        let dot = grid.unwrap().to_search_dot().unwrap();
+
+        // Without guesses, the solution comes right after the start
+        assert!(dot.starts_with("digraph search {\n"));
+        assert!(dot.contains("label=\"solution after 9 cells\""));
+        assert!(dot.contains("  n0 -> n"));
+        assert!(dot.ends_with("}\n"));
+
+        let grid = Grid::parse(vec!["- - - - - - - - - -"; 10].into_iter()).unwrap();
+        let dot = grid.to_search_dot().unwrap();
+        assert!(dot.contains("label=\"r1c1 = "));
+        assert!(dot.contains("color=red"));
+        assert_eq!(dot.matches("color=green").count(), 1);
+    }
+}
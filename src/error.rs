@@ -1,41 +1,472 @@
-use std::error;
-use std::fmt;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::error;
+use core::fmt;
+use core::time::Duration;
+
+use crate::grid::Grid;
+use crate::index::{Index, Lane};
+use crate::parse;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rule {
+    Adjacency,
+    Balance,
+    Uniqueness,
+    Sign,
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Adjacency => write!(fmt, "too many adjacent identical cells"),
+            Self::Balance => write!(fmt, "too many identical cells in a lane"),
+            Self::Uniqueness => write!(fmt, "two identical lanes"),
+            Self::Sign => write!(fmt, "two cells disagree with the sign between them"),
+        }
+    }
+}
+
+// How a grid breaks a rule: the lines or columns at fault, when the rule is
+// about them, and the cells involved
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Violation {
+    pub(crate) rule: Rule,
+    pub(crate) lanes: Vec<Lane>,
+    pub(crate) cells: Vec<Index>,
+}
+
+impl Violation {
+    pub(crate) fn new(rule: Rule, lanes: Vec<Lane>, cells: Vec<Index>) -> Self {
+        Self { rule, lanes, cells }
+    }
+
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+
+    // One lane for runs and counts, both lanes when two are identical, none
+    // for diagonals, regions and signs
+    pub fn lanes(&self) -> &[Lane] {
+        &self.lanes
+    }
+
+    // The run too long, the cells of the value in excess, the cells of both
+    // identical lanes or both cells of a sign
+    pub fn cells(&self) -> &[Index] {
+        &self.cells
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let name = |lane: &Lane| match lane {
+            Lane::Line(i) => ("line", i + 1),
+            Lane::Column(j) => ("column", j + 1),
+        };
+
+        match (self.lanes.as_slice(), self.cells.first()) {
+            ([lane], _) => {
+                let (kind, k) = name(lane);
+                write!(fmt, "{} {}: {}", kind, k, self.rule)
+            }
+            ([first, second], _) => {
+                let ((kind, a), (_, b)) = (name(first), name(second));
+                write!(fmt, "{}s {} and {}: {}", kind, a, b, self.rule)
+            }
+            (_, Some(idx)) => {
+                write!(
+                    fmt,
+                    "line {}, column {}: {}",
+                    idx.0 + 1,
+                    idx.1 + 1,
+                    self.rule
+                )
+            }
+            (_, None) => write!(fmt, "{}", self.rule),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Limit {
+    Depth(usize),
+    Nodes(usize),
+    Time(Duration),
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Depth(0) => write!(fmt, "the grid cannot be solved without guessing"),
+            Self::Depth(limit) => write!(fmt, "more than {} nested guesses", limit),
+            Self::Nodes(limit) => write!(fmt, "more than {} search nodes", limit),
+            Self::Time(limit) => write!(fmt, "more than {:?} of search", limit),
+        }
+    }
+}
 
 #[derive(Debug)]
-pub enum GridError {
+pub enum ParseError {
     EmptyGrid,
-    InvalidChar(char),
-    InvalidGrid,
-    NoSolution,
-    OddDimension,
-    WidthMismatch,
+    // Line and column of the character in the input, counting from 1, when
+    // it was read from text
+    InvalidChar {
+        found: char,
+        position: Option<(usize, usize)>,
+    },
+    InvalidEncoding,
+    InvalidGrid(Violation),
+    OddDimension {
+        width: usize,
+        height: usize,
+    },
+    RatioMismatch {
+        width: usize,
+        height: usize,
+    },
+    SizeMismatch {
+        expected: (usize, usize),
+        found: (usize, usize),
+    },
+    // The grid goes beyond the limits of the parser, given here since
+    // reading stops before its whole size is known
+    TooLarge {
+        max_width: usize,
+        max_height: usize,
+        max_cells: usize,
+    },
+    // A lane cut by holes, whose other cells cannot hold their share of each
+    // value
+    UnbalancedLane {
+        lane: Lane,
+        cells: usize,
+    },
+    UnbalancedRegion(char),
+    // No complete grid of this size follows the rules, such as lanes too
+    // short to all be different
+    UnsolvableDimensions {
+        width: usize,
+        height: usize,
+    },
+    Unsupported(&'static str),
+    WidthMismatch {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
 }
 
-impl fmt::Display for GridError {
+impl ParseError {
+    pub(crate) fn invalid_char(found: char) -> Self {
+        Self::InvalidChar {
+            found,
+            position: None,
+        }
+    }
+
+    // Tell where an invalid character was found, unless it is known already
+    pub(crate) fn at(self, line: usize, column: usize) -> Self {
+        match self {
+            Self::InvalidChar {
+                found,
+                position: None,
+            } => Self::InvalidChar {
+                found,
+                position: Some((line, column)),
+            },
+            err => err,
+        }
+    }
+
+    // Errors of lines read after some others, shifted to their place
+    pub(crate) fn below(self, lines: usize) -> Self {
+        match self {
+            Self::InvalidChar {
+                found,
+                position: Some((line, column)),
+            } => Self::InvalidChar {
+                found,
+                position: Some((line + lines, column)),
+            },
+            Self::WidthMismatch {
+                line,
+                expected,
+                found,
+            } => Self::WidthMismatch {
+                line: line + lines,
+                expected,
+                found,
+            },
+            err => err,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         write!(fmt, "error: ")?;
 
         match self {
             Self::EmptyGrid => {
-                write!(fmt, "gris is empty")
+                write!(fmt, "grid is empty")
+            }
+            Self::InvalidChar { found, position } => {
+                if let Some((line, column)) = position {
+                    write!(fmt, "line {}, column {}: ", line, column)?;
+                }
+
+                write!(fmt, "unknown character '{}'", found)?;
+
+                // Characters looking like others, or not showing at all
+                match parse::ascii_variant(*found) {
+                    _ if found.is_ascii() => Ok(()),
+                    Some(c) => write!(fmt, " (U+{:04X}, a variant of '{}')", *found as u32, c),
+                    None => write!(fmt, " (U+{:04X})", *found as u32),
+                }
+            }
+            Self::InvalidEncoding => {
+                write!(fmt, "grid encoding is invalid")
+            }
+            Self::InvalidGrid(violation) => {
+                write!(fmt, "grid is invalid: {}", violation)
+            }
+            Self::OddDimension { width, height } => {
+                write!(fmt, "grid has odd dimensions ({}x{})", width, height)
             }
-            Self::InvalidChar(c) => {
-                write!(fmt, "unknown character '{}'", c)
+            Self::RatioMismatch { width, height } => {
+                write!(
+                    fmt,
+                    "grid lanes cannot hold their share of each value ({}x{})",
+                    width, height
+                )
+            }
+            Self::SizeMismatch { expected, found } => {
+                write!(
+                    fmt,
+                    "grid has the wrong size ({}x{} instead of {}x{})",
+                    found.0, found.1, expected.0, expected.1
+                )
+            }
+            Self::TooLarge {
+                max_width,
+                max_height,
+                max_cells,
+            } => {
+                write!(
+                    fmt,
+                    "grid is too large (at most {}x{} and {} cells)",
+                    max_width, max_height, max_cells
+                )
+            }
+            Self::UnbalancedLane { lane, cells } => {
+                let (name, k) = match lane {
+                    Lane::Line(i) => ("line", i + 1),
+                    Lane::Column(j) => ("column", j + 1),
+                };
+
+                write!(
+                    fmt,
+                    "{} {} has {} cells besides its holes, which cannot hold their share of each value",
+                    name, k, cells
+                )
             }
-            Self::InvalidGrid => {
-                write!(fmt, "grid is invalid")
+            Self::UnbalancedRegion(label) => {
+                write!(fmt, "region {} cannot hold its share of each value", label)
             }
-            Self::NoSolution => {
+            Self::UnsolvableDimensions { width, height } => {
+                write!(
+                    fmt,
+                    "no complete grid has these dimensions ({}x{})",
+                    width, height
+                )
+            }
+            Self::Unsupported(what) => {
+                write!(fmt, "grid has {}, which are not supported here", what)
+            }
+            Self::WidthMismatch {
+                line,
+                expected,
+                found,
+            } => {
+                write!(
+                    fmt,
+                    "line {} has {} cells, expected {}",
+                    line, found, expected
+                )
+            }
+        }
+    }
+}
+
+impl error::Error for ParseError {}
+
+// Where a search without solution went the furthest: the cells filled at
+// that point, and the cell left without any value that fits
+#[derive(Clone, Debug)]
+pub struct DeadEnd {
+    pub(crate) grid: Grid,
+    pub(crate) index: Index,
+    pub(crate) lane: Option<Lane>,
+    pub(crate) rule: Rule,
+}
+
+impl DeadEnd {
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    pub fn index(&self) -> Index {
+        self.index
+    }
+
+    // The lane ruling out the last value of the cell, when there is one
+    pub fn lane(&self) -> Option<Lane> {
+        self.lane
+    }
+
+    pub fn rule(&self) -> Rule {
+        self.rule
+    }
+}
+
+impl fmt::Display for DeadEnd {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "line {}, column {}: {}",
+            self.index.0 + 1,
+            self.index.1 + 1,
+            self.rule
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum SolveError {
+    InvalidGrid(Violation),
+    LimitExceeded(Limit),
+    // Searches tell where they got stuck, other ways of solving may not
+    NoSolution(Option<Box<DeadEnd>>),
+    // Two of the solutions, when the solution had to be unique
+    MultipleSolutions(Box<[Grid; 2]>),
+}
+
+impl SolveError {
+    // A cell that takes a different value in each solution
+    pub fn ambiguous_cell(&self) -> Option<Index> {
+        match self {
+            Self::MultipleSolutions(solutions) => {
+                let [first, second] = &**solutions;
+                let differences = first.diff(second).ok()?;
+
+                differences.first().map(|difference| difference.index())
+            }
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "error: ")?;
+
+        match self {
+            Self::InvalidGrid(violation) => {
+                write!(fmt, "grid is invalid: {}", violation)
+            }
+            Self::LimitExceeded(limit) => {
+                write!(fmt, "search limit exceeded: {}", limit)
+            }
+            Self::NoSolution(Some(dead_end)) => {
+                write!(fmt, "grid has no solution, stuck at {}", dead_end)
+            }
+            Self::NoSolution(None) => {
                 write!(fmt, "grid has no solution")
             }
-            Self::OddDimension => {
-                write!(fmt, "grid has odd dimensions")
+            Self::MultipleSolutions(_) => match self.ambiguous_cell() {
+                Some(idx) => write!(
+                    fmt,
+                    "grid has several solutions, line {}, column {} can take either value",
+                    idx.0 + 1,
+                    idx.1 + 1
+                ),
+                None => write!(fmt, "grid has several solutions"),
+            },
+        }
+    }
+}
+
+impl error::Error for SolveError {}
+
+#[derive(Debug)]
+pub enum GridError {
+    Parse(ParseError),
+    Solve(SolveError),
+}
+
+impl fmt::Display for GridError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Parse(err) => err.fmt(fmt),
+            Self::Solve(err) => err.fmt(fmt),
+        }
+    }
+}
+
+impl error::Error for GridError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Parse(err) => Some(err),
+            Self::Solve(err) => Some(err),
+        }
+    }
+}
+
+impl From<ParseError> for GridError {
+    fn from(err: ParseError) -> Self {
+        Self::Parse(err)
+    }
+}
+
+impl From<SolveError> for GridError {
+    fn from(err: SolveError) -> Self {
+        Self::Solve(err)
+    }
+}
+
+#[derive(Debug)]
+pub enum VerificationError {
+    ClueMismatch(Index),
+    DimensionMismatch,
+    Incomplete(Index),
+    InvalidGrid(Violation),
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "error: ")?;
+
+        match self {
+            Self::ClueMismatch(idx) => {
+                write!(
+                    fmt,
+                    "line {}, column {} does not match the puzzle",
+                    idx.0 + 1,
+                    idx.1 + 1
+                )
+            }
+            Self::DimensionMismatch => {
+                write!(fmt, "solution and puzzle have different dimensions")
+            }
+            Self::Incomplete(idx) => {
+                write!(fmt, "line {}, column {} is empty", idx.0 + 1, idx.1 + 1)
             }
-            Self::WidthMismatch => {
-                write!(fmt, "not all lines of the grid have the same length")
+            Self::InvalidGrid(violation) => {
+                write!(fmt, "solution is invalid: {}", violation)
             }
         }
     }
 }
 
-impl error::Error for GridError {}
+impl error::Error for VerificationError {}
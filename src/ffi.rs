@@ -0,0 +1,212 @@
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{self, UnwindSafe};
+use std::ptr;
+
+use crate::cell::Cell;
+use crate::error::{ParseError, SolveError};
+use crate::grid::Grid;
+
+// Same values as the exit codes of the command line tool
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BineroStatus {
+    Ok = 0,
+    // Null pointers, text that is not UTF-8, or a bug caught at the boundary
+    Error = 1,
+    Parse = 2,
+    Invalid = 3,
+    NoSolution = 4,
+    Limit = 5,
+    MultipleSolutions = 6,
+}
+
+// Opaque to C, only handled through pointers
+pub struct BineroGrid(Grid);
+
+impl From<ParseError> for BineroStatus {
+    fn from(err: ParseError) -> Self {
+        match err {
+            ParseError::InvalidGrid(_) => Self::Invalid,
+            _ => Self::Parse,
+        }
+    }
+}
+
+impl From<SolveError> for BineroStatus {
+    fn from(err: SolveError) -> Self {
+        match err {
+            SolveError::InvalidGrid(_) => Self::Invalid,
+            SolveError::NoSolution(_) => Self::NoSolution,
+            SolveError::LimitExceeded(_) => Self::Limit,
+            SolveError::MultipleSolutions(_) => Self::MultipleSolutions,
+        }
+    }
+}
+
+// Panics must not unwind into C
+fn guard<F>(f: F) -> BineroStatus
+where
+    F: FnOnce() -> Result<(), BineroStatus> + UnwindSafe,
+{
+    match panic::catch_unwind(f) {
+        Ok(Ok(())) => BineroStatus::Ok,
+        Ok(Err(status)) => status,
+        Err(_) => BineroStatus::Error,
+    }
+}
+
+/// Parse a grid in the text format, to be released with `binero_free`.
+///
+/// # Safety
+///
+/// `text` must be a valid C string, and `grid` a valid pointer.
+#[no_mangle]
+pub unsafe extern "C" fn binero_parse(
+    text: *const c_char,
+    grid: *mut *mut BineroGrid,
+) -> BineroStatus {
+    guard(|| {
+        if text.is_null() || grid.is_null() {
+            return Err(BineroStatus::Error);
+        }
+
+        let text = CStr::from_ptr(text)
+            .to_str()
+            .map_err(|_| BineroStatus::Error)?;
+        let parsed = Grid::parse(text.lines())?;
+
+        *grid = Box::into_raw(Box::new(BineroGrid(parsed)));
+        Ok(())
+    })
+}
+
+/// Solve a grid in place, it is left untouched when there is no solution.
+///
+/// # Safety
+///
+/// `grid` must come from `binero_parse`, and not be freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn binero_solve(grid: *mut BineroGrid) -> BineroStatus {
+    let grid = panic::AssertUnwindSafe(grid);
+
+    guard(move || {
+        let grid = grid.0.as_mut().ok_or(BineroStatus::Error)?;
+        let mut solution = grid.0.clone();
+
+        solution.solve()?;
+        grid.0 = solution;
+        Ok(())
+    })
+}
+
+/// The value of a cell: 0 or 1, or -1 when it is empty or out of the grid.
+///
+/// # Safety
+///
+/// `grid` must come from `binero_parse`, and not be freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn binero_get(grid: *const BineroGrid, line: usize, column: usize) -> i8 {
+    let Some(BineroGrid(grid)) = grid.as_ref() else {
+        return -1;
+    };
+
+    if line >= grid.height() || column >= grid.width() {
+        return -1;
+    }
+
+    match grid[(line, column)] {
+        Some(Cell::Zero) => 0,
+        Some(Cell::One) => 1,
+        None => -1,
+    }
+}
+
+/// The grid in the text format, to be released with `binero_free_string`.
+///
+/// # Safety
+///
+/// `grid` must come from `binero_parse`, and not be freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn binero_to_string(grid: *const BineroGrid) -> *mut c_char {
+    let Some(BineroGrid(grid)) = grid.as_ref() else {
+        return ptr::null_mut();
+    };
+
+    // Grids never hold a null byte
+    CString::new(grid.to_string()).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Release a grid, null pointers are ignored.
+///
+/// # Safety
+///
+/// `grid` must come from `binero_parse`, and not be freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn binero_free(grid: *mut BineroGrid) {
+    if !grid.is_null() {
+        drop(Box::from_raw(grid));
+    }
+}
+
+/// Release a string, null pointers are ignored.
+///
+/// # Safety
+///
+/// `s` must come from `binero_to_string`, and not be freed yet.
+#[no_mangle]
+pub unsafe extern "C" fn binero_free_string(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header() {
+        // Regenerated with: cbindgen --config cbindgen.toml --output include/binero.h
+        assert_eq!(
+            include_str!("../include/binero.h"),
+            include_str!(concat!(env!("OUT_DIR"), "/binero.h")),
+            "include/binero.h does not match the exported functions"
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let text = CString::new("1 1 - 0\n- 0 - -\n- - 0 -\n- 1 - 0").unwrap();
+        let mut grid = ptr::null_mut();
+
+        unsafe {
+            assert_eq!(binero_parse(text.as_ptr(), &mut grid), BineroStatus::Ok);
+            assert_eq!(binero_get(grid, 0, 2), -1);
+            assert_eq!(binero_solve(grid), BineroStatus::Ok);
+            assert_eq!(binero_get(grid, 0, 2), 0);
+            assert_eq!(binero_get(grid, 4, 0), -1);
+
+            let s = binero_to_string(grid);
+            assert!(CStr::from_ptr(s).to_str().unwrap().starts_with("1 1 0 0"));
+
+            binero_free_string(s);
+            binero_free(grid);
+        }
+
+        let invalid = CString::new("1 1 1 -\n- - - -\n- - - -\n- - - -").unwrap();
+        let garbage = CString::new("1 x").unwrap();
+
+        unsafe {
+            assert_eq!(
+                binero_parse(invalid.as_ptr(), &mut grid),
+                BineroStatus::Invalid
+            );
+            assert_eq!(
+                binero_parse(garbage.as_ptr(), &mut grid),
+                BineroStatus::Parse
+            );
+            assert_eq!(binero_parse(ptr::null(), &mut grid), BineroStatus::Error);
+            assert_eq!(binero_solve(ptr::null_mut()), BineroStatus::Error);
+        }
+    }
+}
@@ -0,0 +1,305 @@
+use core::fmt;
+use core::ops;
+
+use crate::board::Board;
+use crate::cell::Cell;
+use crate::error::{ParseError, Rule, SolveError, Violation};
+use crate::grid::{Grid, GridCell, Histogram, LaneMask};
+use crate::index::Index;
+use crate::rules::Rules;
+use crate::solver::{self, SolveStats, SolverConfig};
+
+// A grid whose size is known at compile time, with every cell stored inline
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FixedGrid<const W: usize, const H: usize> {
+    cells: [[GridCell; W]; H],
+    line_counts: [Histogram; H],
+    column_counts: [Histogram; W],
+    line_masks: [LaneMask<u64>; H],
+    column_masks: [LaneMask<u64>; W],
+}
+
+// Lanes are kept in a single word
+const MAX_LANE: usize = u64::BITS as usize;
+
+impl<const W: usize, const H: usize> FixedGrid<W, H> {
+    // Sizes are checked once per size, when the type is first used
+    const SIZE: () = assert!(
+        W > 0
+            && H > 0
+            && W.is_multiple_of(2)
+            && H.is_multiple_of(2)
+            && W <= MAX_LANE
+            && H <= MAX_LANE,
+        "grids have even sizes of up to 64"
+    );
+
+    pub fn new() -> Self {
+        let () = Self::SIZE;
+
+        Self {
+            cells: [[None; W]; H],
+            line_counts: [Histogram::default(); H],
+            column_counts: [Histogram::default(); W],
+            line_masks: [LaneMask::default(); H],
+            column_masks: [LaneMask::default(); W],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        W
+    }
+
+    pub fn height(&self) -> usize {
+        H
+    }
+
+    pub fn is_valid(&self) -> Result<(), Violation> {
+        // The masks are quick to check, a grid tells what is wrong with them
+        self.check().or_else(|_| Grid::from(*self).is_valid())
+    }
+
+    fn check(&self) -> Result<(), Rule> {
+        let lines = self.line_masks.iter().zip(&self.line_counts);
+        let columns = self.column_masks.iter().zip(&self.column_counts);
+
+        for (mask, map) in lines {
+            Grid::check_mask(mask, map, W, &Rules::default())?;
+        }
+
+        for (mask, map) in columns {
+            Grid::check_mask(mask, map, H, &Rules::default())?;
+        }
+
+        let lines = self.line_masks.iter().enumerate();
+        let columns = self.column_masks.iter().enumerate();
+
+        // Only complete lanes can be identical
+        for (k, mask) in lines.filter(|(_, mask)| mask.is_complete(W)) {
+            if Self::duplicated(&self.line_masks, k, mask, W) {
+                return Err(Rule::Uniqueness);
+            }
+        }
+
+        for (k, mask) in columns.filter(|(_, mask)| mask.is_complete(H)) {
+            if Self::duplicated(&self.column_masks, k, mask, H) {
+                return Err(Rule::Uniqueness);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn solve(&mut self) -> Result<(), SolveError> {
+        self.solve_with(&SolverConfig::default())
+    }
+
+    pub fn solve_with(&mut self, config: &SolverConfig) -> Result<(), SolveError> {
+        self.solve_with_stats(config).map(|_| ())
+    }
+
+    pub fn solve_with_stats(&mut self, config: &SolverConfig) -> Result<SolveStats, SolveError> {
+        assert!(
+            config.grid_rules().is_none_or(|rules| rules.is_standard()),
+            "fixed-size grids only follow the standard rules"
+        );
+
+        solver::solve(self, config)
+    }
+
+    // Whether another complete lane is the same as the one at k
+    fn duplicated(masks: &[LaneMask<u64>], k: usize, mask: &LaneMask<u64>, size: usize) -> bool {
+        masks
+            .iter()
+            .enumerate()
+            .any(|(other, lane)| other != k && lane.is_complete(size) && lane == mask)
+    }
+}
+
+impl<const W: usize, const H: usize> Board for FixedGrid<W, H> {
+    fn width(&self) -> usize {
+        W
+    }
+
+    fn height(&self) -> usize {
+        H
+    }
+
+    fn line_count(&self, i: usize) -> Histogram {
+        self.line_counts[i]
+    }
+
+    fn column_count(&self, j: usize) -> Histogram {
+        self.column_counts[j]
+    }
+
+    fn is_valid(&self) -> Result<(), Violation> {
+        FixedGrid::is_valid(self)
+    }
+
+    fn check_move(&self, idx: Index, cell: Cell) -> Result<(), Rule> {
+        let Index(i, j) = idx;
+        let old = self[idx];
+
+        // Lanes going through the cell, as they would be after the move
+        let line = self.line_masks[i].with(j, cell);
+        let column = self.column_masks[j].with(i, cell);
+
+        let rules = &Rules::default();
+
+        Grid::check_move_lane(&line, j, W, &self.line_counts[i], old, cell, rules)?;
+        Grid::check_move_lane(&column, i, H, &self.column_counts[j], old, cell, rules)?;
+
+        // A complete lane must differ from every other complete lane
+        if line.is_complete(W) && Self::duplicated(&self.line_masks, i, &line, W) {
+            return Err(Rule::Uniqueness);
+        }
+
+        if column.is_complete(H) && Self::duplicated(&self.column_masks, j, &column, H) {
+            return Err(Rule::Uniqueness);
+        }
+
+        Ok(())
+    }
+
+    fn set(&mut self, idx: Index, new: GridCell) -> bool {
+        let Index(i, j) = idx;
+        let old = self.cells[i][j];
+
+        self.cells[i][j] = new;
+
+        // Keep lane histograms up to date
+        if let Some(cell) = old {
+            self.line_counts[i][cell] -= 1;
+            self.column_counts[j][cell] -= 1;
+        }
+
+        if let Some(cell) = new {
+            self.line_counts[i][cell] += 1;
+            self.column_counts[j][cell] += 1;
+        }
+
+        self.line_masks[i].set(j, new);
+        self.column_masks[j].set(i, new);
+
+        old != new
+    }
+
+    fn to_grid(&self) -> Grid {
+        Grid::from(*self)
+    }
+}
+
+impl<const W: usize, const H: usize> Default for FixedGrid<W, H> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const W: usize, const H: usize> TryFrom<&Grid> for FixedGrid<W, H> {
+    type Error = ParseError;
+
+    fn try_from(grid: &Grid) -> Result<Self, Self::Error> {
+        if (grid.width(), grid.height()) != (W, H) {
+            return Err(ParseError::SizeMismatch {
+                expected: (W, H),
+                found: (grid.width(), grid.height()),
+            });
+        }
+
+        // Only standard puzzles fit
+        if grid.signs().next().is_some() {
+            return Err(ParseError::Unsupported("signs"));
+        }
+
+        if grid.regions().next().is_some() {
+            return Err(ParseError::Unsupported("regions"));
+        }
+
+        if grid.holes().next().is_some() {
+            return Err(ParseError::Unsupported("holes"));
+        }
+
+        if grid.has_targets() {
+            return Err(ParseError::Unsupported("margin clues"));
+        }
+
+        if !grid.rules().is_standard() {
+            return Err(ParseError::Unsupported("rules of a variant"));
+        }
+
+        let mut fixed = Self::new();
+
+        for idx in (0..H).flat_map(|i| (0..W).map(move |j| Index(i, j))) {
+            Board::set(&mut fixed, idx, grid[idx]);
+        }
+
+        Ok(fixed)
+    }
+}
+
+// Every filled cell becomes a clue of the grid
+impl<const W: usize, const H: usize> From<FixedGrid<W, H>> for Grid {
+    fn from(fixed: FixedGrid<W, H>) -> Self {
+        let mut grid = Grid::with_size(W, H);
+
+        for idx in (0..H).flat_map(|i| (0..W).map(move |j| Index(i, j))) {
+            grid.set_clue(idx, fixed[idx]);
+        }
+
+        grid
+    }
+}
+
+impl<I, const W: usize, const H: usize> ops::Index<I> for FixedGrid<W, H>
+where
+    I: Into<Index>,
+{
+    type Output = GridCell;
+
+    fn index(&self, idx: I) -> &Self::Output {
+        let Index(i, j) = idx.into();
+        &self.cells[i][j]
+    }
+}
+
+impl<const W: usize, const H: usize> fmt::Display for FixedGrid<W, H> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        Grid::from(*self).fmt(fmt)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn solve() {
+        let input = vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"];
+        let grid = Grid::parse(input.into_iter()).unwrap();
+
+        let mut fixed = FixedGrid::<4, 4>::try_from(&grid).unwrap();
+        assert_eq!(fixed[(0, 2)], None);
+        fixed.solve().unwrap();
+
+        let mut expected = grid.clone();
+        expected.solve().unwrap();
+        assert_eq!(Grid::from(fixed), expected);
+
+        // Guesses are needed on an empty grid
+        let mut fixed = FixedGrid::<6, 6>::new();
+        let stats = fixed.solve_with_stats(&SolverConfig::new()).unwrap();
+        assert!(stats.guesses() > 0);
+        assert!(Grid::from(FixedGrid::<6, 6>::new())
+            .verify_solution(&Grid::from(fixed))
+            .is_ok());
+
+        assert!(matches!(
+            FixedGrid::<6, 4>::try_from(&grid),
+            Err(ParseError::SizeMismatch {
+                expected: (6, 4),
+                found: (4, 4)
+            })
+        ));
+    }
+}
@@ -0,0 +1,51 @@
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::cell::Cell;
+use crate::grid::Grid;
+use crate::index::Index;
+
+// Valid puzzles of up to 16x16, small enough to be solved quickly
+impl<'a> Arbitrary<'a> for Grid {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let width = 2 * u.int_in_range(1..=8)?;
+        let height = 2 * u.int_in_range(1..=8)?;
+        let mut grid = Grid::with_size(width, height);
+
+        for i in 0..height {
+            for j in 0..width {
+                let cell = match u.int_in_range(0..=2)? {
+                    0 => Some(Cell::Zero),
+                    1 => Some(Cell::One),
+                    _ => None,
+                };
+
+                // Clues breaking a rule are left out
+                grid.set_clue(Index(i, j), cell);
+                if grid.is_conflict(Index(i, j)) {
+                    grid.set_clue(Index(i, j), None);
+                }
+            }
+        }
+
+        Ok(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_grids() {
+        let data: Vec<u8> = (0..4096u32).map(|k| (k * 7919 % 251) as u8).collect();
+        let mut u = Unstructured::new(&data);
+
+        for _ in 0..16 {
+            let grid = Grid::arbitrary(&mut u).unwrap();
+
+            assert!(grid.is_valid().is_ok());
+            assert_eq!(grid.width() % 2, 0);
+            assert_eq!(grid.height() % 2, 0);
+        }
+    }
+}
@@ -0,0 +1,483 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::error::{Limit, ParseError, SolveError};
+use crate::grid::{Grid, GridCell};
+use crate::index::Index;
+use crate::random::Rng;
+use crate::rating::Difficulty;
+use crate::solver::SolverConfig;
+use crate::symmetry::Symmetry;
+
+// Candidates tried before settling for the closest difficulty
+const MAX_ATTEMPTS: usize = 32;
+
+// Longer lanes have more possible values than any grid has lanes
+const MAX_COUNTED_LANE: usize = 64;
+
+#[derive(Clone, Debug, Default)]
+pub struct GeneratorConfig {
+    difficulty: Option<Difficulty>,
+    symmetry: Symmetry,
+    clue_ratio: f32,
+    logic_only: bool,
+}
+
+impl GeneratorConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn difficulty(mut self, difficulty: Difficulty) -> Self {
+        self.difficulty = Some(difficulty);
+        self
+    }
+
+    pub fn symmetry(mut self, symmetry: Symmetry) -> Self {
+        self.symmetry = symmetry;
+        self
+    }
+
+    pub fn target_clue_ratio(mut self, ratio: f32) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&ratio),
+            "invalid clue ratio ({})",
+            ratio
+        );
+
+        self.clue_ratio = ratio;
+        self
+    }
+
+    pub fn logic_only(mut self, logic_only: bool) -> Self {
+        self.logic_only = logic_only;
+        self
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Generator {
+    width: usize,
+    height: usize,
+    seed: Option<u64>,
+    config: GeneratorConfig,
+}
+
+impl Generator {
+    pub fn new(width: usize, height: usize) -> Result<Self, ParseError> {
+        // Same constraints as a parsed grid, and a solution must exist
+        Grid::empty_solvable(width, height)?;
+
+        Ok(Self {
+            width,
+            height,
+            seed: None,
+            config: GeneratorConfig::default(),
+        })
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn difficulty(mut self, difficulty: Difficulty) -> Self {
+        self.config.difficulty = Some(difficulty);
+        self
+    }
+
+    pub fn config(mut self, config: GeneratorConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn generate(&self) -> Grid {
+        let (mut puzzle, rating) = self.generate_rated();
+
+        // Puzzles remember how they were made
+        let mut metadata = puzzle.metadata().clone().with_difficulty(rating);
+        if let Some(seed) = self.seed {
+            metadata = metadata.with_seed(seed);
+        }
+
+        puzzle.set_metadata(metadata);
+        puzzle
+    }
+
+    fn generate_rated(&self) -> (Grid, Difficulty) {
+        let mut rng = self.rng();
+
+        let Some(target) = self.config.difficulty else {
+            let puzzle = self.candidate(&mut rng);
+            let rating = puzzle.rate().expect("generated puzzles are solvable");
+            return (puzzle, rating);
+        };
+
+        // Small grids cannot reach every difficulty, keep the closest candidate
+        let mut best: Option<(usize, Grid, Difficulty)> = None;
+
+        for _ in 0..MAX_ATTEMPTS {
+            let puzzle = self.candidate(&mut rng);
+            let rating = puzzle.rate().expect("generated puzzles are solvable");
+
+            if rating == target {
+                return (puzzle, rating);
+            }
+
+            let distance = (rating as usize).abs_diff(target as usize);
+
+            if best
+                .as_ref()
+                .is_none_or(|(closest, ..)| distance < *closest)
+            {
+                best = Some((distance, puzzle, rating));
+            }
+        }
+
+        best.map(|(_, puzzle, rating)| (puzzle, rating))
+            .expect("at least one candidate is generated")
+    }
+
+    pub(crate) fn rng(&self) -> Rng {
+        self.seed.map_or_else(Rng::from_time, Rng::new)
+    }
+
+    fn candidate(&self, rng: &mut Rng) -> Grid {
+        // Remove clues in random order, as long as the solution stays unique
+        let mut puzzle = self.complete(rng);
+        let mut cells: Vec<_> = (0..self.height)
+            .flat_map(|i| (0..self.width).map(move |j| Index(i, j)))
+            .collect();
+
+        rng.shuffle(&mut cells);
+
+        // Removal stops short of going under the requested density
+        let exact = self.config.clue_ratio * cells.len() as f32;
+        let min_clues = exact as usize + usize::from((exact as usize as f32) < exact);
+        let mut clues = cells.len();
+
+        for idx in cells {
+            if puzzle[idx].is_none() {
+                continue;
+            }
+
+            // Symmetric clues are removed together
+            let orbit = self.config.symmetry.orbit(idx, self.width, self.height);
+
+            if clues - orbit.len() < min_clues {
+                continue;
+            }
+
+            let orbit = puzzle.take_clues(orbit);
+
+            if self.accepts(&puzzle) {
+                clues -= orbit.len();
+            } else {
+                for (idx, cell) in orbit {
+                    puzzle.set_clue(idx, cell);
+                }
+            }
+        }
+
+        puzzle
+    }
+
+    fn complete(&self, rng: &mut Rng) -> Grid {
+        let grid = Grid::empty_solvable(self.width, self.height)
+            .expect("dimensions are checked on creation")
+            .random_fill(rng)
+            .expect("dimensions are checked on creation");
+
+        // Every cell of the solution starts as a clue
+        let cells: Vec<Vec<GridCell>> = grid
+            .lines()
+            .map(|i| grid.line(i).copied().collect())
+            .collect();
+
+        Grid::from_cells(cells).expect("solution is valid")
+    }
+
+    fn accepts(&self, puzzle: &Grid) -> bool {
+        // The puzzle must keep a single solution, without getting too hard
+        let deducible = || {
+            let config = SolverConfig::new().max_depth(0);
+            puzzle.clone().solve_with(&config).is_ok()
+        };
+
+        // Proving the solution stays unique can take very long on big grids,
+        // the clue is then kept
+        let unique = || {
+            let config = SolverConfig::new().max_nodes(self.width + self.height);
+            puzzle
+                .count_solutions_with(2, &config)
+                .is_ok_and(|count| count == 1)
+        };
+
+        (!self.config.logic_only || deducible())
+            && unique()
+            && self
+                .config
+                .difficulty
+                .is_none_or(|target| puzzle.rate().is_ok_and(|rating| rating <= target))
+    }
+}
+
+impl Grid {
+    pub fn random_complete(width: usize, height: usize, seed: u64) -> Result<Grid, ParseError> {
+        let empty = Grid::empty_solvable(width, height)?;
+
+        empty
+            .random_fill(&mut Rng::new(seed))
+            .map_err(|_| ParseError::UnsolvableDimensions { width, height })
+    }
+
+    // An empty grid, when complete grids of its size exist, which is told
+    // without searching one
+    fn empty_solvable(width: usize, height: usize) -> Result<Grid, ParseError> {
+        let empty = Grid::from_cells(vec![vec![None; width]; height])?;
+
+        // Narrow grids do not have enough different lanes for their length
+        if lane_count(width) < height || lane_count(height) < width {
+            return Err(ParseError::UnsolvableDimensions { width, height });
+        }
+
+        Ok(empty)
+    }
+
+    fn random_fill(&self, rng: &mut Rng) -> Result<Grid, SolveError> {
+        // A search going the wrong way on a big grid can take very long to
+        // get back, it is quicker to start over with other guesses. Most
+        // searches that succeed do so within a node for every other cell,
+        // some restarts get more so that the search still ends on grids
+        // without solution.
+        let nodes = (self.width() * self.height() / 2).max(1);
+
+        (1..)
+            .map(|restart| {
+                // Guessing values in random order lands on a random solution
+                let config = SolverConfig::new()
+                    .seed(rng.next_u64())
+                    .max_nodes(nodes.saturating_mul(luby(restart)));
+                let mut grid = self.clone();
+
+                grid.solve_with(&config).map(|()| grid)
+            })
+            .find(|result| !matches!(result, Err(SolveError::LimitExceeded(Limit::Nodes(_)))))
+            .expect("restarts go on until the search ends")
+    }
+}
+
+// Share of the node limit given to each restart: 1, 1, 2, 1, 1, 2, 4, 1...
+fn luby(mut restart: usize) -> usize {
+    loop {
+        let mut size = 1;
+
+        while size < restart {
+            size = 2 * size + 1;
+        }
+
+        // Ends a block of the sequence, which doubles the last one
+        if size == restart {
+            return size.div_ceil(2);
+        }
+
+        restart -= size / 2;
+    }
+}
+
+// Lanes of the given length with as many zeros as ones and no three identical
+// cells in a row
+fn lane_count(length: usize) -> usize {
+    if length > MAX_COUNTED_LANE {
+        return usize::MAX;
+    }
+
+    // Lanes by number of zeros and ones so far, then by last cell and by the
+    // length of the run it ends: zero once, zero twice, one once, one twice
+    let half = length / 2;
+    let mut counts = vec![vec![[0usize; 4]; half + 1]; half + 1];
+
+    if half > 0 {
+        counts[1][0][0] = 1;
+        counts[0][1][2] = 1;
+    }
+
+    for zeros in 0..=half {
+        for ones in 0..=half {
+            let [zero, zeros_2, one, ones_2] = counts[zeros][ones];
+
+            if zeros < half {
+                let next = &mut counts[zeros + 1][ones];
+                next[0] = next[0].saturating_add(one).saturating_add(ones_2);
+                next[1] = next[1].saturating_add(zero);
+            }
+
+            if ones < half {
+                let next = &mut counts[zeros][ones + 1];
+                next[2] = next[2].saturating_add(zero).saturating_add(zeros_2);
+                next[3] = next[3].saturating_add(one);
+            }
+        }
+    }
+
+    counts[half][half]
+        .iter()
+        .fold(0, |total, count| total.saturating_add(*count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generation() {
+        let puzzle = Generator::new(8, 6).unwrap().seed(42).generate();
+
+        assert_eq!((puzzle.width(), puzzle.height()), (8, 6));
+        assert_eq!(puzzle, Generator::new(8, 6).unwrap().seed(42).generate());
+        assert!(puzzle.givens().count() < 8 * 6);
+        assert!(puzzle.has_unique_solution());
+
+        let mut solution = puzzle.clone();
+        solution.solve().unwrap();
+        assert!(puzzle.verify_solution(&solution).is_ok());
+    }
+
+    #[test]
+    fn difficulties() {
+        for difficulty in [Difficulty::Easy, Difficulty::Medium] {
+            let puzzle = Generator::new(6, 6)
+                .unwrap()
+                .seed(7)
+                .difficulty(difficulty)
+                .generate();
+
+            assert_eq!(puzzle.rate().unwrap(), difficulty);
+        }
+    }
+
+    #[test]
+    fn symmetries() {
+        for symmetry in [
+            Symmetry::Rotational,
+            Symmetry::Horizontal,
+            Symmetry::Vertical,
+        ] {
+            let config = GeneratorConfig::new().symmetry(symmetry);
+            let puzzle = Generator::new(6, 6)
+                .unwrap()
+                .seed(3)
+                .config(config)
+                .generate();
+
+            assert!(puzzle.has_unique_solution());
+            assert!(puzzle.givens().all(|(idx, _)| symmetry
+                .orbit(idx, 6, 6)
+                .into_iter()
+                .all(|other| puzzle[other].is_some())));
+        }
+    }
+
+    #[test]
+    fn clue_ratios() {
+        let config = GeneratorConfig::new().target_clue_ratio(0.5);
+        let puzzle = Generator::new(6, 6)
+            .unwrap()
+            .seed(9)
+            .config(config)
+            .generate();
+
+        assert!(puzzle.givens().count() >= 18);
+        assert!(puzzle.has_unique_solution());
+
+        let config = GeneratorConfig::new().target_clue_ratio(1.0);
+        let puzzle = Generator::new(6, 6)
+            .unwrap()
+            .seed(9)
+            .config(config)
+            .generate();
+
+        assert_eq!(puzzle.givens().count(), 36);
+    }
+
+    #[test]
+    fn logic_only() {
+        let config = GeneratorConfig::new().logic_only(true);
+
+        for seed in 0..4 {
+            let mut puzzle = Generator::new(8, 8)
+                .unwrap()
+                .seed(seed)
+                .config(config.clone())
+                .generate();
+
+            assert!(puzzle.rate().unwrap() <= Difficulty::Medium);
+            assert!(puzzle.solve_with(&SolverConfig::new().max_depth(0)).is_ok());
+        }
+    }
+
+    #[test]
+    fn rectangular() {
+        for (width, height) in [(10, 6), (6, 10)] {
+            let config = GeneratorConfig::new().symmetry(Symmetry::Rotational);
+            let puzzle = Generator::new(width, height)
+                .unwrap()
+                .seed(11)
+                .config(config)
+                .generate();
+
+            assert_eq!((puzzle.width(), puzzle.height()), (width, height));
+            assert!(puzzle.has_unique_solution());
+            assert!(puzzle.rate().is_ok());
+
+            let minimal = puzzle.minimize_with(Symmetry::Rotational);
+            assert_eq!((minimal.width(), minimal.height()), (width, height));
+            assert!(minimal.has_unique_solution());
+
+            let mut solution = puzzle.clone();
+            solution.solve().unwrap();
+            assert!(puzzle.verify_solution(&solution).is_ok());
+        }
+    }
+
+    #[test]
+    fn complete_grids() {
+        let grid = Grid::random_complete(10, 6, 5).unwrap();
+
+        assert_eq!((grid.width(), grid.height()), (10, 6));
+        assert!(grid.empty_cells().next().is_none());
+        assert!(grid.is_valid().is_ok());
+        assert_eq!(grid, Grid::random_complete(10, 6, 5).unwrap());
+        assert_ne!(grid, Grid::random_complete(10, 6, 6).unwrap());
+
+        assert!(Grid::random_complete(3, 4, 5).is_err());
+        assert!(matches!(
+            Grid::random_complete(4, 12, 5),
+            Err(ParseError::UnsolvableDimensions {
+                width: 4,
+                height: 12
+            })
+        ));
+    }
+
+    #[test]
+    fn invalid_dimensions() {
+        assert!(matches!(
+            Generator::new(5, 6),
+            Err(ParseError::OddDimension { .. })
+        ));
+        assert!(matches!(
+            Generator::new(4, 8),
+            Err(ParseError::UnsolvableDimensions { .. })
+        ));
+
+        let counts: Vec<_> = (1..=5).map(|half| lane_count(2 * half)).collect();
+        assert_eq!(counts, vec![2, 6, 14, 34, 84]);
+
+        // Big grids are filled without waiting on an unlucky search
+        for size in [34, 36] {
+            let grid = Grid::random_complete(size, size, 0).unwrap();
+            assert!(grid.empty_cells().next().is_none());
+        }
+    }
+}
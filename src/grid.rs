@@ -1,330 +1,1345 @@
-use std::collections::HashMap;
-use std::fmt;
-use std::ops;
-
+use alloc::collections::btree_map::Entry;
+use alloc::collections::BTreeMap;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::ops;
+
+use crate::bits::{BitSet, LaneBits};
 use crate::cell::*;
-use crate::error::GridError;
+use crate::display::DisplayOptions;
+use crate::error::{ParseError, Rule, SolveError, VerificationError, Violation};
 use crate::index::*;
+use crate::journal::Journal;
+use crate::metadata::Metadata;
+use crate::parse::{self, ParseOptions};
+use crate::region::Regions;
+use crate::rules::Rules;
+use crate::sign::Sign;
+use crate::solver::{self, SolveStats, Solver, SolverConfig};
+
+pub(crate) type GridCell = Option<Cell>;
+
+// Cells are packed on 2 bits in row-major order: empty, zero or one
+const CELL_BITS: usize = 2;
+const CELL_MASK: u64 = (1 << CELL_BITS) - 1;
+const CELLS_PER_WORD: usize = u64::BITS as usize / CELL_BITS;
+
+static CELL_VALUES: [GridCell; 3] = [None, Some(Cell::Zero), Some(Cell::One)];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Histogram([usize; 2]);
+
+// One bit per cell of a lane: whether it is filled, whether it is a one, and
+// whether it is a hole outside of the puzzle; then the count of ones given by
+// a margin clue, if any. Grids keep lanes of any length, fixed-size grids
+// lanes that fit in a word.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct LaneMask<B = BitSet> {
+    filled: B,
+    ones: B,
+    holes: B,
+    target: Option<usize>,
+}
+
+// What sets a grid apart from a plain rectangle: the cells left out of the
+// puzzle, and the counts of ones given in the margins
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Shape {
+    pub(crate) holes: Vec<Index>,
+    pub(crate) line_targets: Vec<Option<usize>>,
+    pub(crate) column_targets: Vec<Option<usize>>,
+}
 
-type Histogram = HashMap<Cell, usize>;
-type GridCell = Option<Cell>;
+// Number of complete lanes with a given pattern of holes and ones
+#[derive(Clone, Debug, Default)]
+struct Patterns(BTreeMap<(BitSet, BitSet), usize>);
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Debug)]
 pub struct Grid {
-    cells: Vec<Vec<GridCell>>,
+    cells: Vec<u64>,
+    givens: Vec<u64>,
+    pub(crate) line_counts: Vec<Histogram>,
+    pub(crate) column_counts: Vec<Histogram>,
+    line_masks: Vec<LaneMask>,
+    column_masks: Vec<LaneMask>,
+    line_patterns: Patterns,
+    column_patterns: Patterns,
+    // Signs of Binairo+ puzzles, from the top or left cell to its neighbor
+    signs: BTreeMap<(Index, Index), Sign>,
+    pub(crate) regions: Regions,
+    // Pencil marks of the player, left out of comparisons like the states
+    pub(crate) marks: BTreeMap<Index, CellSet>,
+    // Edits of the player that can be undone, once recording them is asked
+    pub(crate) journal: Option<Journal>,
+    rules: Rules,
     width: usize,
     height: usize,
+    metadata: Metadata,
 }
 
 impl Grid {
-    pub fn parse<I, S>(lines: I) -> Result<Grid, GridError>
+    pub fn parse<I, S>(lines: I) -> Result<Grid, ParseError>
     where
         I: Iterator<Item = S>,
         S: AsRef<str>,
     {
-        let mut grid = Grid {
-            cells: Vec::new(),
-            height: 0,
-            width: 0,
-        };
+        Self::parse_with(lines, &ParseOptions::default())
+    }
+
+    pub fn parse_with<I, S>(lines: I, options: &ParseOptions) -> Result<Grid, ParseError>
+    where
+        I: Iterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut cells: Vec<Vec<GridCell>> = Vec::new();
+        let mut metadata = Metadata::default();
+        let mut signs = Vec::new();
+        let mut regions = Vec::new();
+        let mut holes = Vec::new();
+        let mut line_targets = Vec::new();
+        let mut column_targets = None;
+        // Where the cells of the last line are, for the signs below them
+        let mut positions = Vec::new();
+        // Line of the input the regions start at
+        let mut regions_line = 0;
+
+        // Fill grid with parsed lines, counting them from 1 for errors
+        for (n, line) in parse::split_lines(lines)
+            .enumerate()
+            .map(|(n, line)| (n + 1, line))
+        {
+            if let Some(header) = options.metadata(&line) {
+                metadata.set(header);
+                continue;
+            }
 
-        // Fill grid with parsed lines
-        for line in lines {
-            let vec = line
-                .as_ref()
+            let content = options.normalize(options.strip_comment(&line));
+            let content = content.as_ref();
+
+            // Grids with margins start with the count of ones of each column
+            if options.has_margins() && column_targets.is_none() && !content.trim().is_empty() {
+                let targets = Self::tokens(content)
+                    .map(|(column, token)| Self::parse_target(token, options, (n, column)));
+                column_targets = Some((n, targets.collect::<Result<Vec<_>, _>>()?));
+                continue;
+            }
+
+            if Self::is_sign_line(content, options) {
+                for (p, c) in content
+                    .chars()
+                    .enumerate()
+                    .filter(|(_, c)| !c.is_whitespace())
+                {
+                    let i = cells.len();
+                    let j = positions
+                        .iter()
+                        .position(|q| *q == p)
+                        .filter(|_| i > 0)
+                        .ok_or(ParseError::invalid_char(c).at(n, p + 1))?;
+
+                    signs.push((Index(i - 1, j), Index(i, j), c, (n, p + 1)));
+                }
+
+                continue;
+            }
+
+            // Lines of region labels follow the cells
+            if Self::is_region_line(content, options) || !regions.is_empty() {
+                if regions.is_empty() {
+                    regions_line = n - 1;
+                }
+
+                regions.push(String::from(content));
+                continue;
+            }
+
+            // Then each line starts with its own count, blanked out so that the
+            // cells keep their positions
+            let blanked;
+            let mut target = None;
+            let content = match Self::tokens(content).next() {
+                Some((column, token)) if options.has_margins() => {
+                    target = Self::parse_target(token, options, (n, column))?;
+                    blanked = content.replacen(token, &" ".repeat(token.chars().count()), 1);
+                    blanked.as_str()
+                }
+                _ => content,
+            };
+
+            let mut vec = Vec::new();
+            let mut line_positions = Vec::new();
+            let mut pending = None;
+
+            for (p, c) in content
                 .chars()
-                .take_while(|c| *c != '#')
-                .filter(|c| !c.is_whitespace())
-                .map(|c| match c {
-                    '-' => Ok(None),
-                    _ => Cell::try_from(c).map(Some),
-                })
-                .collect::<Result<Vec<_>, _>>()?;
+                .enumerate()
+                .filter(|(_, c)| !c.is_whitespace())
+            {
+                // Holes take the place of a cell outside of the puzzle
+                let hole = options.is_hole_char(c);
+
+                match options
+                    .cell(c)
+                    .or_else(|err| if hole { Ok(None) } else { Err(err) })
+                    .map_err(|err| err.at(n, p + 1))
+                {
+                    Ok(cell) => {
+                        let idx = Index(cells.len(), vec.len());
+
+                        // A sign between this cell and the previous one
+                        if let (Some((sign, at)), Some(previous)) =
+                            (pending.take(), idx.checked_col(-1))
+                        {
+                            signs.push((previous, idx, sign, at));
+                        }
+
+                        if hole {
+                            holes.push(idx);
+                        }
+
+                        vec.push(cell);
+                        line_positions.push(p);
+
+                        // Stop reading as soon as the grid is too large
+                        let width = cells.first().map_or(0, Vec::len).max(vec.len());
+                        options.check_size(width, cells.len() + 1)?;
+                    }
+                    Err(_) if Sign::from_char(c).is_some() && !vec.is_empty() => {
+                        if pending.replace((c, (n, p + 1))).is_some() {
+                            return Err(ParseError::invalid_char(c).at(n, p + 1));
+                        }
+                    }
+                    Err(err) => return Err(err),
+                }
+            }
+
+            if let Some((c, (line, column))) = pending {
+                return Err(ParseError::invalid_char(c).at(line, column));
+            }
 
             if !vec.is_empty() {
-                if grid.cells.is_empty() {
-                    // Set width of the grid
-                    if (vec.len() % 2) != 0 {
-                        return Err(GridError::OddDimension);
+                if let Some(first) = cells.first() {
+                    if vec.len() != first.len() {
+                        return Err(ParseError::WidthMismatch {
+                            line: n,
+                            expected: first.len(),
+                            found: vec.len(),
+                        });
                     }
+                }
+
+                cells.push(vec);
+                line_targets.push(target);
+                positions = line_positions;
+            }
+        }
 
-                    grid.width = vec.len();
-                } else if vec.len() != grid.width {
-                    return Err(GridError::WidthMismatch);
+        let (targets_line, column_targets) = column_targets.unwrap_or_default();
+        let width = cells.first().map_or(0, Vec::len);
+
+        if options.has_margins() && column_targets.len() != width {
+            return Err(ParseError::WidthMismatch {
+                line: targets_line,
+                expected: width,
+                found: column_targets.len(),
+            });
+        }
+
+        let shape = Shape {
+            holes,
+            line_targets,
+            column_targets,
+        };
+
+        let mut grid = Self::from_cells_with_shape(cells, &shape, options.grid_rules())?;
+        grid.metadata = metadata;
+
+        if !regions.is_empty() {
+            grid.parse_regions(regions.into_iter())
+                .map_err(|err| err.below(regions_line))?;
+        }
+
+        for (a, b, c, (line, column)) in signs {
+            // Signs below the last line have no cell to go to
+            if b.0 >= grid.height {
+                return Err(ParseError::invalid_char(c).at(line, column));
+            }
+
+            grid.set_sign(a, b, Sign::from_char(c));
+        }
+
+        // The cells were checked, only the signs are left
+        grid.check_signs(&grid).map_err(ParseError::InvalidGrid)?;
+        Ok(grid)
+    }
+
+    // A count of ones in the margin, or an empty cell when there is none
+    fn parse_target(
+        token: &str,
+        options: &ParseOptions,
+        (line, column): (usize, usize),
+    ) -> Result<Option<usize>, ParseError> {
+        match token.parse() {
+            Ok(count) => Ok(Some(count)),
+            Err(_) if token.chars().all(|c| options.is_empty_char(c)) => Ok(None),
+            Err(_) => {
+                let (k, c) = token
+                    .chars()
+                    .enumerate()
+                    .find(|(_, c)| !c.is_ascii_digit())
+                    .unwrap_or((0, ' '));
+                Err(ParseError::invalid_char(c).at(line, column + k + 1))
+            }
+        }
+    }
+
+    // Words of a line with the column they start at, counting from 0
+    fn tokens(content: &str) -> impl Iterator<Item = (usize, &str)> + '_ {
+        let mut start = None;
+
+        content
+            .char_indices()
+            .chain([(content.len(), ' ')])
+            .enumerate()
+            .filter_map(move |(column, (k, c))| match (c.is_whitespace(), start) {
+                (false, None) => {
+                    start = Some((column, k));
+                    None
+                }
+                (true, Some((column, s))) => {
+                    start = None;
+                    Some((column, &content[s..k]))
                 }
+                _ => None,
+            })
+    }
 
-                grid.cells.push(vec);
+    // Lines only made of signs go between two lines of cells
+    fn is_sign_line(content: &str, options: &ParseOptions) -> bool {
+        let mut chars = content.chars().filter(|c| !c.is_whitespace()).peekable();
+
+        chars.peek().is_some()
+            && chars.all(|c| options.cell(c).is_err() && Sign::from_char(c).is_some())
+    }
+
+    // Letters and digits which are not cells label regions, unless they
+    // stand for a cell in another script
+    fn is_region_line(content: &str, options: &ParseOptions) -> bool {
+        let mut chars = content.chars().filter(|c| !c.is_whitespace()).peekable();
+        let is_cell = |c| options.cell(c).is_ok();
+
+        chars.peek().is_some()
+            && chars.all(|c| {
+                c.is_alphanumeric() && !is_cell(c) && !parse::ascii_variant(c).is_some_and(is_cell)
+            })
+    }
+
+    pub(crate) fn from_cells(cells: Vec<Vec<GridCell>>) -> Result<Grid, ParseError> {
+        Self::from_cells_with(cells, Rules::default())
+    }
+
+    pub(crate) fn from_cells_with(
+        cells: Vec<Vec<GridCell>>,
+        rules: Rules,
+    ) -> Result<Grid, ParseError> {
+        Self::from_cells_with_shape(cells, &Shape::default(), rules)
+    }
+
+    pub(crate) fn from_cells_with_shape(
+        cells: Vec<Vec<GridCell>>,
+        shape: &Shape,
+        rules: Rules,
+    ) -> Result<Grid, ParseError> {
+        let Shape {
+            holes,
+            line_targets,
+            column_targets,
+        } = shape;
+
+        let height = cells.len();
+        let width = cells.first().map_or(0, Vec::len);
+
+        if height == 0 || width == 0 {
+            return Err(ParseError::EmptyGrid);
+        } else if let Some(i) = cells.iter().position(|line| line.len() != width) {
+            return Err(ParseError::WidthMismatch {
+                line: i + 1,
+                expected: width,
+                found: cells[i].len(),
+            });
+        }
+
+        // Lanes cut by holes only hold their share of each value in the
+        // other cells, and margin clues replace that share
+        let target = |targets: &[Option<usize>], k: usize| targets.get(k).copied().flatten();
+        let lines = (0..height).map(|i| {
+            let size = width - holes.iter().filter(|idx| idx.0 == i).count();
+            (size, target(line_targets, i))
+        });
+        let columns = (0..width).map(|j| {
+            let size = height - holes.iter().filter(|idx| idx.1 == j).count();
+            (size, target(column_targets, j))
+        });
+        let mut lanes = lines.chain(columns);
+        let lane = |k: usize| {
+            if k < height {
+                Lane::Line(k)
+            } else {
+                Lane::Column(k - height)
             }
+        };
+
+        if let Some(k) = lanes
+            .clone()
+            .position(|(size, target)| target.is_none() && !rules.fits(size))
+        {
+            let (cells, _) = lanes.nth(k).expect("the lane was just found");
+            let full = if k < height { width } else { height };
+
+            // Lanes only unbalanced by their holes are named, and halves are
+            // the ratio of standard puzzles
+            return Err(match rules.ones_ratio() {
+                _ if cells < full && rules.fits(full) => ParseError::UnbalancedLane {
+                    lane: lane(k),
+                    cells,
+                },
+                (1, 2) => ParseError::OddDimension { width, height },
+                _ => ParseError::RatioMismatch { width, height },
+            });
+        } else if rules.has_diagonals() && width != height {
+            return Err(ParseError::Unsupported("diagonals without being square"));
+        } else if let Some(k) =
+            lanes.position(|(size, target)| target.is_some_and(|ones| ones > size))
+        {
+            let violation = Violation::new(Rule::Balance, vec![lane(k)], Vec::new());
+            return Err(ParseError::InvalidGrid(violation));
+        }
+
+        let mut grid = Grid::with_size(width, height);
+        grid.rules = rules;
+
+        for (i, mask) in grid.line_masks.iter_mut().enumerate() {
+            mask.target = target(line_targets, i);
         }
 
-        // Set height of the grid
-        grid.height = grid.cells.len();
+        for (j, mask) in grid.column_masks.iter_mut().enumerate() {
+            mask.target = target(column_targets, j);
+        }
 
-        if grid.height == 0 {
-            return Err(GridError::EmptyGrid);
-        } else if (grid.height % 2) != 0 {
-            return Err(GridError::OddDimension);
+        for (i, line) in cells.into_iter().enumerate() {
+            for (j, cell) in line.into_iter().enumerate() {
+                // Every filled cell of the input is a clue
+                grid.set((i, j), cell);
+                grid.set_given(Index(i, j), cell.is_some());
+            }
+        }
+
+        for idx in holes {
+            grid.set_hole(*idx, true);
+            grid.set_given(*idx, false);
         }
 
         // Check if the grid is valid
-        grid.is_valid()?;
+        grid.is_valid().map_err(ParseError::InvalidGrid)?;
 
         Ok(grid)
     }
 
-    pub fn solve(&mut self) -> Result<(), GridError> {
-        loop {
-            loop {
-                // Fill grid with constraints
-                if !self.fill_constraints() {
-                    break;
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    pub fn set_metadata(&mut self, metadata: Metadata) {
+        self.metadata = metadata;
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn rules(&self) -> Rules {
+        self.rules
+    }
+
+    // Cells are kept as they are, even when they break the new rules
+    pub fn set_rules(&mut self, rules: Rules) {
+        self.rules = rules;
+    }
+
+    pub fn sign<I, J>(&self, a: I, b: J) -> Option<Sign>
+    where
+        I: Into<Index>,
+        J: Into<Index>,
+    {
+        let (a, b) = (a.into(), b.into());
+        self.signs.get(&(a.min(b), a.max(b))).copied()
+    }
+
+    // Put a sign between two adjacent cells, or remove it
+    pub fn set_sign<I, J>(&mut self, a: I, b: J, sign: Option<Sign>)
+    where
+        I: Into<Index>,
+        J: Into<Index>,
+    {
+        let (a, b) = (a.into(), b.into());
+        let (first, second) = (a.min(b), a.max(b));
+
+        assert!(
+            second.0 < self.height
+                && second.1 < self.width
+                && (first.checked_line(1) == Some(second) || first.checked_col(1) == Some(second)),
+            "signs go between two adjacent cells"
+        );
+
+        match sign {
+            Some(sign) => self.signs.insert((first, second), sign),
+            None => self.signs.remove(&(first, second)),
+        };
+    }
+
+    pub fn signs(&self) -> impl Iterator<Item = (Index, Index, Sign)> + '_ {
+        self.signs.iter().map(|((a, b), sign)| (*a, *b, *sign))
+    }
+
+    // Cells joined to a cell by a sign
+    fn linked(&self, idx: Index) -> impl Iterator<Item = (Index, Sign)> + '_ {
+        idx.neighbors()
+            .filter(|other| other.0 < self.height && other.1 < self.width)
+            .filter_map(move |other| self.sign(idx, other).map(|sign| (other, sign)))
+    }
+
+    // Whether the values of both cells of every sign agree with it
+    fn check_signs(&self, grid: &Grid) -> Result<(), Violation> {
+        let broken = self
+            .signs()
+            .find(|(a, b, sign)| match (grid[*a], grid[*b]) {
+                (Some(x), Some(y)) => sign.forced(x) != y,
+                _ => false,
+            });
+
+        if let Some((a, b, _)) = broken {
+            return Err(Violation::new(Rule::Sign, Vec::new(), vec![a, b]));
+        }
+
+        Ok(())
+    }
+
+    pub fn rotate90(&self) -> Grid {
+        // Rotate clockwise: first column becomes the first line
+        self.remap(self.height, self.width, |Index(i, j)| {
+            Index(self.height - 1 - j, i)
+        })
+    }
+
+    pub fn mirror_horizontal(&self) -> Grid {
+        // Reverse the order of the cells in each line
+        self.remap(self.width, self.height, |Index(i, j)| {
+            Index(i, self.width - 1 - j)
+        })
+    }
+
+    pub fn mirror_vertical(&self) -> Grid {
+        // Reverse the order of the lines
+        self.remap(self.width, self.height, |Index(i, j)| {
+            Index(self.height - 1 - i, j)
+        })
+    }
+
+    pub fn transpose(&self) -> Grid {
+        self.remap(self.height, self.width, |Index(i, j)| Index(j, i))
+    }
+
+    pub fn complement(&self) -> Grid {
+        let mut grid = self.clone();
+
+        for i in self.lines() {
+            for j in self.columns() {
+                grid.set((i, j), self[(i, j)].map(|cell| !cell));
+            }
+        }
+
+        grid
+    }
+
+    pub fn canonical_form(&self) -> Grid {
+        // Smallest grid among all rotations, reflections and their complements
+        self.variants()
+            .into_iter()
+            .min_by_key(Self::sort_key)
+            .unwrap_or_else(|| self.clone())
+    }
+
+    pub fn is_isomorphic_to(&self, other: &Grid) -> bool {
+        self.canonical_form() == other.canonical_form()
+    }
+
+    pub fn solve(&mut self) -> Result<(), SolveError> {
+        self.solve_with(&SolverConfig::default())
+    }
+
+    pub fn solve_with(&mut self, config: &SolverConfig) -> Result<(), SolveError> {
+        self.solve_with_stats(config).map(|_| ())
+    }
+
+    pub fn solve_with_stats(&mut self, config: &SolverConfig) -> Result<SolveStats, SolveError> {
+        if let Some(rules) = config.grid_rules() {
+            self.rules = rules;
+        }
+
+        solver::solve(self, config)
+    }
+
+    pub fn count_solutions(&self, limit: usize) -> Result<usize, SolveError> {
+        self.count_solutions_with(limit, &SolverConfig::default())
+    }
+
+    // Count within the search limits of a configuration
+    pub fn count_solutions_with(
+        &self,
+        limit: usize,
+        config: &SolverConfig,
+    ) -> Result<usize, SolveError> {
+        let mut grid = self.clone();
+
+        if let Some(rules) = config.grid_rules() {
+            grid.rules = rules;
+        }
+
+        grid.is_valid().map_err(SolveError::InvalidGrid)?;
+        Solver::new(&mut grid, config).count(limit)
+    }
+
+    pub fn has_unique_solution(&self) -> bool {
+        self.count_solutions(2).is_ok_and(|count| count == 1)
+    }
+
+    #[cfg(feature = "parallel")]
+    pub fn solve_portfolio(&mut self, configs: &[SolverConfig]) -> Result<(), SolveError> {
+        use rayon::prelude::*;
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        self.is_valid().map_err(SolveError::InvalidGrid)?;
+
+        // Race every configuration, the first solution cancels the others
+        let found = Arc::new(AtomicBool::new(false));
+        let results: Vec<_> = configs
+            .par_iter()
+            .map(|config| {
+                let mut grid = self.clone();
+                grid.rules = config.grid_rules().unwrap_or(grid.rules);
+
+                Solver::new(&mut grid, config)
+                    .cancel_with(found.clone())
+                    .search()?;
+
+                found.store(true, Ordering::Relaxed);
+                Ok(grid)
+            })
+            .collect();
+
+        // Report limits only when no configuration succeeded, or else where
+        // one got stuck
+        let mut error = SolveError::NoSolution(None);
+
+        for result in results {
+            match result {
+                Ok(grid) => {
+                    *self = grid;
+                    return Ok(());
+                }
+                Err(err @ SolveError::LimitExceeded(_)) => error = err,
+                Err(err @ SolveError::NoSolution(Some(_)))
+                    if matches!(error, SolveError::NoSolution(None)) =>
+                {
+                    error = err
                 }
+                Err(_) => {}
             }
+        }
+
+        Err(error)
+    }
+
+    pub fn is_move_valid<I>(&self, idx: I, cell: Cell) -> bool
+    where
+        I: Into<Index>,
+    {
+        let idx = idx.into();
 
-            // Fill grid with heuristics
-            if !self.fill_heuristics() {
-                break;
+        !self.is_hole(idx) && self.check_move(idx, cell).is_ok()
+    }
+
+    // Holes are outside of the puzzle, and never filled
+    pub fn is_hole<I>(&self, idx: I) -> bool
+    where
+        I: Into<Index>,
+    {
+        let Index(i, j) = idx.into();
+        self.line_masks[i].is_hole(j)
+    }
+
+    // Count of ones a margin clue gives to a lane, which then does not need
+    // to hold its share of each value
+    pub fn target(&self, lane: Lane) -> Option<usize> {
+        match lane {
+            Lane::Line(i) => self.line_masks[i].target,
+            Lane::Column(j) => self.column_masks[j].target,
+        }
+    }
+
+    pub(crate) fn has_targets(&self) -> bool {
+        self.targets().any(|target| target.is_some())
+    }
+
+    // Margin clues of the lines, then of the columns
+    fn targets(&self) -> impl Iterator<Item = Option<usize>> + '_ {
+        let masks = self.line_masks.iter().chain(&self.column_masks);
+        masks.map(|mask| mask.target)
+    }
+
+    // Most cells of a value a lane can hold, whatever sets it
+    pub(crate) fn lane_max(&self, lane: Lane, cell: Cell) -> usize {
+        match lane {
+            Lane::Line(i) => self.line_masks[i].max_count(self.width, cell, &self.rules),
+            Lane::Column(j) => self.column_masks[j].max_count(self.height, cell, &self.rules),
+        }
+    }
+
+    pub fn holes(&self) -> impl Iterator<Item = Index> + '_ {
+        self.lines().flat_map(move |i| {
+            self.columns()
+                .filter(move |j| self.line_masks[i].is_hole(*j))
+                .map(move |j| Index(i, j))
+        })
+    }
+
+    pub fn state<I>(&self, idx: I) -> Option<CellState>
+    where
+        I: Into<Index>,
+    {
+        let idx = idx.into();
+
+        self[idx].map(|_| {
+            if self.is_given(idx) {
+                CellState::Given
+            } else {
+                CellState::Derived
             }
+        })
+    }
+
+    pub fn givens(&self) -> impl Iterator<Item = (Index, Cell)> + '_ {
+        self.lines().flat_map(move |i| {
+            self.columns().filter_map(move |j| {
+                self.is_given(Index(i, j))
+                    .then_some(self[(i, j)])
+                    .flatten()
+                    .map(|cell| (Index(i, j), cell))
+            })
+        })
+    }
+
+    pub fn verify_solution(&self, candidate: &Grid) -> Result<(), VerificationError> {
+        if candidate.width != self.width || candidate.height != self.height {
+            return Err(VerificationError::DimensionMismatch);
         }
 
-        // Check that grid is still valid
-        self.is_valid()?;
+        // Check that the candidate is complete and follows the rules
+        if let Some(idx) = candidate.empty_cells().next() {
+            return Err(VerificationError::Incomplete(idx));
+        }
+
+        candidate
+            .is_valid()
+            .and_then(|_| self.check_signs(candidate))
+            .map_err(VerificationError::InvalidGrid)?;
 
-        // Bruteforce remaining empty cells
-        self.get_empty()
-            .map(|idx| self.fill_bruteforce(idx))
+        // Check that the clues have been kept
+        self.givens()
+            .find(|(idx, cell)| candidate[*idx] != Some(*cell))
+            .map(|(idx, _)| Err(VerificationError::ClueMismatch(idx)))
             .unwrap_or(Ok(()))
     }
 
-    fn is_valid(&self) -> Result<(), GridError> {
-        for i in self.lines() {
-            // Check lane
-            let lane: Vec<_> = self.line(i).collect();
-            Self::check_lane(lane.iter().copied())?;
+    fn remap<F>(&self, width: usize, height: usize, f: F) -> Grid
+    where
+        F: Fn(Index) -> Index,
+    {
+        let mut grid = Grid::with_size(width, height);
+
+        // Copy each cell from its source index
+        for i in 0..height {
+            for j in 0..width {
+                let source = f(Index(i, j));
 
-            // Check pair of lanes
-            for i_pair in i + 1..self.height {
-                Self::check_pair(lane.iter().copied().zip(self.line(i_pair)))?;
+                grid.set_hole(Index(i, j), self.is_hole(source));
+                grid.set((i, j), self[source]);
+                grid.set_given(Index(i, j), self.is_given(source));
             }
         }
 
-        for j in self.columns() {
-            // Check lane
-            let lane: Vec<_> = self.column(j).collect();
-            Self::check_lane(lane.iter().copied())?;
+        // Margin clues follow the lanes they count, which are lines of the
+        // source when their first cells are on the same line
+        let source = |a: Index, b: Index| {
+            if a.0 == b.0 {
+                self.target(Lane::Line(a.0))
+            } else {
+                self.target(Lane::Column(a.1))
+            }
+        };
 
-            // Check pair of lanes
-            for j_pair in j + 1..self.width {
-                Self::check_pair(lane.iter().copied().zip(self.column(j_pair)))?;
+        for i in 0..height {
+            grid.line_masks[i].target = source(f(Index(i, 0)), f(Index(i, width.min(2) - 1)));
+        }
+
+        for j in 0..width {
+            grid.column_masks[j].target = source(f(Index(0, j)), f(Index(height.min(2) - 1, j)));
+        }
+
+        // So do marks
+        for i in 0..height {
+            for j in 0..width {
+                if let Some(marks) = self.marks(f(Index(i, j))) {
+                    grid.set_marks((i, j), Some(marks));
+                }
             }
         }
 
-        Ok(())
+        // Regions follow their cells
+        if !self.regions.is_empty() {
+            let labels = (0..height)
+                .flat_map(|i| (0..width).map(move |j| Index(i, j)))
+                .filter_map(|idx| self.region(f(idx)))
+                .collect();
+
+            grid.regions = Regions::new(labels, width);
+        }
+
+        // Signs follow the cells they are between
+        if !self.signs.is_empty() {
+            let targets: BTreeMap<Index, Index> = (0..height)
+                .flat_map(|i| (0..width).map(move |j| Index(i, j)))
+                .map(|idx| (f(idx), idx))
+                .collect();
+
+            for (a, b, sign) in self.signs() {
+                grid.set_sign(targets[&a], targets[&b], Some(sign));
+            }
+        }
+
+        // Transformed grids are still the same puzzle
+        grid.metadata = self.metadata.clone();
+        grid.rules = self.rules;
+        grid
     }
 
-    fn get_empty(&self) -> Option<Index> {
-        self.lines()
-            .find_map(|i| (0..self.width).find_map(|j| self[(i, j)].is_none().then(|| Index(i, j))))
+    pub(crate) fn with_size(width: usize, height: usize) -> Grid {
+        let size = width * height;
+
+        Grid {
+            cells: vec![0; size.div_ceil(CELLS_PER_WORD)],
+            givens: vec![0; size.div_ceil(u64::BITS as usize)],
+            line_counts: vec![Histogram::default(); height],
+            column_counts: vec![Histogram::default(); width],
+            line_masks: vec![LaneMask::default(); height],
+            column_masks: vec![LaneMask::default(); width],
+            line_patterns: Patterns::default(),
+            column_patterns: Patterns::default(),
+            signs: BTreeMap::new(),
+            regions: Regions::default(),
+            marks: BTreeMap::new(),
+            journal: None,
+            rules: Rules::default(),
+            width,
+            height,
+            metadata: Metadata::default(),
+        }
     }
 
-    fn fill_constraints(&mut self) -> bool {
-        let mut changed = false;
+    fn offset(&self, idx: Index) -> usize {
+        idx.0 * self.width + idx.1
+    }
 
-        // Process lines
-        for i in self.lines() {
-            let saturated = Self::fill_saturated(self.line(i));
+    fn is_given(&self, idx: Index) -> bool {
+        let offset = self.offset(idx);
+        let bits = u64::BITS as usize;
 
-            for j in self.columns() {
-                let idx = Index(i, j);
-
-                if self[idx].is_none() {
-                    // If a line is already saturated, fill it with the opposite value
-                    let new = saturated
-                        .or_else(|| {
-                            // Or check 2 previous cells
-                            (j >= 2)
-                                .then(|| Self::fill_cell(self[idx.col(-2)], self[idx.col(-1)]))
-                                .flatten()
-                        })
-                        .or_else(|| {
-                            // Or check 2 next cells
-                            (j + 2 < self.width)
-                                .then(|| Self::fill_cell(self[idx.col(1)], self[idx.col(2)]))
-                                .flatten()
-                        })
-                        .or_else(|| {
-                            // Or check 2 surrounding cells
-                            (j >= 1 && j + 1 < self.width)
-                                .then(|| Self::fill_cell(self[idx.col(-1)], self[idx.col(1)]))
-                                .flatten()
-                        });
+        (self.givens[offset / bits] >> (offset % bits)) & 1 != 0
+    }
 
-                    changed |= self.set(idx, new);
-                }
-            }
+    // A filled cell breaking a rule with its current value
+    pub(crate) fn is_conflict(&self, idx: Index) -> bool {
+        self[idx].is_some_and(|cell| self.check_move(idx, cell).is_err())
+    }
+
+    // Clues are exactly the filled cells of a puzzle
+    pub(crate) fn set_clue(&mut self, idx: Index, cell: GridCell) {
+        self.set(idx, cell);
+        self.set_given(idx, cell.is_some());
+    }
+
+    // Remove clues, returning them so they can be restored
+    pub(crate) fn take_clues(&mut self, cells: Vec<Index>) -> Vec<(Index, GridCell)> {
+        cells
+            .into_iter()
+            .map(|idx| {
+                let cell = self[idx];
+                self.set_clue(idx, None);
+                (idx, cell)
+            })
+            .collect()
+    }
+
+    fn set_given(&mut self, idx: Index, given: bool) {
+        let offset = self.offset(idx);
+        let bits = u64::BITS as usize;
+        let mask = 1 << (offset % bits);
+
+        if given {
+            self.givens[offset / bits] |= mask;
+        } else {
+            self.givens[offset / bits] &= !mask;
         }
+    }
 
-        // Process columns
-        for j in self.columns() {
-            let saturated = Self::fill_saturated(self.column(j));
-
-            for i in self.lines() {
-                let idx = Index(i, j);
-
-                if self[idx].is_none() {
-                    // If a line is already saturated, fill it with the opposite value
-                    let new = saturated
-                        .or_else(|| {
-                            // Or check 2 previous cells
-                            (i >= 2)
-                                .then(|| Self::fill_cell(self[idx.line(-2)], self[idx.line(-1)]))
-                                .flatten()
-                        })
-                        .or_else(|| {
-                            // Or check 2 next cells
-                            (i + 2 < self.height)
-                                .then(|| Self::fill_cell(self[idx.line(1)], self[idx.line(2)]))
-                                .flatten()
-                        })
-                        .or_else(|| {
-                            // Or check 2 surrounding cells
-                            (i >= 1 && i + 1 < self.height)
-                                .then(|| Self::fill_cell(self[idx.line(-1)], self[idx.line(1)]))
-                                .flatten()
-                        });
+    pub(crate) fn variants(&self) -> Vec<Grid> {
+        let mut variants = Vec::new();
+        let mut grid = self.clone();
+
+        // Four rotations, each with its mirror image
+        for _ in 0..4 {
+            variants.push(grid.mirror_horizontal());
+            variants.push(grid.clone());
+            grid = grid.rotate90();
+        }
+
+        // And the same with values swapped
+        let complements: Vec<_> = variants.iter().map(Grid::complement).collect();
+        variants.extend(complements);
+
+        variants
+    }
+
+    fn sort_key(&self) -> (usize, usize, Vec<u8>) {
+        let cells = self
+            .lines()
+            .flat_map(|i| self.line(i))
+            .map(|cell| match cell {
+                None => 0,
+                Some(Cell::Zero) => 1,
+                Some(Cell::One) => 2,
+            })
+            .collect();
+
+        (self.height, self.width, cells)
+    }
 
-                    changed |= self.set(idx, new);
+    pub fn is_valid(&self) -> Result<(), Violation> {
+        let rules = &self.rules;
+        let lanes = self.lines().map(Lane::Line);
+
+        for lane in lanes.chain(self.columns().map(Lane::Column)) {
+            let (mask, map, size) = match lane {
+                Lane::Line(i) => (&self.line_masks[i], &self.line_counts[i], self.width),
+                Lane::Column(j) => (&self.column_masks[j], &self.column_counts[j], self.height),
+            };
+
+            Self::check_mask(mask, map, size, rules).map_err(|rule| {
+                let cells = (0..size).map(|k| lane.index(k)).collect();
+                let max = |cell| mask.max_count(size, cell, rules);
+
+                Violation::new(rule, vec![lane], self.culprits(cells, rule, rules, max))
+            })?;
+        }
+
+        // Only complete lanes can be identical
+        if rules.is_unique(Lane::Line(0)) && self.line_patterns.has_duplicates() {
+            let (a, b) = Self::duplicates(&self.line_masks, self.width);
+            return Err(self.identical(Lane::Line(a), Lane::Line(b)));
+        }
+
+        if rules.is_unique(Lane::Column(0)) && self.column_patterns.has_duplicates() {
+            let (a, b) = Self::duplicates(&self.column_masks, self.height);
+            return Err(self.identical(Lane::Column(a), Lane::Column(b)));
+        }
+
+        self.check_diagonals(None).map_err(|(rule, diagonal)| {
+            let rules = rules.diagonal_rules();
+            let max = |cell| rules.max_count(diagonal.len(), cell);
+
+            Violation::new(
+                rule,
+                Vec::new(),
+                self.culprits(diagonal.clone(), rule, &rules, max),
+            )
+        })?;
+
+        self.check_regions(None).map_err(|region| {
+            let max = |cell| rules.max_count(self.usable(region), cell);
+            let cells = self.culprits(region.to_vec(), Rule::Balance, rules, max);
+
+            Violation::new(Rule::Balance, Vec::new(), cells)
+        })?;
+
+        self.check_signs(self)
+    }
+
+    // Cells breaking a rule among the ones of a lane, found again once the
+    // lane failed its check: the run too long, or the cells of the value in
+    // excess
+    fn culprits<F>(&self, cells: Vec<Index>, rule: Rule, rules: &Rules, max: F) -> Vec<Index>
+    where
+        F: Fn(Cell) -> usize,
+    {
+        // Holes are left out, like they are when checking
+        let cells: Vec<Index> = cells
+            .into_iter()
+            .filter(|idx| !self.is_hole(*idx))
+            .collect();
+
+        match rule {
+            Rule::Adjacency => {
+                let limit = rules.run_limit();
+                let wrapped = if rules.wraps(cells.len()) { limit } else { 0 };
+                let mut run: Vec<Index> = Vec::new();
+
+                for idx in cells.iter().chain(cells.iter().take(wrapped)) {
+                    if run.last().is_some_and(|last| self[*last] != self[*idx]) {
+                        run.clear();
+                    }
+
+                    if self[*idx].is_some() {
+                        run.push(*idx);
+                    }
+
+                    if run.len() > limit {
+                        return run;
+                    }
                 }
+
+                cells
             }
+            Rule::Balance => Cell::iter()
+                .map(|cell| {
+                    let filled = cells.iter().filter(|idx| self[**idx] == Some(cell));
+                    (cell, filled.copied().collect::<Vec<_>>())
+                })
+                .find(|(cell, filled)| filled.len() > max(*cell))
+                .map_or(cells, |(_, filled)| filled),
+            _ => cells,
         }
+    }
 
-        changed
+    // The first two complete lanes that are the same
+    fn duplicates(masks: &[LaneMask], size: usize) -> (usize, usize) {
+        (0..masks.len())
+            .flat_map(|a| (a + 1..masks.len()).map(move |b| (a, b)))
+            .find(|(a, b)| masks[*a].is_complete(size) && masks[*a] == masks[*b])
+            .unwrap_or_default()
     }
 
-    fn fill_heuristics(&mut self) -> bool {
-        let mut changed = false;
+    fn identical(&self, a: Lane, b: Lane) -> Violation {
+        let size = match a {
+            Lane::Line(_) => self.width,
+            Lane::Column(_) => self.height,
+        };
+
+        let cells = [a, b]
+            .into_iter()
+            .flat_map(|lane| (0..size).map(move |k| lane.index(k)));
 
-        // Process lines
-        for i in self.lines() {
-            // Check if a value is close to be filled, and is unbalanced with the other
-            for (j, cell) in Self::try_missings(self.line(i)) {
-                changed |= self.set((i, j), cell);
+        Violation::new(Rule::Uniqueness, vec![a, b], cells.collect())
+    }
+
+    // Check the diagonals, or only the ones going through a move, giving the
+    // cells of the one breaking a rule
+    fn check_diagonals(&self, mv: Option<(Index, Cell)>) -> Result<(), (Rule, Vec<Index>)> {
+        let rules = self.rules.diagonal_rules();
+
+        for mut diagonal in self.rules.diagonal_cells(self.width, self.height) {
+            // Holes are left out, like they are from lines and columns
+            diagonal.retain(|idx| !self.is_hole(*idx));
+
+            let cells: Vec<GridCell> = match mv {
+                Some((idx, _)) if !diagonal.contains(&idx) => continue,
+                Some((idx, cell)) => diagonal
+                    .iter()
+                    .map(|other| {
+                        if *other == idx {
+                            Some(cell)
+                        } else {
+                            self[*other]
+                        }
+                    })
+                    .collect(),
+                None => diagonal.iter().map(|idx| self[*idx]).collect(),
+            };
+
+            Self::check_lane(cells.iter(), &rules).map_err(|rule| (rule, diagonal))?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn check_mask<B: LaneBits>(
+        mask: &LaneMask<B>,
+        map: &Histogram,
+        size: usize,
+        rules: &Rules,
+    ) -> Result<(), Rule> {
+        let adjacency = rules.enforces(Rule::Adjacency);
+
+        if adjacency && Cell::iter().any(|cell| !mask.runs(cell, size, rules).is_zero()) {
+            return Err(Rule::Adjacency);
+        }
+
+        if Cell::iter().any(|cell| map[cell] > mask.max_count(size, cell, rules)) {
+            return Err(Rule::Balance);
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn check_move(&self, idx: Index, cell: Cell) -> Result<(), Rule> {
+        let Index(i, j) = idx;
+        let old = self[idx];
+
+        // Lanes going through the cell, as they would be after the move
+        let line = self.line_masks[i].with(j, cell);
+        let column = self.column_masks[j].with(i, cell);
+
+        let (width, height, rules) = (self.width, self.height, &self.rules);
+
+        Self::check_move_lane(&line, j, width, &self.line_counts[i], old, cell, rules)?;
+        Self::check_move_lane(&column, i, height, &self.column_counts[j], old, cell, rules)?;
+
+        // A complete lane must differ from every other complete lane
+        if self.rules.is_unique(Lane::Line(i)) && line.is_complete(self.width) {
+            let current = self.line_masks[i] == line;
+
+            if self.line_patterns.count(&line) > usize::from(current) {
+                return Err(Rule::Uniqueness);
             }
         }
 
-        // Process columns
-        for j in self.columns() {
-            // Check if a value is close to be filled, and is unbalanced with the other
-            for (i, cell) in Self::try_missings(self.column(j)) {
-                changed |= self.set((i, j), cell);
+        if self.rules.is_unique(Lane::Column(j)) && column.is_complete(self.height) {
+            let current = self.column_masks[j] == column;
+
+            if self.column_patterns.count(&column) > usize::from(current) {
+                return Err(Rule::Uniqueness);
             }
         }
 
-        changed
+        // Filled cells across a sign force the value of the cell
+        let forced = |(other, sign): (Index, Sign)| {
+            self[other].is_some_and(|value| sign.forced(value) != cell)
+        };
+
+        if !self.signs.is_empty() && self.linked(idx).any(forced) {
+            return Err(Rule::Sign);
+        }
+
+        if self.rules.on_diagonal(idx, width, height) {
+            self.check_diagonals(Some((idx, cell)))
+                .map_err(|(rule, _)| rule)?;
+        }
+
+        if !self.regions.is_empty() {
+            self.check_regions(Some((idx, cell)))
+                .map_err(|_| Rule::Balance)?;
+        }
+
+        Ok(())
     }
 
-    fn fill_bruteforce(&mut self, idx: Index) -> Result<(), GridError> {
-        for cell in Cell::iter() {
-            let mut grid = self.clone();
-            grid.set(idx, Some(cell));
+    pub(crate) fn check_move_lane<B: LaneBits>(
+        mask: &LaneMask<B>,
+        k: usize,
+        size: usize,
+        map: &Histogram,
+        old: GridCell,
+        cell: Cell,
+        rules: &Rules,
+    ) -> Result<(), Rule> {
+        // Check the runs starting up to the limit of cells before the move,
+        // the segments between holes making a single lane
+        let (k, usable) = (mask.packed_index(k), mask.usable(size));
+        let near = (-(rules.run_limit() as isize)..=0)
+            .filter_map(|offset| rules.neighbor(k, offset, usable))
+            .fold(B::default(), |mut near, m| {
+                near.set_bit(m, true);
+                near
+            });
 
-            if grid.solve().is_ok() {
-                *self = grid;
-                return Ok(());
-            }
+        if rules.enforces(Rule::Adjacency) && !mask.runs(cell, size, rules).and(&near).is_zero() {
+            return Err(Rule::Adjacency);
         }
 
-        Err(GridError::NoSolution)
+        // Check the balance of the lane
+        let count = map[cell] + usize::from(old != Some(cell));
+
+        if count > mask.max_count(size, cell, rules) {
+            return Err(Rule::Balance);
+        }
+
+        Ok(())
     }
 
-    fn set<I>(&mut self, idx: I, new: GridCell) -> bool
+    pub(crate) fn set<I>(&mut self, idx: I, new: GridCell) -> bool
     where
         I: Into<Index>,
     {
         let idx = idx.into();
         let old = self[idx];
 
-        self.cells[idx.0][idx.1] = new;
+        let offset = self.offset(idx);
+        let shift = (offset % CELLS_PER_WORD) * CELL_BITS;
+        let value = match new {
+            None => 0,
+            Some(Cell::Zero) => 1,
+            Some(Cell::One) => 2,
+        };
+
+        let word = &mut self.cells[offset / CELLS_PER_WORD];
+        *word = (*word & !(CELL_MASK << shift)) | (value << shift);
+
+        // Keep lane histograms up to date
+        if let Some(cell) = old {
+            self.line_counts[idx.0][cell] -= 1;
+            self.column_counts[idx.1][cell] -= 1;
+        }
+
+        if let Some(cell) = new {
+            self.line_counts[idx.0][cell] += 1;
+            self.column_counts[idx.1][cell] += 1;
+        }
+
+        // Lanes are only recorded as patterns while complete
+        let (line, column) = (&mut self.line_masks[idx.0], &mut self.column_masks[idx.1]);
+
+        self.line_patterns.remove(line, self.width);
+        self.column_patterns.remove(column, self.height);
+
+        line.set(idx.1, new);
+        column.set(idx.0, new);
+
+        self.line_patterns.insert(line, self.width);
+        self.column_patterns.insert(column, self.height);
 
         old != new
     }
 
-    fn lines(&self) -> impl Iterator<Item = usize> {
+    // Take a cell out of the puzzle, or bring it back, empty either way
+    pub(crate) fn set_hole(&mut self, idx: Index, hole: bool) {
+        let Index(i, j) = idx;
+        self.set(idx, None);
+
+        let (line, column) = (&mut self.line_masks[i], &mut self.column_masks[j]);
+
+        self.line_patterns.remove(line, self.width);
+        self.column_patterns.remove(column, self.height);
+
+        line.set_hole(j, hole);
+        column.set_hole(i, hole);
+
+        self.line_patterns.insert(line, self.width);
+        self.column_patterns.insert(column, self.height);
+    }
+
+    pub(crate) fn empty_cells(&self) -> impl Iterator<Item = Index> + '_ {
+        self.lines().flat_map(move |i| {
+            self.columns()
+                .filter(move |j| self[(i, *j)].is_none() && !self.is_hole((i, *j)))
+                .map(move |j| Index(i, j))
+        })
+    }
+
+    pub(crate) fn lines(&self) -> impl Iterator<Item = usize> {
         0..self.height
     }
 
-    fn columns(&self) -> impl Iterator<Item = usize> {
+    pub(crate) fn columns(&self) -> impl Iterator<Item = usize> {
         0..self.width
     }
 
-    fn line(&self, i: usize) -> impl Iterator<Item = &GridCell> {
+    pub(crate) fn line(&self, i: usize) -> impl Iterator<Item = &GridCell> {
         self.columns().map(move |j| &self[(i, j)])
     }
 
-    fn column(&self, j: usize) -> impl Iterator<Item = &GridCell> {
-        self.lines().map(move |i| &self[(i, j)])
+    // Pack the lane once, then check it a word at a time
+    #[cfg(feature = "simd")]
+    pub(crate) fn check_lane<'a, I>(lane: I, rules: &Rules) -> Result<(), Rule>
+    where
+        I: Iterator<Item = &'a GridCell> + Clone,
+    {
+        let mut mask = LaneMask::<BitSet>::default();
+        let mut size = 0;
+
+        for (k, cell) in lane.enumerate() {
+            mask.set(k, *cell);
+            size = k + 1;
+        }
+
+        Self::check_mask(&mask, &mask.histogram(), size, rules)
     }
 
-    fn check_lane<'a, I>(lane: I) -> Result<(), GridError>
+    #[cfg(not(feature = "simd"))]
+    pub(crate) fn check_lane<'a, I>(lane: I, rules: &Rules) -> Result<(), Rule>
     where
         I: Iterator<Item = &'a GridCell> + Clone,
     {
-        // Check if no more than 2 adjacent identical values
-        lane.clone().try_fold(
-            (None, None) as (Option<&GridCell>, Option<&GridCell>),
-            |acc, cell| {
-                if let (Some(x), Some(y)) = acc {
-                    if x.is_some() && x == y && y == cell {
-                        return Err(GridError::InvalidGrid);
-                    }
+        let limit = rules.run_limit();
+
+        // Cyclic lanes go on with their first cells
+        let wrapped = if rules.wraps(lane.clone().count()) {
+            limit
+        } else {
+            0
+        };
+
+        // Check if no more than the limit of adjacent identical values
+        lane.clone().chain(lane.clone().take(wrapped)).try_fold(
+            (None, 0),
+            |(last, run): (Option<&GridCell>, usize), cell| {
+                let run = if cell.is_some() && last == Some(cell) {
+                    run + 1
+                } else {
+                    1
+                };
+
+                if rules.enforces(Rule::Adjacency) && run > limit {
+                    return Err(Rule::Adjacency);
                 }
 
-                Ok((acc.1, Some(cell)))
+                Ok((Some(cell), run))
             },
         )?;
 
         // Check if both numbers are balanced
         Self::find_count(lane, |map, size, cell| {
-            (map[&cell] > (size / 2)).then(|| cell)
+            (map[cell] > rules.max_count(size, cell)).then_some(cell)
         })
-        .map(|_| Err(GridError::InvalidGrid))
+        .map(|_| Err(Rule::Balance))
         .unwrap_or(Ok(()))
     }
 
-    fn check_pair<'a, 'b, I>(mut pairs: I) -> Result<(), GridError>
-    where
-        I: Iterator<Item = (&'a GridCell, &'b GridCell)>,
-    {
-        pairs
-            .any(|(lhs, rhs)| lhs.is_none() || lhs != rhs)
-            .then(|| ())
-            .ok_or(GridError::InvalidGrid)
-    }
-
-    fn fill_cell(cell0: GridCell, cell1: GridCell) -> GridCell {
-        cell0
-            .zip(cell1)
-            .and_then(|(value0, value1)| (value0 == value1).then(|| !value0))
-    }
-
-    fn fill_saturated<'a, I>(lane: I) -> GridCell
-    where
-        I: Iterator<Item = &'a GridCell>,
-    {
-        Self::find_count(lane, |map, size, cell| {
-            (map[&cell] >= size / 2).then(|| !cell)
-        })
-    }
-
+    #[cfg(not(feature = "simd"))]
     fn find_count<'a, I, F>(lane: I, f: F) -> GridCell
     where
         I: Iterator<Item = &'a GridCell>,
         F: Fn(&Histogram, usize, Cell) -> GridCell,
     {
-        let mut map = Histogram::from_iter(Cell::iter().map(|cell| (cell, 0)));
+        let mut map = Histogram::default();
         let size = lane.fold(0, |size, cell| {
             if let Some(cell) = cell {
-                *map.entry(*cell).or_default() += 1;
+                map[*cell] += 1;
             }
 
             size + 1
@@ -332,62 +1347,170 @@ impl Grid {
 
         Cell::iter().find_map(|cell| f(&map, size, cell))
     }
+}
 
-    fn try_missings<'a, I>(lane: I) -> HashMap<usize, GridCell>
-    where
-        I: Iterator<Item = &'a GridCell>,
-    {
-        let mut result = HashMap::new();
-        let lane: Vec<&GridCell> = lane.collect();
+impl Histogram {
+    pub(crate) fn filled(&self) -> usize {
+        self.0.iter().sum()
+    }
+}
 
-        for num_guess in 1..3 {
-            let mut none_idx = Vec::new();
+impl<B: LaneBits> LaneMask<B> {
+    pub(crate) fn set(&mut self, k: usize, cell: GridCell) {
+        self.filled.set_bit(k, cell.is_some());
+        self.ones.set_bit(k, cell == Some(Cell::One));
+    }
 
-            // Get value that is almost complete
-            let almost = Self::find_count(lane.iter().copied(), |map, size, cell| {
-                (map[&cell] > map[&!cell] && map[&cell] + num_guess == (size / 2)).then(|| cell)
-            });
+    pub(crate) fn with(&self, k: usize, cell: Cell) -> Self {
+        let mut mask = self.clone();
+        mask.set(k, Some(cell));
+        mask
+    }
 
-            if let Some(cell) = almost {
-                // Replace empty cells by opposite value, but keep track of indice
-                let mut lane: Vec<_> = lane
-                    .iter()
-                    .enumerate()
-                    .map(|(idx, c)| {
-                        c.or_else(|| {
-                            none_idx.push(idx);
-                            Some(!cell)
-                        })
-                    })
-                    .collect();
-
-                // For each empty place
-                for i in none_idx.iter().copied() {
-                    // Try the tested value
-                    lane[i] = Some(cell);
-
-                    let is_possible = if num_guess == 1 {
-                        Self::check_lane(lane.iter()).is_ok()
-                    } else {
-                        none_idx.iter().copied().filter(|j| i != *j).any(|j| {
-                            lane[j] = Some(cell);
-                            let is_possible = Self::check_lane(lane.iter()).is_ok();
-                            lane[j] = Some(!cell);
-                            is_possible
-                        })
-                    };
-
-                    if !is_possible {
-                        result.insert(i, Some(!cell));
-                    }
+    pub(crate) fn set_hole(&mut self, k: usize, hole: bool) {
+        self.holes.set_bit(k, hole);
+    }
 
-                    // Restore opposite value
-                    lane[i] = Some(!cell);
-                }
+    pub(crate) fn is_hole(&self, k: usize) -> bool {
+        self.holes.bit(k)
+    }
+
+    // Cells of the lane that are part of the puzzle
+    pub(crate) fn usable(&self, size: usize) -> usize {
+        size - self.holes.count_ones()
+    }
+
+    // Most cells of a value the lane can hold: the count of its margin clue,
+    // or its share of the cells that are not holes
+    pub(crate) fn max_count(&self, size: usize, cell: Cell, rules: &Rules) -> usize {
+        let usable = self.usable(size);
+
+        match (self.target, cell) {
+            (Some(ones), Cell::One) => ones,
+            (Some(ones), Cell::Zero) => usable - ones,
+            (None, _) => rules.max_count(usable, cell),
+        }
+    }
+
+    pub(crate) fn is_complete(&self, size: usize) -> bool {
+        self.filled.or(&self.holes).count_ones() == size
+    }
+
+    fn values(&self, cell: Cell) -> B {
+        match cell {
+            Cell::Zero => self.filled.and_not(&self.ones),
+            Cell::One => self.ones.clone(),
+        }
+    }
+
+    #[cfg(feature = "simd")]
+    fn histogram(&self) -> Histogram {
+        let ones = self.ones.count_ones();
+        let filled = self.filled.count_ones();
+
+        Histogram([filled - ones, ones])
+    }
+
+    // Cells that are not holes, next to each other
+    fn pack(&self, bits: B, size: usize) -> B {
+        if self.holes.is_zero() {
+            return bits;
+        }
+
+        (0..size).filter(|k| !self.holes.bit(*k)).enumerate().fold(
+            B::default(),
+            |mut packed, (m, k)| {
+                packed.set_bit(m, bits.bit(k));
+                packed
+            },
+        )
+    }
+
+    // Position of a cell once the holes are left out of the lane
+    fn packed_index(&self, k: usize) -> usize {
+        k - self.holes.count_below(k)
+    }
+
+    // First cell of every run of identical values over the limit, including
+    // the runs going over the end of cyclic lanes; holes are left out, so the
+    // cells are the packed ones
+    fn runs(&self, cell: Cell, size: usize, rules: &Rules) -> B {
+        let values = self.pack(self.values(cell), size);
+        let size = self.usable(size);
+
+        let next = |shift: usize| {
+            if rules.wraps(size) {
+                values
+                    .shr(shift)
+                    .or(&values.shl(size - shift).truncate(size))
+            } else {
+                values.shr(shift)
+            }
+        };
+
+        (1..=rules.run_limit()).fold(values.clone(), |runs, shift| runs.and(&next(shift)))
+    }
+}
+
+impl Patterns {
+    fn insert(&mut self, mask: &LaneMask, size: usize) {
+        if mask.is_complete(size) {
+            let key = (mask.holes.clone(), mask.ones.clone());
+            *self.0.entry(key).or_default() += 1;
+        }
+    }
+
+    fn remove(&mut self, mask: &LaneMask, size: usize) {
+        if !mask.is_complete(size) {
+            return;
+        }
+
+        let key = (mask.holes.clone(), mask.ones.clone());
+
+        if let Entry::Occupied(mut entry) = self.0.entry(key) {
+            *entry.get_mut() -= 1;
+
+            if *entry.get() == 0 {
+                entry.remove();
             }
         }
+    }
+
+    fn count(&self, mask: &LaneMask) -> usize {
+        let key = (mask.holes.clone(), mask.ones.clone());
+        self.0.get(&key).copied().unwrap_or(0)
+    }
+
+    fn has_duplicates(&self) -> bool {
+        self.0.values().any(|count| *count > 1)
+    }
+}
+
+impl ops::Index<Cell> for Histogram {
+    type Output = usize;
 
-        result
+    fn index(&self, cell: Cell) -> &Self::Output {
+        &self.0[cell as usize]
+    }
+}
+
+impl ops::IndexMut<Cell> for Histogram {
+    fn index_mut(&mut self, cell: Cell) -> &mut Self::Output {
+        &mut self.0[cell as usize]
+    }
+}
+
+impl PartialEq for Grid {
+    fn eq(&self, other: &Self) -> bool {
+        // Only compare the values, not how they were obtained
+        self.width == other.width
+            && self.height == other.height
+            && self.cells == other.cells
+            && self.signs == other.signs
+            && self.regions == other.regions
+            && self.rules == other.rules
+            && self.holes().eq(other.holes())
+            && self.targets().eq(other.targets())
     }
 }
 
@@ -398,31 +1521,17 @@ where
     type Output = GridCell;
 
     fn index(&self, idx: I) -> &Self::Output {
-        let idx = idx.into();
-        &self.cells[idx.0][idx.1]
+        let offset = self.offset(idx.into());
+        let shift = (offset % CELLS_PER_WORD) * CELL_BITS;
+        let value = (self.cells[offset / CELLS_PER_WORD] >> shift) & CELL_MASK;
+
+        &CELL_VALUES[value as usize]
     }
 }
 
 impl fmt::Display for Grid {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        for i in 0..self.height {
-            for j in 0..self.width {
-                match self[(i, j)] {
-                    Some(cell) => cell.fmt(fmt)?,
-                    None => write!(fmt, "-")?,
-                }
-
-                if j < self.width - 1 {
-                    write!(fmt, " ")?;
-                }
-            }
-
-            if i < self.height - 1 {
-                writeln!(fmt)?;
-            }
-        }
-
-        Ok(())
+        self.display_with(DisplayOptions::default()).fmt(fmt)
     }
 }
 
@@ -430,6 +1539,454 @@ impl fmt::Display for Grid {
 mod tests {
     use super::*;
 
+    #[test]
+    fn transformations() {
+        let grid = Grid::parse(vec!["1 - 0 -", "- - 1 1"].into_iter()).unwrap();
+
+        let rotated = Grid::parse(vec!["- 1", "- -", "1 0", "1 -"].into_iter()).unwrap();
+        assert_eq!(grid.rotate90(), rotated);
+
+        let mirrored = Grid::parse(vec!["- 0 - 1", "1 1 - -"].into_iter()).unwrap();
+        assert_eq!(grid.mirror_horizontal(), mirrored);
+
+        let mirrored = Grid::parse(vec!["- - 1 1", "1 - 0 -"].into_iter()).unwrap();
+        assert_eq!(grid.mirror_vertical(), mirrored);
+
+        let transposed = Grid::parse(vec!["1 -", "- -", "0 1", "- 1"].into_iter()).unwrap();
+        assert_eq!(grid.transpose(), transposed);
+
+        let complement = Grid::parse(vec!["0 - 1 -", "- - 0 0"].into_iter()).unwrap();
+        assert_eq!(grid.complement(), complement);
+
+        // Four rotations give back the original grid
+        let rotated = grid.rotate90().rotate90().rotate90().rotate90();
+        assert_eq!(rotated, grid);
+    }
+
+    #[test]
+    fn cell_states() {
+        let input = vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"];
+
+        let mut grid = Grid::parse(input.into_iter()).unwrap();
+        grid.solve().unwrap();
+
+        assert_eq!(grid.state((0, 0)), Some(CellState::Given));
+        assert_eq!(grid.state((0, 2)), Some(CellState::Derived));
+
+        let givens: Vec<_> = grid
+            .givens()
+            .map(|(idx, cell)| (idx.0, idx.1, cell))
+            .collect();
+        assert_eq!(
+            givens,
+            vec![
+                (0, 0, Cell::One),
+                (0, 1, Cell::One),
+                (0, 3, Cell::Zero),
+                (1, 1, Cell::Zero),
+                (2, 2, Cell::Zero),
+                (3, 1, Cell::One),
+                (3, 3, Cell::Zero),
+            ]
+        );
+
+        // Transformations keep track of clues
+        assert_eq!(grid.transpose().state((3, 0)), Some(CellState::Given));
+        assert_eq!(grid.transpose().state((2, 0)), Some(CellState::Derived));
+    }
+
+    #[test]
+    fn verification() {
+        let grid =
+            Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter()).unwrap();
+
+        let mut solution = grid.clone();
+        solution.solve().unwrap();
+        assert!(grid.verify_solution(&solution).is_ok());
+
+        let candidate =
+            Grid::parse(vec!["1 1 0 0", "0 0 1 1", "1 - 0 1", "0 1 1 0"].into_iter()).unwrap();
+        assert!(matches!(
+            grid.verify_solution(&candidate),
+            Err(VerificationError::Incomplete(Index(2, 1)))
+        ));
+
+        let candidate =
+            Grid::parse(vec!["1 1 0 0", "0 0 1 1", "1 0 0 1", "0 1 1 0"].into_iter()).unwrap();
+        assert!(grid
+            .complement()
+            .verify_solution(&candidate.complement())
+            .is_ok());
+        assert!(matches!(
+            grid.verify_solution(&candidate.mirror_horizontal()),
+            Err(VerificationError::ClueMismatch(Index(0, 0)))
+        ));
+
+        let candidate = Grid::parse(vec!["1 0", "0 1"].into_iter()).unwrap();
+        assert!(matches!(
+            grid.verify_solution(&candidate),
+            Err(VerificationError::DimensionMismatch)
+        ));
+    }
+
+    #[test]
+    fn move_validity() {
+        let input = vec!["1 0 1 0", "- - - -", "0 - - -", "1 0 1 -"];
+        let grid = Grid::parse(input.into_iter()).unwrap();
+
+        assert!(grid.is_move_valid((1, 0), Cell::Zero));
+        assert!(!grid.is_move_valid((1, 0), Cell::One));
+
+        // Line would have three ones
+        assert!(!grid.is_move_valid((3, 3), Cell::One));
+
+        // Line would be identical to the first one
+        assert!(!grid.is_move_valid((3, 3), Cell::Zero));
+    }
+
+    #[test]
+    fn lane_checks() {
+        let lane =
+            |s: &str| -> Vec<GridCell> { s.chars().map(|c| Cell::try_from(c).ok()).collect() };
+
+        assert!(Grid::check_lane(lane("10-1-0").iter(), &Rules::default()).is_ok());
+        assert!(Grid::check_lane(lane("1--000").iter(), &Rules::default()).is_err());
+        assert!(matches!(
+            Grid::check_lane(lane("-0001-").iter(), &Rules::default()),
+            Err(Rule::Adjacency)
+        ));
+        assert!(Grid::check_lane(lane("1-1-1-1-").iter(), &Rules::default()).is_ok());
+        assert!(matches!(
+            Grid::check_lane(lane("101101").iter(), &Rules::default()),
+            Err(Rule::Balance)
+        ));
+    }
+
+    #[test]
+    fn solution_count() {
+        let grid = Grid::parse(vec!["- - - -"; 4].into_iter()).unwrap();
+        assert_eq!(grid.count_solutions(usize::MAX).unwrap(), 72);
+        assert_eq!(grid.count_solutions(10).unwrap(), 10);
+        assert!(!grid.has_unique_solution());
+
+        let grid =
+            Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter()).unwrap();
+        assert_eq!(grid.count_solutions(usize::MAX).unwrap(), 1);
+        assert!(grid.has_unique_solution());
+
+        let grid =
+            Grid::parse(vec!["0 - - 0", "0 - - 0", "- - - -", "- - - -"].into_iter()).unwrap();
+        assert_eq!(grid.count_solutions(usize::MAX).unwrap(), 0);
+    }
+
+    #[test]
+    fn holes() {
+        let input = vec!["0 0 * * - -", "- - - - - -", "- - - - - -", "- - * * - -"];
+        let grid = Grid::parse(input.into_iter()).unwrap();
+        assert_eq!(
+            grid.holes().collect::<Vec<_>>(),
+            [Index(0, 2), Index(0, 3), Index(3, 2), Index(3, 3)]
+        );
+        assert_eq!(Grid::parse(grid.to_string().lines()).unwrap(), grid);
+        assert_eq!(Grid::from_json(&grid.to_json()).unwrap(), grid);
+
+        // Runs go on across the holes, and lanes are balanced without them
+        assert!(!grid.is_move_valid((0, 4), Cell::Zero));
+        assert!(grid.is_move_valid((0, 4), Cell::One));
+        assert!(!grid.is_move_valid((0, 2), Cell::Zero));
+        assert_eq!(grid.count_solutions(usize::MAX).unwrap(), 16);
+
+        let mut solution = grid.clone();
+        solution.solve().unwrap();
+        assert!(solution.holes().all(|idx| solution[idx].is_none()));
+        assert!(solution.is_valid().is_ok());
+
+        // Lanes left with an odd number of cells
+        let input = vec!["0 1 * - - -", "- - - - - -"];
+        let error = Grid::parse(input.into_iter()).unwrap_err();
+        assert!(matches!(
+            error,
+            ParseError::UnbalancedLane {
+                lane: Lane::Line(0),
+                cells: 5
+            }
+        ));
+        assert_eq!(
+            error.to_string(),
+            "error: line 1 has 5 cells besides its holes, which cannot hold their share of each value"
+        );
+    }
+
+    #[test]
+    fn long_lanes() {
+        let empty = "- ".repeat(70);
+        let pattern = "0 1 ".repeat(35);
+        let mut input = vec![empty.as_str(); 70];
+        input[0] = &pattern;
+
+        let mut grid = Grid::parse(input.iter()).unwrap();
+        assert_eq!(grid.width(), 70);
+        grid.set_cell((2, 66), Some(Cell::One));
+        grid.set_cell((2, 67), Some(Cell::One));
+        assert!(!grid.is_move_valid((2, 68), Cell::One));
+        assert!(!grid.is_move_valid((2, 65), Cell::One));
+        assert!(grid.is_move_valid((2, 68), Cell::Zero));
+
+        // Rules are checked past the first 64 cells
+        let mut input = input.clone();
+        input[2] = &pattern;
+        let violation = |input: &[&str]| match Grid::parse(input.iter()) {
+            Err(ParseError::InvalidGrid(violation)) => violation,
+            other => panic!("unexpected {other:?}"),
+        };
+        let broken = violation(&input);
+        assert_eq!(broken.rule(), Rule::Uniqueness);
+        assert_eq!(broken.lanes(), [Lane::Line(0), Lane::Line(2)]);
+
+        let run = format!("{}1 1 1 -", "- ".repeat(66));
+        input[2] = &run;
+        let broken = violation(&input);
+        assert_eq!(broken.rule(), Rule::Adjacency);
+        assert_eq!(broken.cells(), [Index(2, 66), Index(2, 67), Index(2, 68)]);
+    }
+
+    #[test]
+    fn margins() {
+        let input = [
+            "  2 2 2 1 2",
+            "1 - - - - -",
+            "2 - - - - -",
+            "3 - - - - -",
+            "3 - - - - -",
+        ];
+        let options = ParseOptions::new().margins(true);
+        let grid = Grid::parse_with(input.iter(), &options).unwrap();
+        assert_eq!(grid.target(Lane::Line(2)), Some(3));
+        assert_eq!(grid.target(Lane::Column(3)), Some(1));
+        assert_eq!(grid.to_string(), input.join("\n"));
+        assert_eq!(Grid::from_json(&grid.to_json()).unwrap(), grid);
+
+        // The counts replace the balance of lanes, odd ones included
+        assert_eq!(grid.count_solutions(usize::MAX).unwrap(), 1);
+
+        let mut solution = grid.clone();
+        solution.solve().unwrap();
+        assert_eq!(
+            solution
+                .line(0)
+                .filter(|cell| **cell == Some(Cell::One))
+                .count(),
+            1
+        );
+
+        // Lanes can have no clue, and then hold their share of each value
+        let input = ["- - 1 -", "- 1 - 1 0", "- - - - -"];
+        let grid = Grid::parse_with(input.iter(), &options).unwrap();
+        assert_eq!(grid.target(Lane::Line(0)), None);
+        assert!(!grid.is_move_valid((0, 1), Cell::One));
+        assert!(!grid.is_move_valid((1, 2), Cell::One));
+        assert!(grid.is_move_valid((1, 2), Cell::Zero));
+
+        let input = ["- - -", "5 - - -", "1 - - -"];
+        assert!(matches!(
+            Grid::parse_with(input.iter(), &options),
+            Err(ParseError::InvalidGrid(violation)) if violation.rule() == Rule::Balance
+        ));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert!(matches!(
+            Grid::parse(vec!["1 - 0", "- - 1"].into_iter()),
+            Err(ParseError::OddDimension {
+                width: 3,
+                height: 2
+            })
+        ));
+        assert!(matches!(
+            Grid::parse(vec!["1 - 0 -", "- - 1"].into_iter()),
+            Err(ParseError::WidthMismatch {
+                line: 2,
+                expected: 4,
+                found: 3
+            })
+        ));
+        assert!(matches!(
+            Grid::parse(vec!["1 1 1 -", "- - - -"].into_iter()),
+            Err(ParseError::InvalidGrid(violation)) if violation.rule() == Rule::Adjacency
+        ));
+        assert!(matches!(
+            Grid::parse(vec!["1 0 1 -", "1 - - -"].into_iter()),
+            Err(ParseError::InvalidGrid(violation)) if violation.rule() == Rule::Balance
+        ));
+        assert!(matches!(
+            Grid::parse(vec!["1 0 1 0", "0 1 0 1", "1 0 1 0", "- - - -"].into_iter()),
+            Err(ParseError::InvalidGrid(violation)) if violation.rule() == Rule::Uniqueness
+        ));
+
+        // Rules broken tell the lanes and cells at fault
+        let violation = |input: Vec<&str>| match Grid::parse(input.into_iter()) {
+            Err(ParseError::InvalidGrid(violation)) => violation,
+            _ => panic!("the grid breaks a rule"),
+        };
+
+        let broken = violation(vec!["0 - - -", "- 1 1 1", "- - - -", "- - - -"]);
+        assert_eq!(broken.rule(), Rule::Adjacency);
+        assert_eq!(broken.lanes(), [Lane::Line(1)]);
+        assert_eq!(broken.cells(), [Index(1, 1), Index(1, 2), Index(1, 3)]);
+
+        let broken = violation(vec!["- - - 1", "- - - -", "- - - 1", "- - - 1"]);
+        assert_eq!(broken.rule(), Rule::Balance);
+        assert_eq!(broken.lanes(), [Lane::Column(3)]);
+        assert_eq!(broken.cells(), [Index(0, 3), Index(2, 3), Index(3, 3)]);
+        assert_eq!(
+            broken.to_string(),
+            "column 4: too many identical cells in a lane"
+        );
+
+        let broken = violation(vec!["0 1 0 1", "1 0 1 0", "- - - -", "0 1 0 1"]);
+        assert_eq!(broken.rule(), Rule::Uniqueness);
+        assert_eq!(broken.lanes(), [Lane::Line(0), Lane::Line(3)]);
+        assert_eq!(broken.cells().len(), 8);
+        assert_eq!(broken.to_string(), "lines 1 and 4: two identical lanes");
+
+        // Errors tell where they are in the input
+        let error = |input: Vec<&str>| Grid::parse(input.into_iter()).unwrap_err().to_string();
+        assert_eq!(
+            error(vec!["# puzzle", "1 - 0 -", "- - 1"]),
+            "error: line 3 has 3 cells, expected 4"
+        );
+        assert_eq!(
+            error(vec!["1 - 0 -", "- y 1 -"]),
+            "error: line 2, column 3: unknown character 'y'"
+        );
+        assert_eq!(
+            error(vec!["1 - 0 -", "- - 1 -", "", "a a b b", "a a b !"]),
+            "error: line 5, column 7: unknown character '!'"
+        );
+        assert_eq!(
+            error(vec!["1 - 0 -", "   =", "- - 1 -"]),
+            "error: line 2, column 4: unknown character '='"
+        );
+
+        // Positions in 14x14 grids written without spaces, where an 'x' only
+        // stands for a sign between two cells
+        let mut input = vec!["--------------"; 14];
+        input[6] = "-----------x";
+        assert_eq!(
+            error(input.clone()),
+            "error: line 7, column 12: unknown character 'x'"
+        );
+        input[3] = "-------------";
+        assert_eq!(error(input), "error: line 4 has 13 cells, expected 14");
+
+        // Parsing stops at the first cell too many, and tells the limits
+        let line = "- ".repeat(1026);
+        assert!(matches!(
+            Grid::parse(vec![line.as_str(), line.as_str()].into_iter()),
+            Err(ParseError::TooLarge {
+                max_width: 1024,
+                max_height: 1024,
+                max_cells: 65536,
+            })
+        ));
+        assert_eq!(
+            error(vec![line.as_str()]),
+            "error: grid is too large (at most 1024x1024 and 65536 cells)"
+        );
+    }
+
+    #[test]
+    fn parse_options() {
+        let expected = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+        let expected = expected.unwrap();
+
+        let input = vec!["11.0 # first", "_0??", "..0.", "?1_0"];
+        assert_eq!(Grid::parse(input.into_iter()).unwrap(), expected);
+
+        let options = ParseOptions::new().empty(['x']).comment("//");
+        let input = vec!["// puzzle #1", "11x0", "x0xx", "xx0x // third", "x1x0"];
+        assert_eq!(
+            Grid::parse_with(input.into_iter(), &options).unwrap(),
+            expected
+        );
+        assert!(Grid::parse_with(vec!["1 1 - 0"].into_iter(), &options).is_err());
+
+        let options = ParseOptions::new().zero('O').one('X');
+        let input = vec!["X X - O", "- O - -", "- - O -", "- X - O"];
+        assert_eq!(
+            Grid::parse_with(input.into_iter(), &options).unwrap(),
+            expected
+        );
+        assert!(Grid::parse_with(vec!["1 1 - 0"].into_iter(), &options).is_err());
+
+        // Reading stops at the first line or cell beyond the limits
+        let options = ParseOptions::new().max_size(4, 6);
+        let input = vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"];
+        assert!(Grid::parse_with(input.into_iter(), &options).is_ok());
+        assert!(matches!(
+            Grid::parse_with(vec!["- - - - - -"; 1000].into_iter(), &options),
+            Err(ParseError::TooLarge {
+                max_width: 4,
+                max_height: 6,
+                ..
+            })
+        ));
+        assert!(matches!(
+            Grid::parse_with(core::iter::repeat("- - - -"), &options),
+            Err(ParseError::TooLarge { max_height: 6, .. })
+        ));
+
+        let options = ParseOptions::new().max_cells(20);
+        assert!(matches!(
+            Grid::parse_with(vec!["- - - - - -"; 6].into_iter(), &options),
+            Err(ParseError::TooLarge { max_cells: 20, .. })
+        ));
+    }
+
+    #[test]
+    fn encodings() {
+        let expected = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+        let expected = expected.unwrap();
+
+        // Byte order marks, and lines ending with CR LF or a lone CR
+        let input = vec!["\u{feff}1 1 - 0\r", "- 0 - -\r", "- - 0 -\r", "- 1 - 0\r"];
+        assert_eq!(Grid::parse(input.into_iter()).unwrap(), expected);
+        let input = vec!["1 1 - 0\r- 0 - -\r- - 0 -\r- 1 - 0"];
+        assert_eq!(Grid::parse(input.into_iter()).unwrap(), expected);
+
+        let input = vec!["１ １ － ０", "-\u{3000}０ - -", "- - ٠ -", "- १ - 0"];
+        let options = ParseOptions::new().unicode_digits(true);
+        assert_eq!(
+            Grid::parse_with(input.clone().into_iter(), &options).unwrap(),
+            expected
+        );
+
+        // Without the option, the error names the character it looks like
+        assert_eq!(
+            Grid::parse(input.into_iter()).unwrap_err().to_string(),
+            "error: line 1, column 1: unknown character '１' (U+FF11, a variant of '1')"
+        );
+        assert_eq!(
+            Grid::parse(vec!["1 1 - 0\u{200b}"].into_iter())
+                .unwrap_err()
+                .to_string(),
+            "error: line 1, column 8: unknown character '\u{200b}' (U+200B)"
+        );
+    }
+
+    #[test]
+    fn isomorphism() {
+        let grid = Grid::parse(vec!["1 - 0 -", "- - 1 1"].into_iter()).unwrap();
+        let other = grid.rotate90().complement().mirror_vertical();
+
+        assert!(grid.is_isomorphic_to(&other));
+        assert_eq!(grid.canonical_form(), other.canonical_form());
+
+        let other = Grid::parse(vec!["1 - 0 -", "- - 1 0"].into_iter()).unwrap();
+        assert!(!grid.is_isomorphic_to(&other));
+    }
+
     #[test]
     fn easy_grid() {
         let input = vec![
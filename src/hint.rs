@@ -0,0 +1,137 @@
+use alloc::format;
+use alloc::string::String;
+
+use crate::cell::Cell;
+use crate::deduction::Step;
+use crate::error::SolveError;
+use crate::grid::Grid;
+use crate::index::{Index, Lane};
+use crate::solver::{Solver, SolverConfig};
+
+// What a hint gives away, from a lane to look at up to the value of a cell
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Hint {
+    Lane(Lane),
+    Cell(Index),
+    // The value of the cell, with a sentence saying why it goes there
+    Value(Index, Cell, String),
+}
+
+impl Grid {
+    pub fn hint(&self) -> Result<Option<(Index, Cell)>, SolveError> {
+        self.is_valid().map_err(SolveError::InvalidGrid)?;
+
+        let config = SolverConfig::default();
+        let mut grid = self.clone();
+
+        // Prefer a cell that follows from the rules
+        if let Some(idx) = Solver::new(&mut grid, &config).deduce()? {
+            return Ok(grid[idx].map(|cell| (idx, cell)));
+        }
+
+        // Otherwise reveal a cell of the solution
+        let Some(idx) = self.empty_cells().next() else {
+            return Ok(None);
+        };
+
+        grid.solve_with(&config)?;
+        Ok(grid[idx].map(|cell| (idx, cell)))
+    }
+
+    // Level 1 names the lane of the next cell, level 2 the cell itself, and
+    // level 3 or more also gives its value with an explanation
+    pub fn hint_at(&self, level: usize) -> Result<Option<Hint>, SolveError> {
+        self.is_valid().map_err(SolveError::InvalidGrid)?;
+
+        let config = SolverConfig::default();
+        let mut grid = self.clone();
+        let mut solver = Solver::new(&mut grid, &config).with_log();
+        solver.deduce()?;
+
+        let deduction = solver.into_log().into_iter().find_map(|step| match step {
+            Step::Deduction(deduction) => Some(deduction),
+            _ => None,
+        });
+
+        let (lane, idx, cell, reason) = match deduction {
+            Some(deduction) => (
+                deduction.lane(),
+                deduction.index(),
+                deduction.value(),
+                deduction.explain(),
+            ),
+            // Cells of the solution come without a reason
+            None => match self.hint()? {
+                Some((idx, cell)) => (
+                    Lane::Line(idx.0),
+                    idx,
+                    cell,
+                    format!(
+                        "r{}c{} = {} in the solution, as nothing can be deduced",
+                        idx.0 + 1,
+                        idx.1 + 1,
+                        cell
+                    ),
+                ),
+                None => return Ok(None),
+            },
+        };
+
+        Ok(Some(match level {
+            0 | 1 => Hint::Lane(lane),
+            2 => Hint::Cell(idx),
+            _ => Hint::Value(idx, cell, reason),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hints() {
+        let grid = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+        let grid = grid.unwrap();
+
+        // The third cell of the first line cannot be a third one
+        assert_eq!(grid.hint().unwrap(), Some((Index(0, 2), Cell::Zero)));
+
+        let grid = Grid::parse(vec!["- - - -"; 4].into_iter()).unwrap();
+        let (idx, cell) = grid.hint().unwrap().unwrap();
+        assert!(grid.is_move_valid(idx, cell));
+
+        let grid = Grid::parse(vec!["1 1 0 0", "0 0 1 1", "1 0 0 1", "0 1 1 0"].into_iter());
+        assert_eq!(grid.unwrap().hint().unwrap(), None);
+
+        let grid = Grid::parse(vec!["0 - - 0", "0 - - 0", "- - - -", "- - - -"].into_iter());
+        assert!(grid.unwrap().hint().is_err());
+    }
+
+    #[test]
+    fn levels() {
+        let grid = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+        let grid = grid.unwrap();
+
+        assert_eq!(grid.hint_at(1).unwrap(), Some(Hint::Lane(Lane::Line(0))));
+        assert_eq!(grid.hint_at(2).unwrap(), Some(Hint::Cell(Index(0, 2))));
+        assert_eq!(
+            grid.hint_at(3).unwrap(),
+            Some(Hint::Value(
+                Index(0, 2),
+                Cell::Zero,
+                "r1c3 = 0 because r1c1 and r1c2 are both 1".into()
+            ))
+        );
+
+        // Without any deduction, a cell of the solution is given
+        let grid = Grid::parse(vec!["- - - -"; 4].into_iter()).unwrap();
+        let Some(Hint::Value(idx, cell, _)) = grid.hint_at(3).unwrap() else {
+            unreachable!()
+        };
+        assert!(grid.is_move_valid(idx, cell));
+
+        let grid = Grid::parse(vec!["1 1 0 0", "0 0 1 1", "1 0 0 1", "0 1 1 0"].into_iter());
+        assert_eq!(grid.unwrap().hint_at(1).unwrap(), None);
+    }
+}
@@ -0,0 +1,153 @@
+use std::fmt::Write;
+
+use crate::cell::CellState;
+use crate::grid::Grid;
+use crate::index::Index;
+
+const HEADER: &str = r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>TITLE</title>
+<style>
+body { font-family: sans-serif; }
+table { border-collapse: collapse; border: 2px solid black; }
+td { width: 40px; height: 40px; border: 1px solid gray; text-align: center; font-size: 24px; cursor: pointer; user-select: none; }
+td.given { font-weight: bold; cursor: default; }
+td.played { color: steelblue; }
+td.wrong { color: red; }
+</style>
+</head>
+<body>
+"#;
+
+const SCRIPT: &str = r##"<script>
+const cells = document.querySelectorAll("#board td:not(.given)");
+const next = { "": "0", "0": "1", "1": "" };
+
+for (const cell of cells) {
+  cell.classList.add("played");
+  cell.addEventListener("click", () => {
+    cell.textContent = next[cell.textContent];
+    cell.classList.remove("wrong");
+  });
+}
+
+function check() {
+  let mistakes = 0, empty = 0;
+
+  document.querySelectorAll("#board tr").forEach((row, i) => {
+    row.querySelectorAll("td").forEach((cell, j) => {
+      if (cell.textContent === "") {
+        empty++;
+      } else if (cell.textContent !== solution[i][j]) {
+        cell.classList.add("wrong");
+        mistakes++;
+      }
+    });
+  });
+
+  document.getElementById("status").textContent =
+    mistakes > 0 ? mistakes + " wrong cell(s)" : empty > 0 ? "so far so good" : "solved!";
+}
+
+function reveal() {
+  document.querySelectorAll("#board tr").forEach((row, i) => {
+    row.querySelectorAll("td").forEach((cell, j) => {
+      cell.textContent = solution[i][j];
+      cell.classList.remove("wrong");
+    });
+  });
+}
+</script>
+"##;
+
+impl Grid {
+    // A standalone page to play the puzzle made of the givens, which can embed
+    // the grid itself as the solution to check the player's moves against
+    pub fn to_html(&self, solution: bool) -> String {
+        let title = escape(self.metadata().title().unwrap_or("Binero"));
+        let mut html = HEADER.replace("TITLE", &title);
+
+        if self.metadata().title().is_some() {
+            let _ = writeln!(html, "<h1>{}</h1>", title);
+        }
+
+        html.push_str("<table id=\"board\">\n");
+        for i in self.lines() {
+            html.push_str("<tr>");
+
+            for j in self.columns() {
+                // Writing to a string cannot fail
+                let _ = match (self.state(Index(i, j)), self[(i, j)]) {
+                    (Some(CellState::Given), Some(cell)) => {
+                        write!(html, "<td class=\"given\">{}</td>", cell)
+                    }
+                    _ => write!(html, "<td></td>"),
+                };
+            }
+
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</table>\n");
+        html.push_str("<p>Click a cell to cycle between 0, 1 and empty.</p>\n");
+
+        if solution {
+            let lines: Vec<String> = self
+                .lines()
+                .map(|i| {
+                    let line: String = self
+                        .line(i)
+                        .map(|cell| cell.map_or(String::new(), |cell| cell.to_string()))
+                        .map(|cell| format!("{:?}", cell))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+
+                    format!("[{}]", line)
+                })
+                .collect();
+
+            html.push_str("<p><button onclick=\"check()\">Check</button> ");
+            html.push_str("<button onclick=\"reveal()\">Show solution</button></p>\n");
+            html.push_str("<p id=\"status\"></p>\n");
+            let _ = writeln!(
+                html,
+                "<script>const solution = [{}];</script>",
+                lines.join(", ")
+            );
+        }
+
+        html.push_str(SCRIPT);
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+// Text put inside tags, such as the puzzle title
+pub(crate) fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export() {
+        let input = vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"];
+        let mut grid = Grid::parse(input.into_iter()).unwrap();
+        grid.solve().unwrap();
+
+        let html = grid.to_html(false);
+        assert!(html.starts_with("<!DOCTYPE html>") && html.ends_with("</html>\n"));
+        assert_eq!(html.matches("<td").count(), 16);
+        assert_eq!(html.matches("class=\"given\"").count(), 7);
+        assert!(!html.contains("const solution"));
+
+        let html = grid.to_html(true);
+        assert!(html.contains(r#"const solution = [["1", "1", "0", "0"], ["0", "0", "1", "1"]"#));
+    }
+}
@@ -1,32 +1,72 @@
-use std::ops;
+use core::ops;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Index(pub usize, pub usize);
 
 impl Index {
+    // The cell some lines below, or above for a negative offset, panicking
+    // when it falls outside of the grid's first line
     pub fn line<T>(&self, i: T) -> Self
     where
         T: Into<i64>,
     {
-        let i = i.into();
-
-        if i.is_positive() {
-            *self + Index(i as usize, 0)
-        } else {
-            *self - Index(i.abs() as usize, 0)
-        }
+        self.checked_line(i).expect("line offset out of range")
     }
 
+    // The cell some columns to the right, or to the left for a negative
+    // offset, panicking when it falls outside of the grid's first column
     pub fn col<T>(&self, j: T) -> Self
     where
         T: Into<i64>,
     {
-        let j = j.into();
+        self.checked_col(j).expect("column offset out of range")
+    }
+
+    // The cell some lines below or above, if the line exists
+    pub fn checked_line<T>(&self, i: T) -> Option<Self>
+    where
+        T: Into<i64>,
+    {
+        Some(Index(Self::offset(self.0, i.into())?, self.1))
+    }
+
+    // The cell some columns to the right or to the left, if the column exists
+    pub fn checked_col<T>(&self, j: T) -> Option<Self>
+    where
+        T: Into<i64>,
+    {
+        Some(Index(self.0, Self::offset(self.1, j.into())?))
+    }
+
+    // The cells right above, below, left and right of the cell, leaving out
+    // those before the first line or column
+    pub fn neighbors(&self) -> impl Iterator<Item = Index> {
+        [
+            self.checked_line(-1),
+            self.checked_line(1),
+            self.checked_col(-1),
+            self.checked_col(1),
+        ]
+        .into_iter()
+        .flatten()
+    }
+
+    fn offset(k: usize, offset: i64) -> Option<usize> {
+        k.checked_add_signed(isize::try_from(offset).ok()?)
+    }
+}
 
-        if j.is_positive() {
-            *self + Index(0, j as usize)
-        } else {
-            *self - Index(0, j.abs() as usize)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Lane {
+    Line(usize),
+    Column(usize),
+}
+
+impl Lane {
+    pub fn index(&self, k: usize) -> Index {
+        match self {
+            Self::Line(i) => Index(*i, k),
+            Self::Column(j) => Index(k, *j),
         }
     }
 }
@@ -60,3 +100,30 @@ impl From<(usize, usize)> for Index {
         Index(i, j)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn offsets() {
+        let idx = Index(1, 0);
+
+        assert_eq!(idx.checked_line(-1), Some(Index(0, 0)));
+        assert_eq!(idx.checked_line(-2), None);
+        assert_eq!(idx.checked_col(3), Some(Index(1, 3)));
+        assert_eq!(idx.checked_col(-1), None);
+        assert_eq!(idx.checked_line(i64::MIN), None);
+        assert_eq!(Index(usize::MAX, 0).checked_line(1), None);
+
+        let neighbors: Vec<_> = idx.neighbors().collect();
+        assert_eq!(neighbors, vec![Index(0, 0), Index(2, 0), Index(1, 1)]);
+        assert_eq!(idx.line(1).col(2), Index(2, 2));
+    }
+
+    #[test]
+    #[should_panic(expected = "line offset out of range")]
+    fn offset_underflow() {
+        Index(1, 0).line(-2);
+    }
+}
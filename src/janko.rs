@@ -0,0 +1,156 @@
+use std::fmt::Write;
+
+use crate::cell::{Cell, CellState};
+use crate::error::ParseError;
+use crate::grid::{Grid, GridCell};
+use crate::index::Index;
+
+impl Grid {
+    // The problem is made of the givens, and the solution is added once the
+    // grid is complete
+    pub fn to_janko(&self) -> String {
+        let mut output = String::from("begin\npuzzle binairo\n");
+
+        // Writing to a string cannot fail
+        if self.width() == self.height() {
+            let _ = writeln!(output, "size {}", self.width());
+        } else {
+            let _ = writeln!(output, "rows {}\ncols {}", self.height(), self.width());
+        }
+
+        output.push_str("problem\n");
+        for i in self.lines() {
+            let line: Vec<String> = self
+                .columns()
+                .map(|j| match (self.state(Index(i, j)), self[(i, j)]) {
+                    (Some(CellState::Given), Some(cell)) => cell.to_string(),
+                    _ => String::from("-"),
+                })
+                .collect();
+
+            let _ = writeln!(output, "{}", line.join(" "));
+        }
+
+        if self.empty_cells().next().is_none() {
+            output.push_str("solution\n");
+
+            for i in self.lines() {
+                let line: Vec<String> = self.line(i).flatten().map(Cell::to_string).collect();
+                let _ = writeln!(output, "{}", line.join(" "));
+            }
+        }
+
+        output.push_str("end\n");
+        output
+    }
+
+    // Only the problem is read, the solution is left to the solver
+    pub fn from_janko(s: &str) -> Result<Grid, ParseError> {
+        // Lines are counted from 1 for errors
+        let mut lines = s
+            .lines()
+            .enumerate()
+            .map(|(n, line)| (n + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty());
+        let (mut width, mut height) = (None, None);
+
+        if lines.next().map(|(_, line)| line) != Some("begin") {
+            return Err(ParseError::InvalidEncoding);
+        }
+
+        while let Some((_, line)) = lines.next() {
+            let (key, value) = line.split_once(' ').unwrap_or((line, ""));
+            let value = value.trim().parse::<usize>();
+
+            match key {
+                "size" => {
+                    let size = value.map_err(|_| ParseError::InvalidEncoding)?;
+                    (width, height) = (Some(size), Some(size));
+                }
+                "rows" => height = Some(value.map_err(|_| ParseError::InvalidEncoding)?),
+                "cols" => width = Some(value.map_err(|_| ParseError::InvalidEncoding)?),
+                "problem" => {
+                    let (Some(width), Some(height)) = (width, height) else {
+                        return Err(ParseError::InvalidEncoding);
+                    };
+
+                    let cells = lines
+                        .by_ref()
+                        .take(height)
+                        .map(|(n, line)| problem_line(line, n, width))
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    if cells.len() != height {
+                        return Err(ParseError::InvalidEncoding);
+                    }
+
+                    return Grid::from_cells(cells);
+                }
+                "end" => break,
+                // Authors, sources and other details are not kept
+                _ => {}
+            }
+        }
+
+        Err(ParseError::InvalidEncoding)
+    }
+}
+
+fn problem_line(line: &str, n: usize, width: usize) -> Result<Vec<GridCell>, ParseError> {
+    let cells = line
+        .split_whitespace()
+        .map(|value| match value {
+            "-" | "." => Ok(None),
+            "0" => Ok(Some(Cell::Zero)),
+            "1" => Ok(Some(Cell::One)),
+            _ => Err(ParseError::InvalidEncoding),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if cells.len() != width {
+        return Err(ParseError::WidthMismatch {
+            line: n,
+            expected: width,
+            found: cells.len(),
+        });
+    }
+
+    Ok(cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let input = vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"];
+        let mut grid = Grid::parse(input.into_iter()).unwrap();
+
+        let janko = grid.to_janko();
+        assert!(janko.starts_with("begin\npuzzle binairo\nsize 4\nproblem\n1 1 - 0\n"));
+        assert!(!janko.contains("solution"));
+        assert_eq!(Grid::from_janko(&janko).unwrap(), grid);
+
+        grid.solve().unwrap();
+        let janko = grid.to_janko();
+        assert!(janko.contains("solution\n1 1 0 0\n0 0 1 1\n1 0 0 1\n0 1 1 0\nend\n"));
+
+        let grid = Grid::parse(vec!["- - - - - -"; 4].into_iter()).unwrap();
+        let janko = grid.to_janko();
+        assert!(janko.contains("rows 4\ncols 6\n"));
+        assert_eq!(Grid::from_janko(&janko).unwrap(), grid);
+    }
+
+    #[test]
+    fn invalid_files() {
+        assert!(Grid::from_janko("").is_err());
+        assert!(Grid::from_janko("begin\nproblem\n1 -\n- -\nend\n").is_err());
+        assert!(Grid::from_janko("begin\nsize 2\nproblem\n1 -\nend\n").is_err());
+        assert!(Grid::from_janko("begin\nsize 2\nproblem\n1 -\n- 2\nend\n").is_err());
+        assert!(matches!(
+            Grid::from_janko("begin\nsize 2\nproblem\n1 - -\n- -\nend\n"),
+            Err(ParseError::WidthMismatch { .. })
+        ));
+    }
+}
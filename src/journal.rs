@@ -0,0 +1,193 @@
+use alloc::vec::Vec;
+
+use crate::cell::CellSet;
+use crate::grid::{Grid, GridCell};
+use crate::index::Index;
+
+// A change made to a grid, with what was there before
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Edit {
+    Cell(Index, GridCell, GridCell),
+    Marks(Index, Option<CellSet>, Option<CellSet>),
+}
+
+// Edits made so far, and the ones undone since the last of them
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Journal {
+    pub(crate) done: Vec<Edit>,
+    pub(crate) undone: Vec<Edit>,
+}
+
+// A point in the edits of a grid, to undo everything made after it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Checkpoint(usize);
+
+impl Grid {
+    // Record the edits made from now on, so they can be undone; solving
+    // the grid is not recorded
+    pub fn start_journal(&mut self) {
+        self.journal.get_or_insert_with(Journal::default);
+    }
+
+    // Forget the edits made so far, and stop recording them
+    pub fn stop_journal(&mut self) {
+        self.journal = None;
+    }
+
+    // Fill or empty a cell, as a player would
+    pub fn set_cell<I>(&mut self, idx: I, cell: GridCell) -> bool
+    where
+        I: Into<Index>,
+    {
+        let idx = idx.into();
+        assert!(
+            idx.0 < self.height() && idx.1 < self.width(),
+            "cells are inside the grid"
+        );
+        assert!(
+            cell.is_none() || !self.is_hole(idx),
+            "holes are never filled"
+        );
+
+        let old = self[idx];
+        let changed = self.set(idx, cell);
+
+        if changed {
+            self.record(Edit::Cell(idx, old, cell));
+        }
+
+        changed
+    }
+
+    pub(crate) fn record_marks(&mut self, idx: Index, old: Option<CellSet>, new: Option<CellSet>) {
+        if old != new {
+            self.record(Edit::Marks(idx, old, new));
+        }
+    }
+
+    fn record(&mut self, edit: Edit) {
+        // A new edit replaces the ones undone
+        if let Some(journal) = &mut self.journal {
+            journal.done.push(edit);
+            journal.undone.clear();
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.journal
+            .as_ref()
+            .is_some_and(|journal| !journal.done.is_empty())
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.journal
+            .as_ref()
+            .is_some_and(|journal| !journal.undone.is_empty())
+    }
+
+    // Revert the last edit, telling whether there was one
+    pub fn undo(&mut self) -> bool {
+        let Some(edit) = self.journal.as_mut().and_then(|journal| journal.done.pop()) else {
+            return false;
+        };
+
+        match edit {
+            Edit::Cell(idx, old, _) => self.set(idx, old),
+            Edit::Marks(idx, old, _) => self.replace_marks(idx, old),
+        };
+
+        if let Some(journal) = &mut self.journal {
+            journal.undone.push(edit);
+        }
+
+        true
+    }
+
+    // Make the last undone edit again, telling whether there was one
+    pub fn redo(&mut self) -> bool {
+        let Some(edit) = self
+            .journal
+            .as_mut()
+            .and_then(|journal| journal.undone.pop())
+        else {
+            return false;
+        };
+
+        match edit {
+            Edit::Cell(idx, _, new) => self.set(idx, new),
+            Edit::Marks(idx, _, new) => self.replace_marks(idx, new),
+        };
+
+        if let Some(journal) = &mut self.journal {
+            journal.done.push(edit);
+        }
+
+        true
+    }
+
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(
+            self.journal
+                .as_ref()
+                .map_or(0, |journal| journal.done.len()),
+        )
+    }
+
+    // Undo the edits made since the checkpoint, which can then be redone
+    pub fn undo_to(&mut self, checkpoint: Checkpoint) {
+        while self.checkpoint() > checkpoint && self.undo() {}
+    }
+
+    fn replace_marks(&mut self, idx: Index, marks: Option<CellSet>) -> bool {
+        match marks {
+            Some(marks) => self.marks.insert(idx, marks) != Some(marks),
+            None => self.marks.remove(&idx).is_some(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::Cell;
+
+    #[test]
+    fn undo_redo() {
+        let puzzle = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+        let puzzle = puzzle.unwrap();
+
+        // Nothing is recorded until asked
+        let mut grid = puzzle.clone();
+        grid.set_cell((0, 2), Some(Cell::Zero));
+        assert!(!grid.undo());
+
+        let mut grid = puzzle.clone();
+        grid.start_journal();
+        let start = grid.checkpoint();
+
+        grid.set_cell((0, 2), Some(Cell::Zero));
+        grid.toggle_mark((1, 0), Cell::One);
+        let middle = grid.checkpoint();
+        grid.set_cell((1, 0), Some(Cell::One));
+        grid.set_cell((1, 0), Some(Cell::Zero));
+
+        assert!(grid.undo());
+        assert_eq!(grid[(1, 0)], Some(Cell::One));
+        assert!(grid.redo());
+        assert_eq!(grid[(1, 0)], Some(Cell::Zero));
+        assert!(!grid.redo());
+
+        grid.undo_to(middle);
+        assert_eq!(grid[(1, 0)], None);
+        assert!(grid.marks((1, 0)).is_some());
+
+        // Editing again drops what was undone
+        grid.set_cell((2, 0), Some(Cell::One));
+        assert!(!grid.can_redo());
+
+        grid.undo_to(start);
+        assert_eq!(grid, puzzle);
+        assert!(grid.marks((1, 0)).is_none());
+        assert!(!grid.can_undo());
+    }
+}
@@ -0,0 +1,203 @@
+use serde::{Deserialize, Serialize};
+
+use crate::cell::Cell;
+use crate::error::ParseError;
+use crate::grid::{Grid, GridCell, Shape};
+use crate::index::{Index, Lane};
+use crate::metadata::Metadata;
+use crate::parse::ParseOptions;
+use crate::rules::Rules;
+use crate::sign::Sign;
+
+// Cells are 0, 1 or null, one array per line, next to the metadata fields
+#[derive(Serialize, Deserialize)]
+struct RawGrid {
+    width: usize,
+    height: usize,
+    cells: Vec<Vec<Option<u8>>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    signs: Vec<RawSign>,
+    #[serde(default, skip_serializing_if = "Rules::is_standard")]
+    rules: Rules,
+    // The label of the region of each cell, one string per line
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    regions: Vec<String>,
+    // Cells outside of the puzzle, as (line, column) pairs
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    holes: Vec<(usize, usize)>,
+    // Counts of ones given in the margins, null for lanes without one
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    line_targets: Vec<Option<usize>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    column_targets: Vec<Option<usize>>,
+    #[serde(flatten)]
+    metadata: Metadata,
+}
+
+// Signs of Binairo+ puzzles, between the cells at two (line, column) pairs
+#[derive(Serialize, Deserialize)]
+struct RawSign {
+    from: (usize, usize),
+    to: (usize, usize),
+    sign: Sign,
+}
+
+impl Grid {
+    pub fn to_json(&self) -> String {
+        let cells = self
+            .lines()
+            .map(|i| {
+                self.line(i)
+                    .map(|cell| cell.map(|cell| (cell == Cell::One) as u8))
+                    .collect()
+            })
+            .collect();
+
+        let raw = RawGrid {
+            width: self.width(),
+            height: self.height(),
+            cells,
+            signs: self
+                .signs()
+                .map(|(a, b, sign)| RawSign {
+                    from: (a.0, a.1),
+                    to: (b.0, b.1),
+                    sign,
+                })
+                .collect(),
+            rules: self.rules(),
+            regions: self
+                .lines()
+                .filter(|_| self.regions().next().is_some())
+                .map(|i| self.columns().filter_map(|j| self.region((i, j))).collect())
+                .collect(),
+            holes: self.holes().map(|idx| (idx.0, idx.1)).collect(),
+            line_targets: self
+                .lines()
+                .filter(|_| self.has_targets())
+                .map(|i| self.target(Lane::Line(i)))
+                .collect(),
+            column_targets: self
+                .columns()
+                .filter(|_| self.has_targets())
+                .map(|j| self.target(Lane::Column(j)))
+                .collect(),
+            metadata: self.metadata().clone(),
+        };
+
+        serde_json::to_string(&raw).expect("grids serialize to JSON")
+    }
+
+    pub fn from_json(s: &str) -> Result<Grid, ParseError> {
+        let raw: RawGrid = serde_json::from_str(s).map_err(|_| ParseError::InvalidEncoding)?;
+
+        ParseOptions::default().check_size(raw.width, raw.height)?;
+
+        if raw.cells.len() != raw.height {
+            return Err(ParseError::InvalidEncoding);
+        }
+
+        let cells = raw
+            .cells
+            .into_iter()
+            .enumerate()
+            .map(|(i, line)| {
+                if line.len() != raw.width {
+                    return Err(ParseError::WidthMismatch {
+                        line: i + 1,
+                        expected: raw.width,
+                        found: line.len(),
+                    });
+                }
+
+                line.into_iter()
+                    .map(|cell| match cell {
+                        None => Ok(None),
+                        Some(0) => Ok(Some(Cell::Zero)),
+                        Some(1) => Ok(Some(Cell::One)),
+                        Some(_) => Err(ParseError::InvalidEncoding),
+                    })
+                    .collect::<Result<Vec<GridCell>, _>>()
+            })
+            .collect::<Result<_, _>>()?;
+
+        let mut holes: Vec<Index> = raw.holes.into_iter().map(Index::from).collect();
+        holes.sort();
+        holes.dedup();
+
+        if holes
+            .iter()
+            .any(|idx| idx.0 >= raw.height || idx.1 >= raw.width)
+        {
+            return Err(ParseError::InvalidEncoding);
+        }
+
+        let targets = [
+            (&raw.line_targets, raw.height),
+            (&raw.column_targets, raw.width),
+        ];
+
+        if targets
+            .iter()
+            .any(|(targets, size)| !targets.is_empty() && targets.len() != *size)
+        {
+            return Err(ParseError::InvalidEncoding);
+        }
+
+        let shape = Shape {
+            holes,
+            line_targets: raw.line_targets,
+            column_targets: raw.column_targets,
+        };
+
+        let mut grid = Grid::from_cells_with_shape(cells, &shape, raw.rules)?;
+
+        for RawSign { from, to, sign } in raw.signs {
+            let (a, b) = (Index::from(from), Index::from(to));
+            let inside = |idx: Index| idx.0 < grid.height() && idx.1 < grid.width();
+
+            if !inside(a) || !inside(b) || a.0.abs_diff(b.0) + a.1.abs_diff(b.1) != 1 {
+                return Err(ParseError::InvalidEncoding);
+            }
+
+            grid.set_sign(a, b, Some(sign));
+        }
+
+        if !raw.regions.is_empty() {
+            grid.parse_regions(raw.regions.iter())?;
+        }
+
+        grid.is_valid().map_err(ParseError::InvalidGrid)?;
+        grid.set_metadata(raw.metadata);
+        Ok(grid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export() {
+        let grid = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+
+        assert_eq!(
+            grid.unwrap().to_json(),
+            r#"{"width":4,"height":4,"cells":[[1,1,null,0],[null,0,null,null],[null,null,0,null],[null,1,null,0]]}"#
+        );
+    }
+
+    #[test]
+    fn import() {
+        let grid = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+        let grid = grid.unwrap();
+
+        assert_eq!(Grid::from_json(&grid.to_json()).unwrap(), grid);
+
+        assert!(Grid::from_json("").is_err());
+        assert!(Grid::from_json(r#"{"width":2,"height":2,"cells":[[1,0]]}"#).is_err());
+        assert!(Grid::from_json(r#"{"width":2,"height":2,"cells":[[1,0],[0]]}"#).is_err());
+        assert!(Grid::from_json(r#"{"width":2,"height":2,"cells":[[1,0],[0,2]]}"#).is_err());
+        assert!(Grid::from_json(r#"{"width":2,"height":2,"cells":[[1,1],[0,0]]}"#).is_err());
+    }
+}
@@ -0,0 +1,77 @@
+use std::fmt::Write;
+
+use crate::cell::CellState;
+use crate::grid::Grid;
+use crate::index::Index;
+
+impl Grid {
+    // A TikZ picture of the puzzle made of the givens, followed by the grid
+    // itself on a new page as its solution
+    pub fn to_latex(&self, solution: bool) -> String {
+        let mut latex = self.tikz(false);
+
+        if solution {
+            latex.push_str("\\newpage\n");
+            latex.push_str(&self.tikz(true));
+        }
+
+        latex
+    }
+
+    fn tikz(&self, solution: bool) -> String {
+        let (width, height) = (self.width(), self.height());
+        let mut tikz = String::from("\\begin{tikzpicture}[scale=0.8]\n");
+
+        // Writing to a string cannot fail
+        let _ = writeln!(tikz, "  \\draw[gray] (0, 0) grid ({}, {});", width, height);
+        let _ = writeln!(
+            tikz,
+            "  \\draw[very thick] (0, 0) rectangle ({}, {});",
+            width, height
+        );
+
+        for i in self.lines() {
+            for j in self.columns() {
+                let Some(cell) = self[(i, j)] else {
+                    continue;
+                };
+
+                // The first line is drawn at the top
+                let (x, y) = (j as f32 + 0.5, (height - i) as f32 - 0.5);
+
+                let style = match self.state(Index(i, j)) {
+                    Some(CellState::Given) => "\\bfseries\\Large",
+                    _ if solution => "\\Large",
+                    _ => continue,
+                };
+
+                let _ = writeln!(tikz, "  \\node at ({}, {}) {{{} {}}};", x, y, style, cell);
+            }
+        }
+
+        tikz.push_str("\\end{tikzpicture}\n");
+        tikz
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export() {
+        let input = vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"];
+        let mut grid = Grid::parse(input.into_iter()).unwrap();
+        grid.solve().unwrap();
+
+        let latex = grid.to_latex(false);
+        assert!(latex.starts_with("\\begin{tikzpicture}"));
+        assert!(latex.ends_with("\\end{tikzpicture}\n"));
+        assert!(latex.contains("\\node at (0.5, 3.5) {\\bfseries\\Large 1};"));
+        assert_eq!(latex.matches("\\node").count(), 7);
+
+        let latex = grid.to_latex(true);
+        assert_eq!(latex.matches("\\newpage").count(), 1);
+        assert_eq!(latex.matches("\\node").count(), 7 + 16);
+    }
+}
@@ -0,0 +1,84 @@
+use crate::grid::Grid;
+use crate::parse::ParseOptions;
+
+impl Grid {
+    // The lines the grid was parsed from, with their empty cells filled from
+    // this grid, keeping spacing and comments untouched
+    pub fn fill_layout<I, S>(&self, lines: I, options: &ParseOptions) -> String
+    where
+        I: Iterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut output = String::new();
+        let mut i = 0;
+
+        for line in lines {
+            let line = line.as_ref();
+            let content = options.strip_comment(line);
+
+            if content.trim().is_empty() || i >= self.height() {
+                output.push_str(line);
+                output.push('\n');
+                continue;
+            }
+
+            let mut j = 0;
+            for c in content.chars() {
+                if c.is_whitespace() {
+                    output.push(c);
+                    continue;
+                }
+
+                match options.cell(c) {
+                    Ok(None) if j < self.width() => match self[(i, j)] {
+                        Some(cell) => output.push(options.symbol(cell)),
+                        None => output.push(c),
+                    },
+                    _ => output.push(c),
+                }
+
+                j += 1;
+            }
+
+            output.push_str(&line[content.len()..]);
+            output.push('\n');
+            i += 1;
+        }
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn layout() {
+        let input = [
+            "# puzzle",
+            "1 1 - 0",
+            "",
+            "-0--  # second",
+            "- - 0 -",
+            "- 1 - 0",
+        ];
+        let mut grid = Grid::parse(input.iter()).unwrap();
+        grid.solve().unwrap();
+
+        assert_eq!(
+            grid.fill_layout(input.iter(), &ParseOptions::default()),
+            "# puzzle\n1 1 0 0\n\n0011  # second\n1 0 0 1\n0 1 1 0\n"
+        );
+
+        let options = ParseOptions::new().zero('O').one('X').comment("//");
+        let input = ["X X . O // done", ". O . .", ". . O .", ". X . O"];
+        let mut grid = Grid::parse_with(input.iter(), &options).unwrap();
+        grid.solve().unwrap();
+
+        assert_eq!(
+            grid.fill_layout(input.iter(), &options),
+            "X X O O // done\nO O X X\nX O O X\nO X X O\n"
+        );
+    }
+}
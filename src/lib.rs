@@ -0,0 +1,119 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+mod binarypuzzle;
+mod bits;
+mod board;
+pub mod cell;
+#[cfg(feature = "std")]
+mod collection;
+mod compact;
+#[cfg(feature = "std")]
+mod csv;
+pub mod deduction;
+mod diff;
+#[cfg(feature = "std")]
+mod dimacs;
+pub mod display;
+#[cfg(feature = "std")]
+mod dot;
+pub mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod fixed;
+#[cfg(feature = "arbitrary")]
+mod fuzz;
+pub mod generator;
+pub mod grid;
+pub mod hint;
+#[cfg(feature = "std")]
+mod html;
+pub mod index;
+#[cfg(feature = "std")]
+mod janko;
+mod journal;
+#[cfg(feature = "std")]
+mod json;
+#[cfg(feature = "std")]
+mod latex;
+#[cfg(feature = "std")]
+mod layout;
+mod line_format;
+pub mod lint;
+#[cfg(feature = "std")]
+mod markdown;
+mod marks;
+pub mod metadata;
+mod minimize;
+#[cfg(feature = "std")]
+mod minizinc;
+#[cfg(feature = "std")]
+pub mod pack;
+pub mod parse;
+#[cfg(feature = "image")]
+mod png;
+#[cfg(feature = "python")]
+mod python;
+mod random;
+pub mod rating;
+mod region;
+pub mod replay;
+pub mod rules;
+#[cfg(feature = "serve")]
+mod server;
+#[cfg(feature = "std")]
+pub mod session;
+pub mod sign;
+#[cfg(feature = "std")]
+pub mod snapshot;
+pub mod solver;
+#[cfg(feature = "std")]
+mod svg;
+pub mod symbol;
+pub mod symmetry;
+pub mod technique;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+#[cfg(feature = "tui")]
+mod tui;
+#[cfg(feature = "wasm")]
+mod wasm;
+
+pub use cell::{Cell, CellSet, CellState};
+pub use deduction::{Deduction, Step};
+pub use diff::Difference;
+pub use display::DisplayOptions;
+pub use error::{
+    DeadEnd, GridError, Limit, ParseError, Rule, SolveError, VerificationError, Violation,
+};
+pub use fixed::FixedGrid;
+pub use generator::{Generator, GeneratorConfig};
+pub use grid::Grid;
+pub use hint::Hint;
+pub use index::{Index, Lane};
+pub use journal::Checkpoint;
+pub use lint::{Check, Finding, Lint, LintConfig, Status};
+pub use metadata::Metadata;
+#[cfg(feature = "std")]
+pub use pack::{Pack, PackEntry};
+pub use parse::{split_lines, ParseOptions};
+pub use rating::{Difficulty, Level, Rating};
+pub use replay::{Move, Replay};
+pub use rules::Rules;
+#[cfg(feature = "serve")]
+pub use server::serve;
+#[cfg(feature = "std")]
+pub use session::Session;
+pub use sign::Sign;
+#[cfg(feature = "std")]
+pub use snapshot::{Progress, Snapshot};
+pub use solver::{solve_all, solve_all_with, GuessStrategy, SolveStats, SolverConfig};
+#[cfg(feature = "std")]
+pub use svg::SvgStyle;
+pub use symbol::{Symbol, SymbolGrid};
+pub use symmetry::Symmetry;
+pub use technique::Technique;
+#[cfg(feature = "tui")]
+pub use tui::{play, play_session};
@@ -0,0 +1,93 @@
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::cell::Cell;
+use crate::error::ParseError;
+use crate::grid::{Grid, GridCell};
+
+impl Grid {
+    pub fn to_line_format(&self) -> String {
+        let cells: String = self
+            .lines()
+            .flat_map(|i| self.line(i))
+            .map(|cell| match cell {
+                Some(Cell::Zero) => '0',
+                Some(Cell::One) => '1',
+                None => '.',
+            })
+            .collect();
+
+        // Square grids do not need their dimensions
+        if self.width() == self.height() {
+            cells
+        } else {
+            format!("{}x{}:{}", self.width(), self.height(), cells)
+        }
+    }
+
+    pub fn from_line_format(s: &str) -> Result<Grid, ParseError> {
+        let (header, data) = match s.trim().split_once(':') {
+            Some((header, data)) => (Some(header), data),
+            None => (None, s.trim()),
+        };
+
+        let cells: Vec<GridCell> = data
+            .chars()
+            .map(|c| match c {
+                '.' => Ok(None),
+                _ => Cell::try_from(c).map(Some),
+            })
+            .collect::<Result<_, _>>()?;
+
+        let (width, height) = match header {
+            Some(header) => {
+                let (width, height) = header.split_once('x').ok_or(ParseError::InvalidEncoding)?;
+                let width: usize = width.parse().map_err(|_| ParseError::InvalidEncoding)?;
+                let height: usize = height.parse().map_err(|_| ParseError::InvalidEncoding)?;
+
+                (width, height)
+            }
+            None => {
+                let size = cells.len().isqrt();
+                (size, size)
+            }
+        };
+
+        if width.checked_mul(height) != Some(cells.len()) {
+            return Err(ParseError::InvalidEncoding);
+        }
+
+        Grid::from_cells(cells.chunks(width.max(1)).map(<[_]>::to_vec).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let grid = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+        let grid = grid.unwrap();
+
+        assert_eq!(grid.to_line_format(), "11.0.0....0..1.0");
+        assert_eq!(Grid::from_line_format("11.0.0....0..1.0").unwrap(), grid);
+
+        let grid = Grid::parse(vec!["- - - - - -"; 4].into_iter()).unwrap();
+        assert_eq!(grid.to_line_format(), format!("6x4:{}", ".".repeat(24)));
+        assert_eq!(
+            Grid::from_line_format(&grid.to_line_format()).unwrap(),
+            grid
+        );
+    }
+
+    #[test]
+    fn invalid_strings() {
+        assert!(Grid::from_line_format("").is_err());
+        assert!(Grid::from_line_format("11.0.0....0..1.").is_err());
+        assert!(Grid::from_line_format("11.0.0....0..1.x").is_err());
+        assert!(Grid::from_line_format("4x3:11.0.0....0..1.0").is_err());
+        assert!(Grid::from_line_format("4:11.0.0....0..1.0").is_err());
+    }
+}
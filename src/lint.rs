@@ -0,0 +1,300 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::error::SolveError;
+use crate::grid::Grid;
+use crate::solver::SolverConfig;
+
+// Checks run on a puzzle before accepting it, each relying on the ones
+// before it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Check {
+    // The givens follow the rules
+    Rules,
+    Solvable,
+    Unique,
+    // Deductions alone finish the grid
+    LogicOnly,
+    // The share of given cells is within bounds
+    Density,
+}
+
+impl Check {
+    pub fn iter() -> impl Iterator<Item = Check> {
+        [
+            Self::Rules,
+            Self::Solvable,
+            Self::Unique,
+            Self::LogicOnly,
+            Self::Density,
+        ]
+        .into_iter()
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Rules => write!(fmt, "rules"),
+            Self::Solvable => write!(fmt, "solvable"),
+            Self::Unique => write!(fmt, "unique"),
+            Self::LogicOnly => write!(fmt, "logic-only"),
+            Self::Density => write!(fmt, "density"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    Pass,
+    Fail,
+    // Not run, as a check it relies on failed
+    Skip,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Pass => write!(fmt, "pass"),
+            Self::Fail => write!(fmt, "fail"),
+            Self::Skip => write!(fmt, "skip"),
+        }
+    }
+}
+
+// Outcome of a check, with what was found when there is more to tell
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Finding {
+    check: Check,
+    status: Status,
+    detail: Option<String>,
+}
+
+impl Finding {
+    pub fn check(&self) -> Check {
+        self.check
+    }
+
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    pub fn detail(&self) -> Option<&str> {
+        self.detail.as_deref()
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}: {}", self.check, self.status)?;
+
+        match &self.detail {
+            Some(detail) => write!(fmt, " ({})", detail),
+            None => Ok(()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lint {
+    findings: Vec<Finding>,
+}
+
+impl Lint {
+    // Findings of every check, in the order they were run
+    pub fn findings(&self) -> &[Finding] {
+        &self.findings
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &Finding> + '_ {
+        self.findings
+            .iter()
+            .filter(|finding| finding.status == Status::Fail)
+    }
+
+    pub fn passed(&self) -> bool {
+        self.failures().next().is_none()
+    }
+
+    pub fn finding(&self, check: Check) -> Option<&Finding> {
+        self.findings.iter().find(|finding| finding.check == check)
+    }
+}
+
+impl fmt::Display for Lint {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        for (k, finding) in self.findings.iter().enumerate() {
+            if k > 0 {
+                writeln!(fmt)?;
+            }
+
+            write!(fmt, "{}", finding)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LintConfig {
+    density: (f32, f32),
+}
+
+impl LintConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Shares of given cells puzzles are expected to have, as too few clues
+    // make them tedious and too many make them trivial
+    pub fn density(mut self, min: f32, max: f32) -> Self {
+        assert!(
+            0.0 <= min && min <= max && max <= 1.0,
+            "invalid clue density ({}..{})",
+            min,
+            max
+        );
+
+        self.density = (min, max);
+        self
+    }
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            density: (0.15, 0.6),
+        }
+    }
+}
+
+impl Grid {
+    pub fn lint(&self) -> Lint {
+        self.lint_with(&LintConfig::default())
+    }
+
+    pub fn lint_with(&self, config: &LintConfig) -> Lint {
+        let mut findings = Vec::new();
+        let mut push = |check, status, detail: Option<String>| {
+            findings.push(Finding {
+                check,
+                status,
+                detail,
+            })
+        };
+
+        let rules = self.is_valid().map_err(|violation| violation.to_string());
+        let solution = match &rules {
+            Ok(()) => {
+                let config = SolverConfig::new().require_unique(true);
+                Some(self.clone().solve_with(&config))
+            }
+            Err(_) => None,
+        };
+
+        push(Check::Rules, status(&rules), rules.err());
+
+        match &solution {
+            None => push(Check::Solvable, Status::Skip, None),
+            Some(Err(err @ SolveError::NoSolution(_))) => {
+                push(Check::Solvable, Status::Fail, Some(message(err)))
+            }
+            Some(_) => push(Check::Solvable, Status::Pass, None),
+        }
+
+        match &solution {
+            None | Some(Err(SolveError::NoSolution(_))) => push(Check::Unique, Status::Skip, None),
+            Some(Err(err)) => push(Check::Unique, Status::Fail, Some(message(err))),
+            Some(Ok(())) => push(Check::Unique, Status::Pass, None),
+        }
+
+        // Only grids with a single solution can be finished by deduction
+        if let Some(Ok(())) = solution {
+            let config = SolverConfig::new().max_depth(0);
+            let logic = self
+                .clone()
+                .solve_with(&config)
+                .map_err(|err| message(&err));
+            push(Check::LogicOnly, status(&logic), logic.err());
+        } else {
+            push(Check::LogicOnly, Status::Skip, None);
+        }
+
+        let (min, max) = config.density;
+        let cells = self.width() * self.height() - self.holes().count();
+        let density = self.givens().count() as f32 / cells as f32;
+        let percent = |ratio: f32| (ratio * 100.0 + 0.5) as usize;
+
+        let (status, detail) = if density < min {
+            let bound = format!(", below {}%", percent(min));
+            (Status::Fail, bound)
+        } else if density > max {
+            let bound = format!(", above {}%", percent(max));
+            (Status::Fail, bound)
+        } else {
+            (Status::Pass, String::new())
+        };
+
+        let detail = format!("{}% of the cells are given{}", percent(density), detail);
+        push(Check::Density, status, Some(detail));
+
+        Lint { findings }
+    }
+}
+
+fn status<T>(result: &Result<(), T>) -> Status {
+    match result {
+        Ok(()) => Status::Pass,
+        Err(_) => Status::Fail,
+    }
+}
+
+// Message of an error, without the prefix telling it is one
+fn message(err: &SolveError) -> String {
+    let message = err.to_string();
+    message
+        .strip_prefix("error: ")
+        .unwrap_or(&message)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks() {
+        let grid = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+        let lint = grid.unwrap().lint();
+        assert!(lint.passed());
+        assert_eq!(
+            lint.to_string(),
+            "rules: pass\nsolvable: pass\nunique: pass\nlogic-only: pass\n\
+             density: pass (44% of the cells are given)"
+        );
+
+        // Later checks are skipped once one they rely on fails
+        let grid = Grid::parse(vec!["- - - -"; 4].into_iter()).unwrap();
+        let lint = grid.lint();
+        let failed: Vec<_> = lint.failures().map(Finding::check).collect();
+        assert_eq!(failed, vec![Check::Unique, Check::Density]);
+        assert_eq!(
+            lint.finding(Check::LogicOnly).unwrap().status(),
+            Status::Skip
+        );
+        assert_eq!(
+            lint.finding(Check::Density).unwrap().detail(),
+            Some("0% of the cells are given, below 15%")
+        );
+
+        let grid = Grid::parse(vec!["0 - - 0", "0 - - 0", "- - - 1", "- - - -"].into_iter());
+        let lint = grid
+            .unwrap()
+            .lint_with(&LintConfig::new().density(0.0, 0.25));
+        let failed: Vec<_> = lint.failures().map(Finding::check).collect();
+        assert_eq!(failed, vec![Check::Solvable, Check::Density]);
+        assert_eq!(lint.finding(Check::Unique).unwrap().status(), Status::Skip);
+    }
+}
@@ -1,41 +1,2331 @@
 use std::env;
+use std::fmt;
 use std::fs;
 use std::io;
-use std::io::BufRead;
+use std::io::{BufRead, IsTerminal, Write};
+use std::mem;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
 
-mod cell;
-mod error;
-mod grid;
-mod index;
+use clap::{ArgAction, Args, CommandFactory, Parser, Subcommand, ValueEnum};
+
+use binero::{
+    Cell, Check, Difference, Difficulty, DisplayOptions, Generator, GeneratorConfig, Grid,
+    GuessStrategy, Hint, Index, Lane, Level, Limit, LintConfig, Pack, ParseError, ParseOptions,
+    Progress, Rules, Snapshot, SolveError, SolveStats, SolverConfig, Step, SvgStyle, Symmetry,
+    Technique, VerificationError, Violation,
+};
+
+// Time limit of each request, unless given with --max-time
+#[cfg(feature = "serve")]
+const SERVE_MAX_TIME: Duration = Duration::from_secs(10);
+
+// Time for the events of a single save to settle
+#[cfg(feature = "watch")]
+const WATCH_DELAY: Duration = Duration::from_millis(100);
+
+#[derive(Parser)]
+#[command(version, about = "Solve, check and generate binero puzzles")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    #[command(flatten)]
+    solver: SolverArgs,
+
+    /// Highlight the cells filled by the solver
+    #[arg(long, global = true, value_enum, default_value_t = ColorChoice::Auto)]
+    color: ColorChoice,
+
+    /// Log the solver steps on the standard error, -vv for every filled cell
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    verbose: u8,
+
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// How errors are printed on the standard error
+    #[arg(long, global = true, value_enum, default_value_t = ErrorFormat::Text)]
+    error_format: ErrorFormat,
+}
+
+#[derive(Args)]
+struct InputArgs {
+    /// Format of the grids read
+    #[arg(long, global = true, value_enum, default_value_t = InputFormat::Auto)]
+    input_format: InputFormat,
+
+    /// Characters standing for empty cells in text grids [default: -._?]
+    #[arg(long, global = true)]
+    empty_chars: Option<String>,
+
+    /// Start of comments in text grids
+    #[arg(long, global = true, default_value = "#", value_parser = clap::builder::NonEmptyStringValueParser::new())]
+    comment: String,
+
+    /// Read the grid with this number, from 1, in files holding several
+    #[arg(long, global = true, conflicts_with = "name", value_parser = clap::value_parser!(u64).range(1..))]
+    index: Option<u64>,
+
+    /// Read the grid under this `== name ==` header in files holding several
+    #[arg(long, global = true)]
+    name: Option<String>,
+
+    /// Characters for zeros and ones, such as OX, when reading and printing grids
+    #[arg(long, global = true, value_parser = parse_symbols)]
+    symbols: Option<(char, char)>,
+
+    /// Wrap lines and columns around, their first and last cells being next to each other
+    #[arg(long, global = true)]
+    torus: bool,
+
+    /// Accept grids with an odd number of lines or columns, whose lanes hold one more cell of a value
+    #[arg(long, global = true)]
+    odd: bool,
+
+    /// Share of ones in each lane, such as 1/3, instead of one half
+    #[arg(long, global = true, value_parser = parse_ones_ratio)]
+    ratio: Option<(u32, u32)>,
+
+    /// Most identical cells allowed next to each other, instead of two
+    #[arg(long, global = true, value_parser = clap::value_parser!(u64).range(1..64))]
+    max_run: Option<u64>,
+
+    /// Apply the adjacency rule to both diagonals of square grids too
+    #[arg(long, global = true)]
+    diagonals: bool,
+
+    /// Apply the balance rule to both diagonals as well
+    #[arg(long, global = true, requires = "diagonals")]
+    diagonal_balance: bool,
+
+    /// File splitting the grid into regions, with a letter for each cell, each region holding as many ones as zeros
+    #[arg(long, global = true)]
+    regions: Option<PathBuf>,
+
+    /// Read the counts of ones of text grids in their margins: one per column on the first line, then one before the cells of each line
+    #[arg(long, global = true)]
+    margins: bool,
+
+    /// Read full-width characters of text grids, such as ０ and １, and the digits of other scripts as their ASCII counterparts
+    #[arg(long, global = true)]
+    unicode_digits: bool,
+
+    /// Rules the grid does not follow, separated by commas
+    #[arg(long, global = true, value_enum, value_delimiter = ',')]
+    ignore_rule: Vec<RuleChoice>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum RuleChoice {
+    /// No run of identical cells longer than allowed
+    Adjacency,
+    /// As many ones as zeros in each lane, or their share of ones
+    Balance,
+    /// No two identical lines
+    UniqueLines,
+    /// No two identical columns
+    UniqueColumns,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum InputFormat {
+    /// Guess from the file extension, then from its content
+    Auto,
+    Text,
+    Json,
+    Csv,
+    Line,
+    Binarypuzzle,
+    Janko,
+    /// Codes printed by the share subcommand
+    Code,
+    /// Puzzle packs, as saved by the generate subcommand
+    Pack,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ErrorFormat {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Args)]
+struct SolverArgs {
+    /// Seed for the order of guesses, or for generated puzzles
+    #[arg(long, global = true)]
+    seed: Option<u64>,
+
+    /// Guess the first empty cell instead of the most constrained one
+    #[arg(long, global = true)]
+    first_empty: bool,
+
+    /// Only use the basic rules to fill cells between guesses
+    #[arg(long, global = true)]
+    no_heuristics: bool,
+
+    /// Only deduce with these techniques, without guessing
+    #[arg(
+        long,
+        global = true,
+        value_delimiter = ',',
+        value_parser = parse_technique,
+        conflicts_with = "no_heuristics"
+    )]
+    techniques: Option<Vec<Technique>>,
+
+    /// Only deduce like a solver of this level: beginner, intermediate,
+    /// advanced or expert
+    #[arg(
+        long,
+        global = true,
+        value_parser = parse_level,
+        conflicts_with_all = ["no_heuristics", "techniques"]
+    )]
+    level: Option<Level>,
+
+    /// Also deduce from the puzzle having a single solution, which is wrong
+    /// for puzzles with several
+    #[arg(long, global = true)]
+    assume_unique: bool,
+
+    /// Give the solution coming first in reading order, zeros first, when
+    /// there are several
+    #[arg(long, global = true)]
+    smallest: bool,
+
+    /// Fail on grids with more than one solution
+    #[arg(long, global = true)]
+    require_unique: bool,
+
+    /// Rule out values leading to a contradiction within this many trials
+    #[arg(long, global = true, default_value_t = 0)]
+    lookahead: usize,
+
+    /// Give up solving after this long, such as 2s or 500ms
+    #[arg(long, global = true, value_parser = parse_duration)]
+    max_time: Option<Duration>,
+
+    /// Give up solving after this many guesses
+    #[arg(long, global = true)]
+    max_nodes: Option<usize>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve one or more grids
+    Solve(SolveArgs),
+    /// Check that grids follow the rules and have a single solution
+    Check {
+        #[arg(default_value = "-")]
+        files: Vec<PathBuf>,
+    },
+    /// Generate new puzzles
+    Generate(GenerateArgs),
+    /// Run the checks expected of a puzzle before accepting it, and print a report
+    Lint {
+        #[arg(default_value = "-")]
+        files: Vec<PathBuf>,
+        /// Smallest share of given cells, between 0 and 1
+        #[arg(long, value_parser = parse_ratio, default_value = "0.15")]
+        min_density: f32,
+        /// Largest share of given cells, between 0 and 1
+        #[arg(long, value_parser = parse_ratio, default_value = "0.6")]
+        max_density: f32,
+    },
+    /// Rate the difficulty of grids
+    Rate {
+        #[arg(default_value = "-")]
+        files: Vec<PathBuf>,
+    },
+    /// Show the value of one more cell
+    Hint {
+        #[arg(default_value = "-")]
+        file: PathBuf,
+
+        /// Only give the lane (1) or the cell (2), or explain the value (3).
+        /// Named tier rather than level, which already picks the techniques
+        /// of the solver
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=3))]
+        tier: Option<u8>,
+    },
+    /// Print the fewest clues that already leave a grid without a solution
+    Conflicts {
+        #[arg(default_value = "-")]
+        file: PathBuf,
+    },
+    /// Carry on a search saved with solve --save-state
+    Resume { state: PathBuf },
+    /// Print each step taken to solve a grid
+    Explain {
+        #[arg(default_value = "-")]
+        file: PathBuf,
+    },
+    /// Count the solutions of a grid
+    Count {
+        #[arg(default_value = "-")]
+        file: PathBuf,
+
+        /// Stop counting after this many solutions
+        #[arg(long, default_value_t = 1000)]
+        limit: usize,
+    },
+    /// Print a short code standing for the grid, to share it as text
+    Share {
+        #[arg(default_value = "-")]
+        file: PathBuf,
+    },
+    /// Draw the solving steps of a grid as an animated SVG image
+    Animate {
+        #[arg(default_value = "-")]
+        file: PathBuf,
+
+        /// Time between two steps, such as 1s or 300ms
+        #[arg(long, default_value = "500ms", value_parser = parse_duration)]
+        delay: Duration,
+
+        /// Size of the cells, in pixels
+        #[arg(long, default_value_t = 32, value_parser = clap::value_parser!(u16).range(1..))]
+        cell_size: u16,
+    },
+    /// Time the solver on every puzzle found in a directory
+    Bench {
+        dir: PathBuf,
+
+        /// Number of times each puzzle is solved, keeping the fastest time
+        #[arg(long, default_value_t = 5, value_parser = clap::value_parser!(u32).range(1..))]
+        repeat: u32,
+
+        /// Print the times as CSV instead of a table
+        #[arg(long)]
+        csv: bool,
+    },
+    /// Compare two grids of the same size, cell by cell
+    Diff { first: PathBuf, second: PathBuf },
+    /// Print the rules of a grid as a SAT problem, in DIMACS CNF format
+    ExportSat {
+        #[arg(default_value = "-")]
+        file: PathBuf,
+    },
+    /// Print a MiniZinc model of a grid, for constraint programming solvers
+    ExportMinizinc {
+        #[arg(default_value = "-")]
+        file: PathBuf,
+    },
+    /// Print the guesses made while solving a grid as a Graphviz graph
+    ExportTree {
+        #[arg(default_value = "-")]
+        file: PathBuf,
+    },
+    /// Print the completion script of a shell
+    #[command(hide = true)]
+    Completions { shell: clap_complete::Shell },
+    /// Print the manual page, in roff format, for the features of this build
+    Manpage,
+    /// Play a puzzle in the terminal
+    #[cfg(feature = "tui")]
+    Play {
+        #[arg(default_value = "-")]
+        file: PathBuf,
+
+        /// Session file the game is saved to and carried on from, holding
+        /// the entries, pencil marks, time and undo history
+        #[arg(long)]
+        session: Option<PathBuf>,
+    },
+    /// Answer solve, rate and generate requests over HTTP
+    #[cfg(feature = "serve")]
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1")]
+        address: String,
+
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+    },
+}
+
+#[derive(Args)]
+struct SolveArgs {
+    #[arg(default_value = "-")]
+    files: Vec<PathBuf>,
+
+    /// File where solutions are written instead of the standard output
+    #[arg(long)]
+    output: Option<PathBuf>,
+
+    /// Serialization of the solutions
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Size of the cells in SVG and PNG images, in pixels
+    #[arg(long, default_value_t = 32, value_parser = clap::value_parser!(u16).range(1..))]
+    cell_size: u16,
+
+    /// Let HTML pages check the player's moves and show the solution, or add
+    /// the solution to LaTeX pictures on a second page
+    #[arg(long)]
+    with_solution: bool,
+
+    /// Print a JSON report per grid, with the solution and solver statistics
+    #[arg(long, conflicts_with = "format")]
+    json: bool,
+
+    /// Only print the solutions, without banners, labels or summary
+    #[arg(short, long)]
+    quiet: bool,
+
+    /// Do not print the input grid again before its solution
+    #[arg(long)]
+    no_echo: bool,
+
+    /// Draw text grids in a box, with line and column numbers
+    #[arg(long)]
+    board: bool,
+
+    /// Separate blocks of this many cells on the board
+    #[arg(long, requires = "board", default_value_t = 0)]
+    blocks: usize,
+
+    /// Solve the grid shared as this code instead of reading files
+    #[arg(long, conflicts_with_all = ["files", "all", "json", "no_bruteforce"])]
+    from_code: Option<String>,
+
+    /// Solve every grid of files holding several
+    #[arg(long, conflicts_with_all = ["index", "name", "json", "no_bruteforce"])]
+    all: bool,
+
+    /// Print solver statistics on the standard error, in total for several grids
+    #[arg(long)]
+    stats: bool,
+
+    /// Save the search to this file when --max-time or --max-nodes stops it,
+    /// so that resume can carry it on
+    #[arg(long, conflicts_with_all = ["all", "json", "no_bruteforce"])]
+    save_state: Option<PathBuf>,
+
+    /// Only fill what can be deduced, and tell how much of the grid is left
+    #[arg(long, conflicts_with_all = ["output", "json"])]
+    no_bruteforce: bool,
+
+    /// Solve the grid again every time its file changes
+    #[cfg(feature = "watch")]
+    #[arg(long, conflicts_with_all = ["output", "json", "all", "from_code"])]
+    watch: bool,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
+    Csv,
+    Svg,
+    Line,
+    Html,
+    Latex,
+    Binarypuzzle,
+    Janko,
+    Markdown,
+    /// The layout of the input file, with its empty cells filled
+    Preserve,
+    #[cfg(feature = "image")]
+    Png,
+}
+
+#[derive(Args)]
+struct GenerateArgs {
+    /// Grid size, as WIDTHxHEIGHT or a single number for a square grid
+    #[arg(long, default_value = "8", value_parser = parse_size)]
+    size: (usize, usize),
+
+    /// Number of puzzles to generate
+    #[arg(long, default_value_t = 1)]
+    count: usize,
+
+    /// One of easy, medium, hard or expert
+    #[arg(long, value_parser = parse_difficulty)]
+    difficulty: Option<Difficulty>,
+
+    /// One of none, rotational, horizontal or vertical
+    #[arg(long, value_parser = parse_symmetry)]
+    symmetry: Option<Symmetry>,
+
+    /// Minimum share of given cells, between 0 and 1
+    #[arg(long, value_parser = parse_ratio)]
+    clue_ratio: Option<f32>,
+
+    /// Only keep puzzles that can be solved without guessing
+    #[arg(long)]
+    logic_only: bool,
+
+    /// Directory where each puzzle is written to its own file
+    #[arg(long, conflicts_with = "pack")]
+    output: Option<PathBuf>,
+
+    /// File where all puzzles are saved as a pack
+    #[arg(long)]
+    pack: Option<PathBuf>,
+
+    /// Add the puzzles to the pack instead of replacing it
+    #[arg(long, requires = "pack")]
+    append: bool,
+}
+
+impl Format {
+    fn render(
+        &self,
+        grid: &Grid,
+        source: &[String],
+        display: &DisplayOptions,
+        args: &SolveArgs,
+    ) -> Vec<u8> {
+        let text = match self {
+            Self::Text => format!(
+                "{}{}\n",
+                grid.metadata(),
+                grid.display_with(display.clone())
+            ),
+            Self::Json => grid.to_json() + "\n",
+            Self::Csv => grid.to_csv(),
+            Self::Svg => grid.to_svg(&SvgStyle::new().cell_size(args.cell_size.into())),
+            Self::Line => grid.to_line_format() + "\n",
+            Self::Html => grid.to_html(args.with_solution),
+            Self::Latex => grid.to_latex(args.with_solution),
+            Self::Binarypuzzle => grid.to_binarypuzzle() + "\n",
+            Self::Janko => grid.to_janko(),
+            Self::Markdown => grid.to_markdown(),
+            Self::Preserve => {
+                let input = INPUT.get().expect("input options are set on startup");
+                grid.fill_layout(source.iter(), &input.options())
+            }
+            #[cfg(feature = "image")]
+            Self::Png => return grid.to_png(args.cell_size.into()),
+        };
+
+        text.into_bytes()
+    }
+}
+
+impl InputArgs {
+    // The chosen format, or the one guessed from the extension then the content
+    fn format(&self, path: &Path, lines: &[String]) -> InputFormat {
+        if self.input_format != InputFormat::Auto {
+            return self.input_format;
+        }
+
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") if is_pack(lines) => return InputFormat::Pack,
+            Some("json") => return InputFormat::Json,
+            Some("csv") => return InputFormat::Csv,
+            _ => {}
+        }
+
+        let options = self.options();
+        let content: Vec<&str> = lines
+            .iter()
+            .map(|line| options.strip_comment(line).trim())
+            .filter(|line| !line.is_empty())
+            .collect();
+
+        match content.as_slice() {
+            [first, ..] if first.starts_with('{') && is_pack(lines) => InputFormat::Pack,
+            [first, ..] if first.starts_with('{') => InputFormat::Json,
+            ["begin", ..] => InputFormat::Janko,
+            // Both single line formats may start with the dimensions, only
+            // puzzle codes do not use dots
+            [line] if line.contains(':') && !line.contains('.') => InputFormat::Binarypuzzle,
+            [line] if is_code(line) => InputFormat::Code,
+            [_] => InputFormat::Line,
+            lines if lines.iter().any(|line| line.contains([',', ';'])) => InputFormat::Csv,
+            _ => InputFormat::Text,
+        }
+    }
+
+    fn options(&self) -> ParseOptions {
+        let (zero, one) = self.symbols.unwrap_or(('0', '1'));
+        let options = ParseOptions::new()
+            .zero(zero)
+            .one(one)
+            .comment(self.comment.as_str())
+            .margins(self.margins)
+            .unicode_digits(self.unicode_digits)
+            .rules(self.rules());
+
+        match &self.empty_chars {
+            Some(chars) => options.empty(chars.chars()),
+            None => options,
+        }
+    }
+
+    fn rules(&self) -> Rules {
+        let ignored = |rule| self.ignore_rule.contains(&rule);
+        let rules = Rules::new()
+            .toroidal(self.torus)
+            .odd_dimensions(self.odd)
+            .diagonals(self.diagonals)
+            .diagonal_balance(self.diagonal_balance)
+            .adjacency(!ignored(RuleChoice::Adjacency))
+            .balance(!ignored(RuleChoice::Balance))
+            .unique_lines(!ignored(RuleChoice::UniqueLines))
+            .unique_columns(!ignored(RuleChoice::UniqueColumns));
+
+        let rules = match self.ratio {
+            Some((ones, cells)) => rules.ratio(ones, cells),
+            None => rules,
+        };
+
+        match self.max_run {
+            Some(run) => rules.max_run(run as usize),
+            None => rules,
+        }
+    }
+
+    // Grids read from other formats than text follow the rules given on the
+    // command line too, unless they bring their own
+    fn apply_rules(&self, mut grid: Grid) -> GridResult {
+        if !self.rules().is_standard() {
+            grid.set_rules(self.rules());
+            grid.is_valid().map_err(ParseError::InvalidGrid)?;
+        }
+
+        Ok(grid)
+    }
+
+    fn apply_regions(&self, mut grid: Grid) -> GridResult {
+        if let Some(path) = &self.regions {
+            let lines = read_lines(path).map_err(|err| FileError::new(path, err))?;
+            grid.parse_regions(lines.iter())?;
+        }
+
+        Ok(grid)
+    }
+}
+
+impl ColorChoice {
+    // Colors are only sent to a terminal, unless NO_COLOR is set
+    fn enabled(&self, terminal: bool) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto => terminal && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()),
+        }
+    }
+}
+
+impl SolverArgs {
+    fn config(&self) -> SolverConfig {
+        let mut config = SolverConfig::new().heuristics(!self.no_heuristics);
+
+        if let Some(seed) = self.seed {
+            config = config.seed(seed);
+        }
+
+        if self.first_empty {
+            config = config.guess_strategy(GuessStrategy::FirstEmpty);
+        }
+
+        if let Some(techniques) = &self.techniques {
+            config = config.techniques(techniques.iter().copied()).max_depth(0);
+        }
+
+        if let Some(level) = self.level {
+            config = config.level(level);
+        }
+
+        if self.assume_unique {
+            config = config.assume_unique(true);
+        }
+
+        if self.smallest {
+            config = config.smallest_solution(true);
+        }
+
+        if self.require_unique {
+            config = config.require_unique(true);
+        }
+
+        if self.lookahead > 0 {
+            config = config.lookahead(self.lookahead);
+        }
+
+        if let Some(time) = self.max_time {
+            config = config.max_time(time);
+        }
+
+        if let Some(nodes) = self.max_nodes {
+            config = config.max_nodes(nodes);
+        }
+
+        config
+    }
+}
+
+// Set once the command line is parsed, grids may be read and errors printed
+// from anywhere
+static INPUT: OnceLock<InputArgs> = OnceLock::new();
+static ERROR_FORMAT: OnceLock<ErrorFormat> = OnceLock::new();
 
 fn main() {
     try_main().unwrap_or_else(|err| {
-        eprintln!("{}", err);
-        std::process::exit(1);
+        report(&*err);
+        std::process::exit(exit_code(&*err).into());
     });
 }
 
 fn try_main() -> Result<(), Box<dyn std::error::Error>> {
-    let args = env::args().collect::<Vec<String>>();
+    let cli = Cli::parse();
+    let config = cli.solver.config();
 
-    if args.len() < 2 {
-        return Err(format!("usage: {} <FILE>", args[0]).into());
+    INPUT.get_or_init(|| cli.input);
+    ERROR_FORMAT.get_or_init(|| cli.error_format);
+
+    if cli.verbose > 0 {
+        let level = match cli.verbose {
+            1 => log::LevelFilter::Debug,
+            _ => log::LevelFilter::Trace,
+        };
+
+        log::set_boxed_logger(Box::new(Logger::new()))?;
+        log::set_max_level(level);
+    }
+
+    match cli.command {
+        Command::Solve(args) => {
+            let color = cli
+                .color
+                .enabled(args.output.is_none() && io::stdout().is_terminal());
+            solve(args, &config, color)
+        }
+        Command::Check { files } => check(&expand(files)),
+        Command::Generate(args) => generate(args, cli.solver.seed),
+        Command::Lint {
+            files,
+            min_density,
+            max_density,
+        } => lint(&expand(files), (min_density, max_density)),
+        Command::Rate { files } => rate(&expand(files)),
+        Command::Hint { file, tier } => hint(&file, tier),
+        Command::Conflicts { file } => conflicts(&file),
+        Command::Resume { state } => resume(&state, &config),
+        Command::Explain { file } => explain(&file),
+        Command::Count { file, limit } => count(&file, limit),
+        Command::Share { file } => share(&file),
+        Command::Animate {
+            file,
+            delay,
+            cell_size,
+        } => animate(&file, delay, cell_size),
+        Command::Bench { dir, repeat, csv } => bench(&dir, repeat, csv, &config),
+        Command::Diff { first, second } => diff(&first, &second),
+        Command::ExportSat { file } => export_sat(&file),
+        Command::ExportMinizinc { file } => export_minizinc(&file),
+        Command::ExportTree { file } => export_tree(&file),
+        Command::Completions { shell } => {
+            clap_complete::generate(shell, &mut Cli::command(), "binero", &mut io::stdout());
+            Ok(())
+        }
+        Command::Manpage => Ok(clap_mangen::Man::new(Cli::command()).render(&mut io::stdout())?),
+        #[cfg(feature = "tui")]
+        Command::Play { file, session } => play(&file, session.as_deref()),
+        #[cfg(feature = "serve")]
+        Command::Serve { address, port } => {
+            // A single request must not keep the solver busy forever
+            let config = match cli.solver.max_time {
+                Some(_) => config,
+                None => config.max_time(SERVE_MAX_TIME),
+            };
+
+            eprintln!("listening on http://{}:{}", address, port);
+            Ok(binero::serve((address.as_str(), port), config)?)
+        }
+    }
+}
+
+fn solve(
+    mut args: SolveArgs,
+    config: &SolverConfig,
+    color: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let paths = expand(mem::take(&mut args.files));
+    let input = INPUT.get().expect("input options are set on startup");
+
+    // The layout can only be kept from a whole text file
+    let text = matches!(input.input_format, InputFormat::Auto | InputFormat::Text);
+    let single = text && input.index.is_none();
+    let whole = single && input.name.is_none() && !args.all && args.from_code.is_none();
+    if args.format == Format::Preserve && !whole {
+        Cli::command()
+            .error(
+                clap::error::ErrorKind::ArgumentConflict,
+                "--format preserve needs text files holding a single grid, without --index, --name or --all",
+            )
+            .exit();
+    }
+
+    let (zero, one) = input.symbols.unwrap_or(('0', '1'));
+    let display = DisplayOptions::new()
+        .zero(zero)
+        .one(one)
+        .color(color)
+        .headers(args.board)
+        .borders(args.board)
+        .blocks(args.blocks);
+
+    #[cfg(feature = "watch")]
+    if args.watch {
+        return watch(&paths, &args, config, &display);
+    }
+
+    let mut output: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(io::BufWriter::new(
+            fs::File::create(path).map_err(|err| FileError::new(path, err))?,
+        )),
+        None => Box::new(io::stdout().lock()),
+    };
+
+    if args.json {
+        return solve_json(&paths, config, output, args.quiet);
+    }
+
+    if args.no_bruteforce {
+        return solve_logic(&paths, &args, config, &display);
+    }
+
+    if let Some(code) = &args.from_code {
+        let grid = Grid::from_compact_string(code)?;
+        return solve_grid(grid, &[], &args, config, &display, &mut output);
+    }
+
+    if let ([path], false) = (paths.as_slice(), args.all) {
+        return solve_file(path, &args, config, &display, &mut output);
+    }
+
+    let mut summary = Summary::default();
+    let mut names = Vec::new();
+    let mut grids = Vec::new();
+    let mut count = 0;
+
+    // Grids that cannot be read are reported, the others are still solved
+    for path in &paths {
+        // The source lines are only needed to keep the layout of whole files
+        let entries = match args.all {
+            true => match read_grids(path) {
+                Ok(grids) => grids
+                    .into_iter()
+                    .map(|(name, grid)| (name, grid.map(|grid| (Vec::new(), grid))))
+                    .collect(),
+                Err(err) => vec![(path.display().to_string(), Err(err.into()))],
+            },
+            false => vec![(
+                path.display().to_string(),
+                read_source(path, args.format == Format::Preserve),
+            )],
+        };
+
+        for (name, grid) in entries {
+            count += 1;
+
+            match grid {
+                Ok((source, grid)) => {
+                    names.push((name, source));
+                    grids.push(grid);
+                }
+                Err(err) => {
+                    report_file(&name, &*err);
+                    summary.unreadable(&*err);
+                }
+            }
+        }
+    }
+
+    let mut total = SolveStats::default();
+    let start = Instant::now();
+
+    // Statistics are gathered one grid at a time
+    let results = if args.stats {
+        grids
+            .into_iter()
+            .map(|mut grid| {
+                total.merge(&grid.solve_with_stats(config)?);
+                Ok(grid)
+            })
+            .collect()
+    } else {
+        binero::solve_all_with(grids, config)
+    };
+
+    let elapsed = start.elapsed();
+
+    for ((name, source), result) in names.into_iter().zip(results) {
+        match &result {
+            Ok(grid) => {
+                // Text solutions are labelled, other formats are self-contained
+                if args.format == Format::Text && !args.quiet {
+                    writeln!(output, "{}:", name)?;
+                }
+
+                output.write_all(&args.format.render(grid, &source, &display, &args))?;
+            }
+            Err(err) => report_file(&name, err),
+        }
+
+        summary.add(&result);
+    }
+
+    output.flush()?;
+    if !args.quiet && !json_errors() {
+        eprintln!("{}", summary);
+    }
+
+    if args.stats {
+        print_stats(&total, elapsed);
+    }
+
+    check_failures(count - summary.solved, count, summary.code)
+}
+
+fn solve_file(
+    path: &Path,
+    args: &SolveArgs,
+    config: &SolverConfig,
+    display: &DisplayOptions,
+    output: &mut dyn Write,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let (source, grid) = read_source(path, args.format == Format::Preserve)
+        .map_err(|err| FileError::new(path, err))?;
+
+    solve_grid(grid, &source, args, config, display, output)
+        .map_err(|err| FileError::new(path, err).into())
+}
+
+fn solve_grid(
+    mut grid: Grid,
+    source: &[String],
+    args: &SolveArgs,
+    config: &SolverConfig,
+    display: &DisplayOptions,
+    output: &mut dyn Write,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let banners = args.output.is_none() && args.format == Format::Text && !args.quiet;
+
+    if banners && !args.no_echo {
+        println!("Input grid:");
+        println!("{}", grid.display_with(display.clone()));
+    }
+
+    // The cells deduced before giving up help to find what went wrong
+    let start = Instant::now();
+    let stats = match &args.save_state {
+        Some(path) => match grid.solve_pausable(config)? {
+            Progress::Solved(stats) => stats,
+            Progress::Paused(snapshot, limit) => {
+                save_state(path, &snapshot)?;
+                return Err(SolveError::LimitExceeded(limit).into());
+            }
+        },
+        None => grid.solve_with_stats(config).inspect_err(|err| {
+            if json_errors() {
+                return;
+            }
+
+            match err {
+                SolveError::LimitExceeded(_) => {
+                    eprintln!("Partial grid:");
+                    eprintln!("{}", grid.display_with(display.clone()));
+                }
+                // The furthest the search went before every value of a cell
+                // was ruled out
+                SolveError::NoSolution(Some(dead_end)) => {
+                    eprintln!("Partial grid:");
+                    eprintln!("{}", dead_end.grid().display_with(display.clone()));
+                }
+                _ => {}
+            }
+        })?,
+    };
+
+    if banners {
+        println!("Solution:");
+    }
+
+    output.write_all(&args.format.render(&grid, source, display, args))?;
+    output.flush()?;
+
+    if args.stats {
+        print_stats(&stats, start.elapsed());
+    }
+
+    Ok(())
+}
+
+fn print_stats(stats: &SolveStats, elapsed: Duration) {
+    let filled: Vec<_> = stats
+        .techniques()
+        .map(|technique| format!("{} {}", technique, stats.filled(technique)))
+        .collect();
+
+    eprintln!("Statistics:");
+    eprintln!("  propagation passes: {}", stats.propagations());
+    eprintln!("  cells filled: {}", filled.join(", "));
+    eprintln!("  guesses: {}", stats.guesses());
+    eprintln!("  backtracks: {}", stats.backtracks());
+    eprintln!("  max search depth: {}", stats.max_depth());
+
+    if stats.assumes_uniqueness() {
+        eprintln!("  note: some cells assume the puzzle has a single solution");
+    }
+
+    eprintln!("  time: {:?}", elapsed);
+}
+
+// Deduce as much as possible without guessing, to see where solvers get stuck
+fn solve_logic(
+    paths: &[PathBuf],
+    args: &SolveArgs,
+    config: &SolverConfig,
+    display: &DisplayOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config = config.clone().max_depth(0);
+    let (mut failures, mut code) = (0, 0);
+
+    for path in paths {
+        let result = read_grid(path).and_then(|mut grid| match grid.solve_with(&config) {
+            Ok(()) | Err(SolveError::LimitExceeded(Limit::Depth(0))) => Ok(grid),
+            Err(err) => Err(err.into()),
+        });
+
+        let grid = match result {
+            Ok(grid) => grid,
+            Err(err) => {
+                report_file(path.display(), &*err);
+                failures += 1;
+                code = code.max(exit_code(&*err));
+                continue;
+            }
+        };
+
+        if paths.len() > 1 && !args.quiet {
+            println!("{}:", path.display());
+        }
+
+        let size = grid.width() * grid.height();
+        let empty = (0..grid.height())
+            .flat_map(|i| (0..grid.width()).map(move |j| (i, j)))
+            .filter(|idx| grid[*idx].is_none())
+            .count();
+
+        println!("{}", grid.display_with(display.clone()));
+        println!(
+            "{} of {} cells filled ({:.1}%), {} remaining",
+            size - empty,
+            size,
+            (size - empty) as f64 * 100.0 / size as f64,
+            empty
+        );
+    }
+
+    check_failures(failures, paths.len(), code)
+}
+
+// Solve the grid again every time its file is saved
+#[cfg(feature = "watch")]
+fn watch(
+    paths: &[PathBuf],
+    args: &SolveArgs,
+    config: &SolverConfig,
+    display: &DisplayOptions,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use notify::Watcher;
+    use std::sync::mpsc;
+
+    let [path] = paths else {
+        return Err("error: --watch needs a single file".into());
+    };
+
+    if path == Path::new("-") {
+        return Err("error: --watch cannot read the standard input".into());
+    }
+
+    // Editors often save by replacing the file, so its directory is watched
+    let path = fs::canonicalize(path).map_err(|err| FileError::new(path, err))?;
+    let directory = path.parent().unwrap_or(Path::new("/"));
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    watcher.watch(directory, notify::RecursiveMode::NonRecursive)?;
+
+    loop {
+        if let Err(err) = solve_file(&path, args, config, display, &mut io::stdout()) {
+            report(&*err);
+        }
+
+        if !args.quiet {
+            eprintln!("Watching {} for changes...", path.display());
+        }
+
+        // Wait for the file to change, then for the burst of events to settle
+        loop {
+            let event = receiver.recv()??;
+
+            if !event.kind.is_access() && event.paths.contains(&path) {
+                break;
+            }
+        }
+
+        while receiver.recv_timeout(WATCH_DELAY).is_ok() {}
+    }
+}
+
+// One document per grid and per line, so batches can be streamed
+fn solve_json(
+    paths: &[PathBuf],
+    config: &SolverConfig,
+    mut output: Box<dyn Write>,
+    quiet: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut summary = Summary::default();
+
+    for path in paths {
+        let mut grid = match read_grid(path) {
+            Ok(grid) => grid,
+            Err(err) => {
+                report_file(path.display(), &*err);
+                summary.unreadable(&*err);
+                continue;
+            }
+        };
+
+        let input = cells(&grid);
+        let start = Instant::now();
+        let result = grid.solve_with_stats(config);
+        let elapsed = start.elapsed();
+
+        match &result {
+            Ok(stats) => {
+                let techniques: serde_json::Map<_, _> = stats
+                    .techniques()
+                    .map(|technique| (technique.to_string(), stats.filled(technique).into()))
+                    .collect();
+
+                let report = serde_json::json!({
+                    "file": path.display().to_string(),
+                    "width": grid.width(),
+                    "height": grid.height(),
+                    "input": input,
+                    "solution": cells(&grid),
+                    "time_ms": elapsed.as_secs_f64() * 1000.0,
+                    "techniques": techniques,
+                    "guesses": stats.guesses(),
+                    "backtracks": stats.backtracks(),
+                    "max_depth": stats.max_depth(),
+                    "propagations": stats.propagations(),
+                    "bruteforce": stats.guesses() > 0,
+                    "assumes_unique": stats.assumes_uniqueness(),
+                });
+
+                writeln!(output, "{}", report)?;
+            }
+            Err(err) => report_file(path.display(), err),
+        }
+
+        summary.add(&result);
+    }
+
+    output.flush()?;
+
+    if !quiet && !json_errors() && paths.len() > 1 {
+        eprintln!("{}", summary);
+    }
+
+    check_failures(paths.len() - summary.solved, paths.len(), summary.code)
+}
+
+fn cells(grid: &Grid) -> Vec<Vec<Option<u8>>> {
+    (0..grid.height())
+        .map(|i| {
+            (0..grid.width())
+                .map(|j| grid[(i, j)].map(|cell| (cell == Cell::One) as u8))
+                .collect()
+        })
+        .collect()
+}
+
+fn check(paths: &[PathBuf]) -> Result<(), Box<dyn std::error::Error>> {
+    let (mut failures, mut code) = (0, 0);
+
+    // Grids are checked as solve --require-unique does, with the same errors
+    let config = SolverConfig::new().require_unique(true);
+
+    for path in paths {
+        let status = read_grid(path).and_then(|mut grid| Ok(grid.solve_with(&config)?));
+
+        match status {
+            Ok(()) => println!("{}: ok", path.display()),
+            Err(err) => {
+                report_file(path.display(), &*err);
+                failures += 1;
+                code = code.max(exit_code(&*err));
+            }
+        }
+    }
+
+    check_failures(failures, paths.len(), code)
+}
+
+fn generate(args: GenerateArgs, seed: Option<u64>) -> Result<(), Box<dyn std::error::Error>> {
+    let (width, height) = args.size;
+    let mut config = GeneratorConfig::new().logic_only(args.logic_only);
+
+    if let Some(difficulty) = args.difficulty {
+        config = config.difficulty(difficulty);
+    }
+
+    if let Some(symmetry) = args.symmetry {
+        config = config.symmetry(symmetry);
+    }
+
+    if let Some(ratio) = args.clue_ratio {
+        config = config.target_clue_ratio(ratio);
     }
 
-    let file = fs::File::open(&args[1]).map_err(|err| format!("{}: {}", args[1], err))?;
-    let lines = io::BufReader::new(file)
-        .lines()
-        .collect::<Result<Vec<_>, _>>()?;
+    let mut generator = Generator::new(width, height)?.config(config);
 
-    let mut grid = grid::Grid::parse(lines.into_iter())?;
+    if let Some(seed) = seed {
+        generator = generator.seed(seed);
+    }
+
+    let puzzles = generator.generate_pack(args.count);
 
-    println!("Input grid:");
-    println!("{}", grid);
+    if let Some(path) = args.pack {
+        let mut pack = match args.append && path.exists() {
+            true => Pack::load(&path).map_err(|err| FileError::new(&path, err))?,
+            false => Pack::new(),
+        };
+
+        pack.append(puzzles);
+        pack.save(&path).map_err(|err| FileError::new(&path, err))?;
+        return Ok(());
+    }
 
-    grid.solve()?;
+    for entry in puzzles.puzzles() {
+        let metadata = entry
+            .puzzle()
+            .metadata()
+            .clone()
+            .with_title(format!("puzzle {}", entry.name()));
+        let text = format!("{}{}\n", metadata, entry.puzzle());
 
-    println!("Solution:");
-    println!("{}", grid);
+        // One file per puzzle, or everything on the standard output
+        match &args.output {
+            Some(dir) => {
+                let path = dir.join(format!("puzzle-{}.txt", entry.name()));
+                fs::write(&path, text).map_err(|err| FileError::new(&path, err))?;
+            }
+            None => println!("{}", text),
+        }
+    }
 
     Ok(())
 }
+
+fn lint(paths: &[PathBuf], density: (f32, f32)) -> Result<(), Box<dyn std::error::Error>> {
+    let (min, max) = density;
+
+    if min > max {
+        return Err(format!("error: invalid clue density ({}..{})", min, max).into());
+    }
+
+    let config = LintConfig::new().density(min, max);
+    let (mut failures, mut code) = (0, 0);
+
+    for path in paths {
+        let grid = match read_grid(path) {
+            Ok(grid) => grid,
+            Err(err) => {
+                println!("{}: fail", path.display());
+
+                // Grids breaking a rule are refused while parsing them
+                match err.downcast_ref::<ParseError>() {
+                    Some(ParseError::InvalidGrid(violation)) => {
+                        println!("  parse: pass");
+                        println!("  {}: fail ({})", Check::Rules, violation);
+                        Check::iter()
+                            .skip(1)
+                            .for_each(|check| println!("  {}: skip", check));
+                    }
+                    _ => println!("  parse: fail ({})", message(&*err)),
+                }
+
+                failures += 1;
+                code = code.max(exit_code(&*err));
+                continue;
+            }
+        };
+
+        let lint = grid.lint_with(&config);
+        let status = if lint.passed() { "pass" } else { "fail" };
+
+        println!("{}: {}", path.display(), status);
+        println!("  parse: pass");
+
+        for finding in lint.findings() {
+            println!("  {}", finding);
+        }
+
+        // Failures keep the exit code of the error they stand for
+        let failed = lint.failures().next().map(|finding| finding.check());
+
+        if let Some(check) = failed {
+            failures += 1;
+            code = code.max(match check {
+                Check::Rules => 3,
+                Check::Solvable => 4,
+                Check::Unique => 6,
+                Check::LogicOnly | Check::Density => 1,
+            });
+        }
+    }
+
+    check_failures(failures, paths.len(), code)
+}
+
+fn rate(paths: &[PathBuf]) -> Result<(), Box<dyn std::error::Error>> {
+    let (mut failures, mut code) = (0, 0);
+
+    for path in paths {
+        match read_grid(path).and_then(|grid| Ok((grid.rate_report()?, grid))) {
+            Ok((report, grid)) => {
+                let name = match grid.metadata().title() {
+                    Some(title) => format!("{} ({})", path.display(), title),
+                    None => path.display().to_string(),
+                };
+
+                // Complete grids do not need any technique
+                let techniques = match report.techniques() {
+                    [] => "none".to_string(),
+                    techniques => {
+                        let names: Vec<_> = techniques.iter().map(|t| t.to_string()).collect();
+                        names.join(", ")
+                    }
+                };
+
+                let solutions = match report.is_unique() {
+                    true => "unique solution",
+                    false => "multiple solutions",
+                };
+
+                let guessing = match report.requires_guessing() {
+                    true => "needs guessing",
+                    false => "logic only",
+                };
+
+                println!(
+                    "{}: {} (score {}, {} guesses, {}, {}, techniques: {})",
+                    name,
+                    report.difficulty(),
+                    report.score(),
+                    report.guesses(),
+                    guessing,
+                    solutions,
+                    techniques
+                );
+            }
+            Err(err) => {
+                report_file(path.display(), &*err);
+                failures += 1;
+                code = code.max(exit_code(&*err));
+            }
+        }
+    }
+
+    check_failures(failures, paths.len(), code)
+}
+
+fn hint(path: &Path, tier: Option<u8>) -> Result<(), Box<dyn std::error::Error>> {
+    let grid = read_grid(path).map_err(|err| FileError::new(path, err))?;
+
+    let Some(tier) = tier else {
+        match grid.hint()? {
+            Some((idx, cell)) => println!("line {}, column {}: {}", idx.0 + 1, idx.1 + 1, cell),
+            None => println!("the grid is already complete"),
+        }
+
+        return Ok(());
+    };
+
+    match grid.hint_at(tier.into())? {
+        Some(Hint::Lane(lane)) => println!("look at {}", lane_name(lane)),
+        Some(Hint::Cell(idx)) => println!("look at line {}, column {}", idx.0 + 1, idx.1 + 1),
+        Some(Hint::Value(_, _, reason)) => println!("{}", reason),
+        None => println!("the grid is already complete"),
+    }
+
+    Ok(())
+}
+
+fn conflicts(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let grid = read_grid(path).map_err(|err| FileError::new(path, err))?;
+    let clues = grid.conflicting_clues();
+
+    if clues.is_empty() {
+        println!("the grid has a solution");
+    }
+
+    for idx in clues {
+        if let Some(cell) = grid[idx] {
+            println!("line {}, column {}: {}", idx.0 + 1, idx.1 + 1, cell);
+        }
+    }
+
+    Ok(())
+}
+
+fn resume(path: &Path, config: &SolverConfig) -> Result<(), Box<dyn std::error::Error>> {
+    let bytes = fs::read(path).map_err(|err| FileError::new(path, err))?;
+    let snapshot = Snapshot::from_bytes(&bytes).map_err(|err| FileError::new(path, err))?;
+
+    match snapshot.resume(config)? {
+        (grid, Progress::Solved(_)) => {
+            println!("{}", grid);
+            Ok(())
+        }
+        // The file is kept up to date, so the search can go on again
+        (_, Progress::Paused(snapshot, limit)) => {
+            save_state(path, &snapshot)?;
+            Err(SolveError::LimitExceeded(limit).into())
+        }
+    }
+}
+
+fn save_state(path: &Path, snapshot: &Snapshot) -> Result<(), Box<dyn std::error::Error>> {
+    fs::write(path, snapshot.to_bytes()).map_err(|err| FileError::new(path, err))?;
+
+    if !json_errors() {
+        eprintln!(
+            "search saved after {} guesses, run binero resume {} to carry on",
+            snapshot.stats().guesses(),
+            path.display()
+        );
+    }
+
+    Ok(())
+}
+
+fn explain(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let grid = read_grid(path).map_err(|err| FileError::new(path, err))?;
+
+    for step in grid.explain()? {
+        match step {
+            Step::Deduction(deduction) => println!("{}", deduction.explain()),
+            Step::Guess(idx, cell) => println!("guess {} = {}", cell_name(idx), cell),
+            Step::Backtrack => println!("contradiction, undo the last guess"),
+        }
+    }
+
+    Ok(())
+}
+
+fn share(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let grid = read_grid(path).map_err(|err| FileError::new(path, err))?;
+    println!("{}", grid.to_compact_string());
+
+    Ok(())
+}
+
+fn animate(path: &Path, delay: Duration, cell_size: u16) -> Result<(), Box<dyn std::error::Error>> {
+    let grid = read_grid(path).map_err(|err| FileError::new(path, err))?;
+    let style = SvgStyle::new().cell_size(cell_size.into());
+
+    print!("{}", grid.to_animated_svg(&style, delay)?);
+    Ok(())
+}
+
+#[cfg(feature = "tui")]
+fn play(path: &Path, session: Option<&Path>) -> Result<(), Box<dyn std::error::Error>> {
+    let Some(session_path) = session else {
+        let grid = read_grid(path).map_err(|err| FileError::new(path, err))?;
+        binero::play(grid)?;
+
+        return Ok(());
+    };
+
+    // An existing session is carried on, the puzzle file is then not read
+    let session = if session_path.exists() {
+        let json =
+            fs::read_to_string(session_path).map_err(|err| FileError::new(session_path, err))?;
+        binero::Session::from_json(&json).map_err(|err| FileError::new(session_path, err))?
+    } else {
+        let grid = read_grid(path).map_err(|err| FileError::new(path, err))?;
+        binero::Session::new(grid)
+    };
+
+    binero::play_session(session, session_path)?;
+    Ok(())
+}
+
+fn cell_name(idx: Index) -> String {
+    format!("r{}c{}", idx.0 + 1, idx.1 + 1)
+}
+
+fn lane_name(lane: Lane) -> String {
+    match lane {
+        Lane::Line(i) => format!("line {}", i + 1),
+        Lane::Column(j) => format!("column {}", j + 1),
+    }
+}
+
+fn count(path: &Path, limit: usize) -> Result<(), Box<dyn std::error::Error>> {
+    let grid = read_grid(path).map_err(|err| FileError::new(path, err))?;
+    let count = grid.count_solutions(limit)?;
+
+    // Counting stops at the limit, there may be more
+    if count == limit {
+        println!("at least {} solutions", count);
+    } else {
+        println!("{} solutions", count);
+    }
+
+    Ok(())
+}
+
+fn export_sat(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let grid = read_grid(path).map_err(|err| FileError::new(path, err))?;
+    print!("{}", grid.to_dimacs());
+
+    Ok(())
+}
+
+fn export_minizinc(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let grid = read_grid(path).map_err(|err| FileError::new(path, err))?;
+    print!("{}", grid.to_minizinc());
+
+    Ok(())
+}
+
+fn export_tree(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let grid = read_grid(path).map_err(|err| FileError::new(path, err))?;
+    print!("{}", grid.to_search_dot()?);
+
+    Ok(())
+}
+
+fn bench(
+    dir: &Path,
+    repeat: u32,
+    csv: bool,
+    config: &SolverConfig,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let paths = puzzle_files(dir).map_err(|err| FileError::new(dir, err))?;
+    let mut timings = Vec::new();
+    let (mut failures, mut count, mut code) = (0, 0, 0);
+
+    for path in &paths {
+        let grids = read_grids(path)
+            .unwrap_or_else(|err| vec![(path.display().to_string(), Err(err.into()))]);
+
+        for (name, grid) in grids {
+            count += 1;
+
+            let timing = grid.and_then(|grid| {
+                let (elapsed, nodes) = time_solve(&grid, repeat, config)?;
+                Ok(Timing {
+                    name: name.clone(),
+                    size: (grid.width(), grid.height()),
+                    elapsed,
+                    nodes,
+                })
+            });
+
+            match timing {
+                Ok(timing) => timings.push(timing),
+                Err(err) => {
+                    report_file(&name, &*err);
+                    failures += 1;
+                    code = code.max(exit_code(&*err));
+                }
+            }
+        }
+    }
+
+    if count == 0 {
+        return Err(Box::new(FileError::new(dir, "no puzzles found")));
+    }
+
+    if csv {
+        println!("puzzle,width,height,time_us,nodes");
+
+        for timing in &timings {
+            println!(
+                "{},{},{},{},{}",
+                csv_field(&timing.name),
+                timing.size.0,
+                timing.size.1,
+                timing.elapsed.as_micros(),
+                timing.nodes
+            );
+        }
+    } else {
+        let width = timings.iter().map(|t| t.name.len()).max().unwrap_or(0);
+
+        println!(
+            "{:width$}  {:>7}  {:>10}  {:>8}",
+            "puzzle", "size", "time", "nodes"
+        );
+
+        for timing in &timings {
+            let size = format!("{}x{}", timing.size.0, timing.size.1);
+            let elapsed = format!("{:.1?}", timing.elapsed);
+
+            println!(
+                "{:width$}  {:>7}  {:>10}  {:>8}",
+                timing.name, size, elapsed, timing.nodes
+            );
+        }
+    }
+
+    // Kept apart from the CSV lines, which are meant to be read by programs
+    if let Some(summary) = bench_summary(&timings) {
+        match csv {
+            true => eprintln!("{}", summary),
+            false => println!("\n{}", summary),
+        }
+    }
+
+    check_failures(failures, count, code)
+}
+
+struct Timing {
+    name: String,
+    size: (usize, usize),
+    elapsed: Duration,
+    nodes: usize,
+}
+
+// The fastest of several runs, with the number of guesses it took
+fn time_solve(
+    grid: &Grid,
+    repeat: u32,
+    config: &SolverConfig,
+) -> Result<(Duration, usize), SolveError> {
+    let mut fastest = (Duration::MAX, 0);
+
+    for _ in 0..repeat {
+        let mut solution = grid.clone();
+        let start = Instant::now();
+        let stats = solution.solve_with_stats(config)?;
+
+        fastest = fastest.min((start.elapsed(), stats.guesses()));
+    }
+
+    Ok(fastest)
+}
+
+fn bench_summary(timings: &[Timing]) -> Option<String> {
+    let worst = timings.iter().max_by_key(|timing| timing.elapsed)?;
+
+    let mut times: Vec<_> = timings.iter().map(|timing| timing.elapsed).collect();
+    times.sort();
+
+    let mean = times.iter().sum::<Duration>() / times.len() as u32;
+    let median = match times.len() % 2 {
+        0 => (times[times.len() / 2 - 1] + times[times.len() / 2]) / 2,
+        _ => times[times.len() / 2],
+    };
+
+    Some(format!(
+        "{} puzzles: mean {:.1?}, median {:.1?}, worst {:.1?} ({})",
+        times.len(),
+        mean,
+        median,
+        worst.elapsed,
+        worst.name
+    ))
+}
+
+// Every file under a directory, in a stable order
+fn puzzle_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    if !fs::metadata(dir)?.is_dir() {
+        return Ok(vec![dir.to_path_buf()]);
+    }
+
+    let mut entries: Vec<_> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<_>>()?;
+    entries.sort();
+
+    let mut paths = Vec::new();
+
+    for entry in entries {
+        paths.extend(puzzle_files(&entry)?);
+    }
+
+    Ok(paths)
+}
+
+// Names holding a comma or a quote are quoted
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn diff(first: &Path, second: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let old = read_grid(first).map_err(|err| FileError::new(first, err))?;
+    let new = read_grid(second).map_err(|err| FileError::new(second, err))?;
+
+    let differences = old.diff(&new).map_err(|_| {
+        format!(
+            "error: grids have different dimensions ({}x{} and {}x{})",
+            old.width(),
+            old.height(),
+            new.width(),
+            new.height()
+        )
+    })?;
+
+    // Shared cells are kept, differences are marked in their place
+    for i in 0..old.height() {
+        let line: Vec<_> = (0..old.width())
+            .map(|j| {
+                let difference = differences.iter().find(|diff| diff.index() == Index(i, j));
+
+                match (difference, old[(i, j)]) {
+                    (Some(Difference::Mismatch(..)), _) => "!".to_string(),
+                    (Some(Difference::Removed(..)), _) => "<".to_string(),
+                    (Some(Difference::Added(..)), _) => ">".to_string(),
+                    (None, Some(cell)) => cell.to_string(),
+                    (None, None) => "-".to_string(),
+                }
+            })
+            .collect();
+
+        println!("{}", line.join(" "));
+    }
+
+    println!();
+
+    for difference in &differences {
+        let (idx, old, new) = match *difference {
+            Difference::Mismatch(idx, old, new) => (idx, old.to_string(), new.to_string()),
+            Difference::Removed(idx, old) => (idx, old.to_string(), "empty".to_string()),
+            Difference::Added(idx, new) => (idx, "empty".to_string(), new.to_string()),
+        };
+
+        println!(
+            "line {}, column {}: {} in {}, {} in {}",
+            idx.0 + 1,
+            idx.1 + 1,
+            old,
+            first.display(),
+            new,
+            second.display()
+        );
+    }
+
+    let mismatches = differences
+        .iter()
+        .filter(|diff| matches!(diff, Difference::Mismatch(..)))
+        .count();
+
+    println!(
+        "{} cells differ: {} with different values, {} filled in only one grid",
+        differences.len(),
+        mismatches,
+        differences.len() - mismatches
+    );
+
+    Ok(())
+}
+
+#[derive(Default)]
+struct Summary {
+    solved: usize,
+    invalid: usize,
+    unsolvable: usize,
+    failed: usize,
+    ambiguous: usize,
+    code: u8,
+}
+
+impl Summary {
+    fn unreadable(&mut self, err: &(dyn std::error::Error + 'static)) {
+        self.invalid += 1;
+        self.code = self.code.max(exit_code(err));
+    }
+
+    fn add<T>(&mut self, result: &Result<T, SolveError>) {
+        if let Err(err) = result {
+            self.code = self.code.max(exit_code(err));
+        }
+
+        match result {
+            Ok(_) => self.solved += 1,
+            Err(SolveError::InvalidGrid(_)) => self.invalid += 1,
+            Err(SolveError::NoSolution(_)) => self.unsolvable += 1,
+            Err(SolveError::LimitExceeded(_)) => self.failed += 1,
+            Err(SolveError::MultipleSolutions(_)) => self.ambiguous += 1,
+        }
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "{} solved, {} invalid, {} without solution, {} failed",
+            self.solved, self.invalid, self.unsolvable, self.failed
+        )?;
+
+        // Only grids required to have a single solution can be ambiguous
+        if self.ambiguous > 0 {
+            write!(fmt, ", {} with several solutions", self.ambiguous)?;
+        }
+
+        Ok(())
+    }
+}
+
+// Patterns are expanded here too, for shells that leave them untouched
+fn expand(paths: Vec<PathBuf>) -> Vec<PathBuf> {
+    paths
+        .into_iter()
+        .flat_map(|path| {
+            let matches: Vec<_> = path
+                .to_str()
+                .filter(|pattern| !path.exists() && pattern.contains(['*', '?', '[']))
+                .and_then(|pattern| glob::glob(pattern).ok())
+                .map(|paths| paths.filter_map(Result::ok).collect())
+                .unwrap_or_default();
+
+            // Unmatched patterns are kept, so they get reported as missing
+            if matches.is_empty() {
+                vec![path]
+            } else {
+                matches
+            }
+        })
+        .collect()
+}
+
+fn check_failures(
+    failures: usize,
+    total: usize,
+    code: u8,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if failures > 0 {
+        return Err(Box::new(BatchError {
+            failures,
+            total,
+            code,
+        }));
+    }
+
+    Ok(())
+}
+
+// Exit codes for scripts: 1 for other errors, 2 for unreadable grids, 3 for
+// grids breaking a rule, 4 without solution, 5 when a limit is exceeded, 6
+// with several solutions when only one is allowed
+fn exit_code(err: &(dyn std::error::Error + 'static)) -> u8 {
+    if let Some(err) = err.downcast_ref::<FileError>() {
+        return exit_code(&*err.source);
+    }
+
+    if let Some(err) = err.downcast_ref::<BatchError>() {
+        return err.code;
+    }
+
+    match (err.downcast_ref(), err.downcast_ref()) {
+        (Some(ParseError::InvalidGrid(_)), _) => 3,
+        (Some(_), _) => 2,
+        (_, Some(SolveError::InvalidGrid(_))) => 3,
+        (_, Some(SolveError::NoSolution(_))) => 4,
+        (_, Some(SolveError::LimitExceeded(_))) => 5,
+        (_, Some(SolveError::MultipleSolutions(_))) => 6,
+        (None, None) => 1,
+    }
+}
+
+// Only errors go to the standard error when they are read by another program
+fn json_errors() -> bool {
+    ERROR_FORMAT.get() == Some(&ErrorFormat::Json)
+}
+
+fn report(err: &(dyn std::error::Error + 'static)) {
+    if json_errors() {
+        eprintln!("{}", error_json(err));
+    } else {
+        eprintln!("{}", err);
+    }
+}
+
+fn report_file(name: impl fmt::Display, err: &(dyn std::error::Error + 'static)) {
+    if json_errors() {
+        let mut value = error_json(err);
+        value["file"] = name.to_string().into();
+        eprintln!("{}", value);
+    } else {
+        eprintln!("{}: {}", name, err);
+    }
+}
+
+// One JSON document per error, with its position when it is known
+fn error_json(err: &(dyn std::error::Error + 'static)) -> serde_json::Value {
+    if let Some(err) = err.downcast_ref::<FileError>() {
+        let mut value = error_json(&*err.source);
+        value["file"] = err.path.display().to_string().into();
+        return value;
+    }
+
+    let (kind, index, lane) = error_kind(err);
+
+    // Parse errors tell where they are in the text rather than in the grid,
+    // some of them without a column
+    let (line, column) = match err.downcast_ref::<ParseError>() {
+        Some(ParseError::InvalidChar {
+            position: Some((line, column)),
+            ..
+        }) => (Some(*line), Some(*column)),
+        Some(ParseError::WidthMismatch { line, .. }) => (Some(*line), None),
+        _ => (index.map(|idx| idx.0 + 1), index.map(|idx| idx.1 + 1)),
+    };
+
+    serde_json::json!({
+        "kind": kind,
+        "message": message(err),
+        "file": null,
+        "line": line,
+        "column": column,
+        "lane": lane.map(lane_name),
+    })
+}
+
+// Message of an error, without the prefix telling it is one
+fn message(err: &dyn std::error::Error) -> String {
+    let message = err.to_string();
+    message
+        .strip_prefix("error: ")
+        .map_or(message.clone(), String::from)
+}
+
+fn error_kind(
+    err: &(dyn std::error::Error + 'static),
+) -> (&'static str, Option<Index>, Option<Lane>) {
+    if let Some(err) = err.downcast_ref::<ParseError>() {
+        let kind = match err {
+            ParseError::EmptyGrid => "empty_grid",
+            ParseError::InvalidChar { .. } => "invalid_char",
+            ParseError::InvalidEncoding => "invalid_encoding",
+            ParseError::InvalidGrid(violation) => return violation_kind(violation),
+            ParseError::OddDimension { .. } => "odd_dimension",
+            ParseError::RatioMismatch { .. } => "ratio_mismatch",
+            ParseError::SizeMismatch { .. } => "size_mismatch",
+            ParseError::UnbalancedLane { .. } => "unbalanced_lane",
+            ParseError::UnbalancedRegion(_) => "unbalanced_region",
+            ParseError::UnsolvableDimensions { .. } => "unsolvable_dimensions",
+            ParseError::Unsupported(_) => "unsupported",
+            ParseError::TooLarge { .. } => "too_large",
+            ParseError::WidthMismatch { .. } => "width_mismatch",
+        };
+
+        return (kind, None, None);
+    }
+
+    if let Some(err) = err.downcast_ref::<SolveError>() {
+        return match err {
+            SolveError::InvalidGrid(violation) => violation_kind(violation),
+            SolveError::LimitExceeded(_) => ("limit_exceeded", None, None),
+            SolveError::NoSolution(Some(dead_end)) => {
+                ("no_solution", Some(dead_end.index()), dead_end.lane())
+            }
+            SolveError::NoSolution(None) => ("no_solution", None, None),
+            SolveError::MultipleSolutions(_) => ("multiple_solutions", err.ambiguous_cell(), None),
+        };
+    }
+
+    if let Some(err) = err.downcast_ref::<VerificationError>() {
+        return match err {
+            VerificationError::ClueMismatch(idx) => ("clue_mismatch", Some(*idx), None),
+            VerificationError::DimensionMismatch => ("dimension_mismatch", None, None),
+            VerificationError::Incomplete(idx) => ("incomplete", Some(*idx), None),
+            VerificationError::InvalidGrid(violation) => violation_kind(violation),
+        };
+    }
+
+    if err.is::<BatchError>() {
+        return ("batch", None, None);
+    }
+
+    if err.is::<io::Error>() {
+        return ("io", None, None);
+    }
+
+    ("other", None, None)
+}
+
+// First cell and lane breaking a rule
+fn violation_kind(violation: &Violation) -> (&'static str, Option<Index>, Option<Lane>) {
+    let idx = violation.cells().first().copied();
+    ("invalid_grid", idx, violation.lanes().first().copied())
+}
+
+// Solver events, timed from the start of the command
+struct Logger {
+    start: Instant,
+}
+
+impl Logger {
+    fn new() -> Self {
+        Self {
+            start: Instant::now(),
+        }
+    }
+}
+
+impl log::Log for Logger {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &log::Record) {
+        if self.enabled(record.metadata()) {
+            let elapsed = self.start.elapsed().as_secs_f64();
+            eprintln!("[{:10.6}s {:5}] {}", elapsed, record.level(), record.args());
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+// An error about a given file
+#[derive(Debug)]
+struct FileError {
+    path: PathBuf,
+    source: Box<dyn std::error::Error>,
+}
+
+impl FileError {
+    fn new<E>(path: &Path, err: E) -> Self
+    where
+        E: Into<Box<dyn std::error::Error>>,
+    {
+        Self {
+            path: path.to_path_buf(),
+            source: err.into(),
+        }
+    }
+}
+
+impl fmt::Display for FileError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}: {}", self.path.display(), self.source)
+    }
+}
+
+impl std::error::Error for FileError {}
+
+// Several grids failed, the exit code is the highest of their codes
+#[derive(Debug)]
+struct BatchError {
+    failures: usize,
+    total: usize,
+    code: u8,
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            fmt,
+            "error: {} of {} grids failed",
+            self.failures, self.total
+        )
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+fn parse_size(value: &str) -> Result<(usize, usize), String> {
+    let invalid = || format!("invalid size '{}'", value);
+
+    // A single number stands for a square grid
+    let (width, height) = value.split_once('x').unwrap_or((value, value));
+
+    Ok((
+        width.parse().map_err(|_| invalid())?,
+        height.parse().map_err(|_| invalid())?,
+    ))
+}
+
+fn parse_ratio(value: &str) -> Result<f32, String> {
+    value
+        .parse()
+        .ok()
+        .filter(|ratio| (0.0..=1.0).contains(ratio))
+        .ok_or_else(|| format!("'{}' is not between 0 and 1", value))
+}
+
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let split = value
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount: f64 = amount
+        .parse()
+        .map_err(|_| format!("invalid duration '{}'", value))?;
+
+    let seconds = match unit {
+        "ms" => amount / 1000.0,
+        "" | "s" => amount,
+        "m" => amount * 60.0,
+        _ => return Err(format!("unknown time unit '{}', use ms, s or m", unit)),
+    };
+
+    Ok(Duration::from_secs_f64(seconds))
+}
+
+fn parse_technique(value: &str) -> Result<Technique, String> {
+    Technique::iter()
+        .find(|technique| technique.to_string() == value)
+        .ok_or_else(|| {
+            let names: Vec<_> = Technique::iter().map(|t| t.to_string()).collect();
+            format!("unknown technique '{}', use {}", value, names.join(", "))
+        })
+}
+
+fn parse_symbols(value: &str) -> Result<(char, char), String> {
+    let mut chars = value.chars();
+
+    match (chars.next(), chars.next(), chars.next()) {
+        (Some(zero), Some(one), None) if zero != one => Ok((zero, one)),
+        _ => Err(format!(
+            "expected two different characters, found '{}'",
+            value
+        )),
+    }
+}
+
+fn parse_ones_ratio(value: &str) -> Result<(u32, u32), String> {
+    value
+        .split_once('/')
+        .and_then(|(ones, cells)| Some((ones.parse().ok()?, cells.parse().ok()?)))
+        .filter(|(ones, cells)| 0 < *ones && ones < cells)
+        .ok_or_else(|| format!("expected a ratio such as 1/3, found '{}'", value))
+}
+
+fn parse_difficulty(value: &str) -> Result<Difficulty, String> {
+    Difficulty::iter()
+        .find(|difficulty| difficulty.to_string() == value)
+        .ok_or_else(|| format!("unknown difficulty '{}'", value))
+}
+
+fn parse_level(value: &str) -> Result<Level, String> {
+    Level::iter()
+        .find(|level| level.to_string() == value)
+        .ok_or_else(|| format!("unknown level '{}'", value))
+}
+
+fn parse_symmetry(value: &str) -> Result<Symmetry, String> {
+    match value {
+        "none" => Ok(Symmetry::None),
+        "rotational" => Ok(Symmetry::Rotational),
+        "horizontal" => Ok(Symmetry::Horizontal),
+        "vertical" => Ok(Symmetry::Vertical),
+        _ => Err(format!("unknown symmetry '{}'", value)),
+    }
+}
+
+fn read_grid(path: &Path) -> Result<Grid, Box<dyn std::error::Error>> {
+    read_source(path, false).map(|(_, grid)| grid)
+}
+
+// The grid with the lines of the file it was read from, which must be a text
+// grid for its layout to be kept
+fn read_source(
+    path: &Path,
+    layout: bool,
+) -> Result<(Vec<String>, Grid), Box<dyn std::error::Error>> {
+    let lines = read_lines(path)?;
+    let input = INPUT.get().expect("input options are set on startup");
+    let format = input.format(path, &lines);
+
+    if layout && format != InputFormat::Text {
+        return Err("error: the layout can only be kept from text grids".into());
+    }
+
+    if input.index.is_none() && input.name.is_none() {
+        let grid = parse_grid(lines.clone(), format, input)?;
+        return Ok((lines, grid));
+    }
+
+    let grids = parse_grids(lines.clone(), format, input);
+    let count = grids.len();
+
+    let found = match (input.index, &input.name) {
+        (Some(index), _) => grids.into_iter().nth(index as usize - 1),
+        (_, Some(name)) => grids.into_iter().find(|(n, _)| n.as_ref() == Some(name)),
+        _ => None,
+    };
+
+    match found {
+        Some((_, grid)) => Ok((lines, grid?)),
+        None => match &input.name {
+            Some(name) => Err(format!("error: no grid named '{}'", name).into()),
+            None => Err(format!("error: the file only holds {} grid(s)", count).into()),
+        },
+    }
+}
+
+// Every grid of a file, named after the file and their header or number
+fn read_grids(path: &Path) -> io::Result<Vec<(String, GridResult)>> {
+    let input = INPUT.get().expect("input options are set on startup");
+    let lines = read_lines(path)?;
+    let grids = parse_grids(lines.clone(), input.format(path, &lines), input);
+
+    if let [(None, _)] = grids.as_slice() {
+        return Ok(grids
+            .into_iter()
+            .map(|(_, grid)| (path.display().to_string(), grid))
+            .collect());
+    }
+
+    Ok(grids
+        .into_iter()
+        .enumerate()
+        .map(|(k, (name, grid))| {
+            let name = name.unwrap_or_else(|| (k + 1).to_string());
+            (format!("{} [{}]", path.display(), name), grid)
+        })
+        .collect())
+}
+
+// Packs are JSON objects holding a list of puzzles
+fn is_pack(lines: &[String]) -> bool {
+    lines.iter().any(|line| line.contains("\"puzzles\""))
+}
+
+// Share codes start with the dimensions, then a dot
+fn is_code(line: &str) -> bool {
+    line.split_once('.')
+        .and_then(|(header, _)| header.split_once('x'))
+        .is_some_and(|(width, height)| {
+            [width, height]
+                .iter()
+                .all(|n| !n.is_empty() && n.bytes().all(|c| c.is_ascii_digit()))
+        })
+}
+
+fn read_lines(path: &Path) -> io::Result<Vec<String>> {
+    let lines = match path
+        .to_str()
+        .filter(|path| path.starts_with("http://") || path.starts_with("https://"))
+    {
+        Some(url) => fetch(url)?,
+        None => {
+            // A dash stands for the standard input
+            let reader: Box<dyn BufRead> = if path == Path::new("-") {
+                Box::new(io::stdin().lock())
+            } else {
+                Box::new(io::BufReader::new(fs::File::open(path)?))
+            };
+
+            reader.lines().collect::<io::Result<_>>()?
+        }
+    };
+
+    // Byte order marks and lone CRs are handled as the parser does
+    Ok(binero::split_lines(lines.into_iter()).collect())
+}
+
+#[cfg(feature = "net")]
+fn fetch(url: &str) -> io::Result<Vec<String>> {
+    // Error statuses are reported as errors too
+    let body = ureq::get(url)
+        .call()
+        .and_then(|mut response| response.body_mut().read_to_string())
+        .map_err(io::Error::other)?;
+
+    Ok(body.lines().map(String::from).collect())
+}
+
+#[cfg(not(feature = "net"))]
+fn fetch(_url: &str) -> io::Result<Vec<String>> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "reading URLs needs the net feature",
+    ))
+}
+
+fn parse_grid(lines: Vec<String>, format: InputFormat, input: &InputArgs) -> GridResult {
+    let grid = match format {
+        InputFormat::Text => {
+            return input.apply_regions(Grid::parse_with(lines.into_iter(), &input.options())?)
+        }
+        InputFormat::Json => Grid::from_json(&lines.join("\n"))?,
+        InputFormat::Csv => Grid::parse_csv(lines.into_iter())?,
+        InputFormat::Line => Grid::from_line_format(&lines.concat())?,
+        InputFormat::Binarypuzzle => Grid::from_binarypuzzle(&lines.concat())?,
+        InputFormat::Janko => Grid::from_janko(&lines.join("\n"))?,
+        InputFormat::Code => Grid::from_compact_string(&lines.concat())?,
+        InputFormat::Pack => {
+            let pack = Pack::from_json(&lines.join("\n"))?;
+
+            match pack.puzzles() {
+                [entry] => entry.puzzle().clone(),
+                puzzles => {
+                    return Err(format!(
+                        "error: the pack holds {} puzzles, pick one with --name or --index",
+                        puzzles.len()
+                    )
+                    .into())
+                }
+            }
+        }
+        InputFormat::Auto => unreachable!("the format is detected before parsing"),
+    };
+
+    input
+        .apply_rules(grid)
+        .and_then(|grid| input.apply_regions(grid))
+}
+
+type GridResult = Result<Grid, Box<dyn std::error::Error>>;
+
+// Only text files and packs hold several grids, each with its name if any
+fn parse_grids(
+    lines: Vec<String>,
+    format: InputFormat,
+    input: &InputArgs,
+) -> Vec<(Option<String>, GridResult)> {
+    match format {
+        InputFormat::Text => Grid::parse_collection(lines.into_iter(), &input.options())
+            .into_iter()
+            .map(|(name, grid)| {
+                let grid = grid.map_err(Into::into);
+                (name, grid.and_then(|grid| input.apply_regions(grid)))
+            })
+            .collect(),
+        InputFormat::Pack => match Pack::from_json(&lines.join("\n")) {
+            Ok(pack) => pack
+                .iter()
+                .map(|entry| {
+                    let grid = input
+                        .apply_rules(entry.puzzle().clone())
+                        .and_then(|grid| input.apply_regions(grid));
+                    (Some(entry.name().to_string()), grid)
+                })
+                .collect(),
+            Err(err) => vec![(None, Err(err.into()))],
+        },
+        _ => vec![(None, parse_grid(lines, format, input))],
+    }
+}
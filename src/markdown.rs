@@ -0,0 +1,45 @@
+use std::fmt::Write;
+
+use crate::grid::Grid;
+
+impl Grid {
+    // Tables need a header, which numbers the columns
+    pub fn to_markdown(&self) -> String {
+        let header: Vec<String> = self.columns().map(|j| (j + 1).to_string()).collect();
+        let mut markdown = format!("| {} |\n", header.join(" | "));
+
+        // Writing to a string cannot fail
+        let _ = writeln!(markdown, "|{}", ":-:|".repeat(self.width()));
+
+        for i in self.lines() {
+            let line: Vec<String> = self
+                .line(i)
+                .map(|cell| cell.map_or(String::from(" "), |cell| cell.to_string()))
+                .collect();
+
+            let _ = writeln!(markdown, "| {} |", line.join(" | "));
+        }
+
+        markdown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export() {
+        let grid = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+
+        assert_eq!(
+            grid.unwrap().to_markdown(),
+            "| 1 | 2 | 3 | 4 |\n\
+             |:-:|:-:|:-:|:-:|\n\
+             | 1 | 1 |   | 0 |\n\
+             |   | 0 |   |   |\n\
+             |   |   | 0 |   |\n\
+             |   | 1 |   | 0 |\n"
+        );
+    }
+}
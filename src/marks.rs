@@ -0,0 +1,115 @@
+use alloc::vec::Vec;
+
+use crate::cell::{Cell, CellSet};
+use crate::error::SolveError;
+use crate::grid::Grid;
+use crate::index::Index;
+
+impl Grid {
+    // Values noted as still possible in a cell, if any were noted
+    pub fn marks<I>(&self, idx: I) -> Option<CellSet>
+    where
+        I: Into<Index>,
+    {
+        self.marks.get(&idx.into()).copied()
+    }
+
+    pub fn set_marks<I>(&mut self, idx: I, marks: Option<CellSet>)
+    where
+        I: Into<Index>,
+    {
+        let idx = idx.into();
+        assert!(
+            idx.0 < self.height() && idx.1 < self.width(),
+            "marks go on a cell"
+        );
+
+        let old = match marks {
+            Some(marks) => self.marks.insert(idx, marks),
+            None => self.marks.remove(&idx),
+        };
+
+        self.record_marks(idx, old, marks);
+    }
+
+    // Note a value as possible in a cell, or rule it out when it already was
+    pub fn toggle_mark<I>(&mut self, idx: I, cell: Cell)
+    where
+        I: Into<Index>,
+    {
+        let idx = idx.into();
+        let mut marks = self.marks(idx).unwrap_or_default();
+
+        if marks.contains(cell) {
+            marks.remove(cell);
+        } else {
+            marks.insert(cell);
+        }
+
+        self.set_marks(idx, Some(marks));
+    }
+
+    pub fn marked_cells(&self) -> impl Iterator<Item = (Index, CellSet)> + '_ {
+        self.marks.iter().map(|(idx, marks)| (*idx, *marks))
+    }
+
+    // Empty cells whose marks rule out a value taken by one of the solutions
+    pub fn check_marks(&self) -> Result<Vec<Index>, SolveError> {
+        self.is_valid().map_err(SolveError::InvalidGrid)?;
+
+        if self.count_solutions(1)? == 0 {
+            return Err(SolveError::NoSolution(None));
+        }
+
+        let mut wrong = Vec::new();
+
+        for (idx, marks) in self.marked_cells().filter(|(idx, _)| self[*idx].is_none()) {
+            for cell in Cell::iter().filter(|cell| !marks.contains(*cell)) {
+                let mut grid = self.clone();
+
+                if grid.is_move_valid(idx, cell) {
+                    grid.set(idx, Some(cell));
+
+                    if grid.count_solutions(1)? > 0 {
+                        wrong.push(idx);
+                        break;
+                    }
+                }
+            }
+        }
+
+        Ok(wrong)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::DisplayOptions;
+
+    #[test]
+    fn marks() {
+        let input = vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"];
+        let mut grid = Grid::parse(input.into_iter()).unwrap();
+
+        // The third cell of the first line can only be a zero
+        grid.toggle_mark((0, 2), Cell::Zero);
+        assert_eq!(grid.marks((0, 2)), Some([Cell::Zero].into_iter().collect()));
+        assert!(grid.check_marks().unwrap().is_empty());
+
+        let options = DisplayOptions::new().marks(true);
+        assert!(grid
+            .display_with(options)
+            .to_string()
+            .starts_with("1 1 ₀ 0\n"));
+
+        grid.set_marks((1, 0), Some([Cell::One].into_iter().collect()));
+        grid.set_marks((1, 2), Some(CellSet::all()));
+        assert_eq!(grid.check_marks().unwrap(), [Index(1, 0)]);
+
+        grid.toggle_mark((1, 0), Cell::Zero);
+        grid.set_marks((0, 2), None);
+        assert!(grid.check_marks().unwrap().is_empty());
+        assert_eq!(grid.marked_cells().count(), 2);
+    }
+}
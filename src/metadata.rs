@@ -0,0 +1,173 @@
+use alloc::string::String;
+use alloc::string::ToString;
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::rating::Difficulty;
+
+// Where a puzzle comes from, none of it is needed to solve it
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    difficulty: Option<Difficulty>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    seed: Option<u64>,
+}
+
+impl Metadata {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_title<S: Into<String>>(mut self, title: S) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    pub fn with_author<S: Into<String>>(mut self, author: S) -> Self {
+        self.author = Some(author.into());
+        self
+    }
+
+    pub fn with_date<S: Into<String>>(mut self, date: S) -> Self {
+        self.date = Some(date.into());
+        self
+    }
+
+    pub fn with_difficulty(mut self, difficulty: Difficulty) -> Self {
+        self.difficulty = Some(difficulty);
+        self
+    }
+
+    pub fn with_source<S: Into<String>>(mut self, source: S) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    pub fn author(&self) -> Option<&str> {
+        self.author.as_deref()
+    }
+
+    pub fn date(&self) -> Option<&str> {
+        self.date.as_deref()
+    }
+
+    pub fn difficulty(&self) -> Option<Difficulty> {
+        self.difficulty
+    }
+
+    pub fn source(&self) -> Option<&str> {
+        self.source.as_deref()
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    // A `key: value` header line, unknown keys and values are left out
+    pub(crate) fn set(&mut self, line: &str) {
+        let Some((key, value)) = line.split_once(':') else {
+            return;
+        };
+
+        let value = value.trim().to_string();
+
+        match key.trim() {
+            "title" => self.title = Some(value),
+            "author" => self.author = Some(value),
+            "date" => self.date = Some(value),
+            "difficulty" => {
+                if let Some(difficulty) = Difficulty::iter().find(|d| d.to_string() == value) {
+                    self.difficulty = Some(difficulty);
+                }
+            }
+            "source" => self.source = Some(value),
+            "seed" => {
+                if let Ok(seed) = value.parse() {
+                    self.seed = Some(seed);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// Header lines, as read back by the text parser
+impl fmt::Display for Metadata {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        let fields = [
+            ("title", self.title.clone()),
+            ("author", self.author.clone()),
+            ("date", self.date.clone()),
+            ("difficulty", self.difficulty.map(|d| d.to_string())),
+            ("source", self.source.clone()),
+            ("seed", self.seed.map(|seed| seed.to_string())),
+        ];
+
+        for (key, value) in fields {
+            if let Some(value) = value {
+                writeln!(fmt, "#! {}: {}", key, value)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+
+    #[test]
+    fn headers() {
+        let lines = vec![
+            "#! title: First steps",
+            "#! difficulty: easy",
+            "#! seed: 42",
+            "#! colour: blue",
+            "# a plain comment",
+            "1 1 - 0",
+            "- 0 - -",
+            "- - 0 -",
+            "- 1 - 0",
+        ];
+        let grid = Grid::parse(lines.into_iter()).unwrap();
+        let metadata = Metadata::new()
+            .with_title("First steps")
+            .with_difficulty(Difficulty::Easy)
+            .with_seed(42);
+
+        assert_eq!(grid.metadata(), &metadata);
+        assert_eq!(
+            metadata.to_string(),
+            "#! title: First steps\n#! difficulty: easy\n#! seed: 42\n"
+        );
+
+        let json = grid.to_json();
+        assert!(json.contains(r#""title":"First steps""#));
+        assert_eq!(Grid::from_json(&json).unwrap().metadata(), &metadata);
+    }
+}
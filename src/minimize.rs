@@ -0,0 +1,105 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::grid::Grid;
+use crate::index::Index;
+use crate::symmetry::Symmetry;
+
+impl Grid {
+    pub fn minimize(&self) -> Grid {
+        self.minimize_with(Symmetry::None)
+    }
+
+    pub fn minimize_with(&self, symmetry: Symmetry) -> Grid {
+        let mut puzzle = self.clone();
+
+        // Nothing to preserve if the solution is not unique already
+        if !puzzle.has_unique_solution() {
+            return puzzle;
+        }
+
+        let givens: Vec<_> = self.givens().map(|(idx, _)| idx).collect();
+
+        for idx in givens {
+            if puzzle[idx].is_none() {
+                continue;
+            }
+
+            // Symmetric clues are removed together
+            let orbit = symmetry.orbit(idx, self.width(), self.height());
+            let orbit = puzzle.take_clues(orbit);
+
+            if !puzzle.has_unique_solution() {
+                for (idx, cell) in orbit {
+                    puzzle.set_clue(idx, cell);
+                }
+            }
+        }
+
+        puzzle
+    }
+
+    // Clues that already leave the grid without a solution, none of which
+    // can be left out, or nothing when the grid can be solved
+    pub fn conflicting_clues(&self) -> Vec<Index> {
+        let unsolvable = |grid: &Grid| !grid.count_solutions(1).is_ok_and(|count| count > 0);
+        let mut puzzle = self.clone();
+
+        if !unsolvable(&puzzle) {
+            return Vec::new();
+        }
+
+        let givens: Vec<_> = self.givens().map(|(idx, _)| idx).collect();
+
+        for idx in givens {
+            let clue = puzzle.take_clues(vec![idx]);
+
+            if !unsolvable(&puzzle) {
+                for (idx, cell) in clue {
+                    puzzle.set_clue(idx, cell);
+                }
+            }
+        }
+
+        puzzle.givens().map(|(idx, _)| idx).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn minimization() {
+        let input = vec!["1 1 0 0", "0 0 1 1", "1 0 0 1", "0 1 1 0"];
+        let grid = Grid::parse(input.into_iter()).unwrap();
+
+        let minimal = grid.minimize();
+        assert!(minimal.has_unique_solution());
+        assert!(minimal.givens().count() < grid.givens().count());
+
+        // Removing any remaining clue makes the solution ambiguous
+        assert_eq!(minimal.minimize(), minimal);
+
+        let symmetric = grid.minimize_with(Symmetry::Rotational);
+        assert!(symmetric.has_unique_solution());
+        assert!(symmetric
+            .givens()
+            .all(|(idx, _)| symmetric[(3 - idx.0, 3 - idx.1)].is_some()));
+    }
+
+    #[test]
+    fn conflicts() {
+        // Both first lines can only be completed the same way
+        let input = vec!["0 - - 0", "0 - - 0", "- - - 1", "- - - -"];
+        let grid = Grid::parse(input.into_iter()).unwrap();
+
+        assert_eq!(
+            grid.conflicting_clues(),
+            vec![Index(0, 0), Index(0, 3), Index(1, 0), Index(1, 3)]
+        );
+
+        let grid = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+        assert!(grid.unwrap().conflicting_clues().is_empty());
+    }
+}
@@ -0,0 +1,300 @@
+use std::fmt::Write;
+
+use crate::cell::Cell;
+use crate::error::Rule;
+use crate::grid::Grid;
+use crate::index::{Index, Lane};
+use crate::sign::Sign;
+
+const ADJACENCY: &str = r#"
+% No more than run adjacent identical cells
+constraint forall(i in 1..height, j in 1..width - run)(
+  sum(m in 0..run)(grid[i, j + m]) in 1..run
+);
+constraint forall(i in 1..height - run, j in 1..width)(
+  sum(m in 0..run)(grid[i + m, j]) in 1..run
+);
+"#;
+
+const BALANCE: &str = r#"
+% The ratio of ones in each lane, rounded either way in lanes not fitting it
+constraint forall(i in 1..height)(
+  sum(j in 1..width)(grid[i, j]) in width * ones div cells..(width * ones + cells - 1) div cells
+);
+constraint forall(j in 1..width)(
+  sum(i in 1..height)(grid[i, j]) in height * ones div cells..(height * ones + cells - 1) div cells
+);
+"#;
+
+const UNIQUE_LINES: &str = r#"
+% No two identical lines
+constraint forall(i, k in 1..height where i < k)(
+  exists(j in 1..width)(grid[i, j] != grid[k, j])
+);
+"#;
+
+const UNIQUE_COLUMNS: &str = r#"
+% No two identical columns
+constraint forall(j, k in 1..width where j < k)(
+  exists(i in 1..height)(grid[i, j] != grid[i, k])
+);
+"#;
+
+const DIAGONAL_ADJACENCY: &str = r#"
+% No more than run adjacent identical cells along both diagonals
+constraint forall(k in 1..width - run)(
+  sum(m in 0..run)(grid[k + m, k + m]) in 1..run
+);
+constraint forall(k in 1..width - run)(
+  sum(m in 0..run)(grid[k + m, width - k - m + 1]) in 1..run
+);
+"#;
+
+const DIAGONAL_BALANCE: &str = r#"
+% The ratio of ones along both diagonals
+constraint sum(k in 1..width)(grid[k, k]) in width * ones div cells..(width * ones + cells - 1) div cells;
+constraint sum(k in 1..width)(grid[k, width - k + 1]) in width * ones div cells..(width * ones + cells - 1) div cells;
+"#;
+
+const SOLVE: &str = r#"
+solve satisfy;
+
+output [
+  show(grid[i, j]) ++ if j == width then "\n" else " " endif
+  | i in 1..height, j in 1..width
+];
+"#;
+
+impl Grid {
+    pub fn to_minizinc(&self) -> String {
+        let mut model = String::new();
+
+        // Writing to a string cannot fail
+        let _ = writeln!(model, "% binero {}x{}", self.width(), self.height());
+        let _ = writeln!(model, "int: width = {};", self.width());
+        let _ = writeln!(model, "int: height = {};", self.height());
+        let (ones, cells) = self.rules().ones_ratio();
+        let _ = writeln!(model, "int: ones = {};", ones);
+        let _ = writeln!(model, "int: cells = {};", cells);
+        let _ = writeln!(model, "int: run = {};", self.rules().run_limit());
+        model.push_str("array[1..height, 1..width] of var 0..1: grid;\n");
+
+        // Holes are zeros which count for nothing
+        let holes = self.holes().next().is_some();
+
+        if holes {
+            model.push_str("\n% Holes\n");
+        }
+
+        for idx in self.holes() {
+            let _ = writeln!(model, "constraint grid[{}, {}] = 0;", idx.0 + 1, idx.1 + 1);
+        }
+
+        model.push_str("\n% Givens\n");
+        for (idx, cell) in self.givens() {
+            let _ = writeln!(
+                model,
+                "constraint grid[{}, {}] = {};",
+                idx.0 + 1,
+                idx.1 + 1,
+                cell
+            );
+        }
+
+        if self.signs().next().is_some() {
+            model.push_str("\n% Signs\n");
+        }
+
+        for (a, b, sign) in self.signs() {
+            let _ = writeln!(
+                model,
+                "constraint grid[{}, {}] {} grid[{}, {}];",
+                a.0 + 1,
+                a.1 + 1,
+                match sign {
+                    Sign::Equal => "=",
+                    Sign::Different => "!=",
+                },
+                b.0 + 1,
+                b.1 + 1
+            );
+        }
+
+        let rules = self.rules();
+        let adjacency = rules.enforces(Rule::Adjacency);
+        let (wrap_lines, wrap_columns) = (
+            adjacency && rules.wraps(self.width()),
+            adjacency && rules.wraps(self.height()),
+        );
+
+        let lanes: Vec<Lane> = self
+            .lines()
+            .map(Lane::Line)
+            .chain(self.columns().map(Lane::Column))
+            .collect();
+
+        if adjacency && holes {
+            model.push_str("\n% Runs going on across holes\n");
+
+            for lane in &lanes {
+                self.write_runs(&mut model, *lane);
+            }
+        }
+
+        // Lanes counted apart from the others, instead of by their share
+        let counted = holes || self.has_targets();
+
+        if counted {
+            model.push_str("\n% Ones of each lane, holes left out\n");
+
+            for lane in &lanes {
+                self.write_count(&mut model, *lane);
+            }
+        }
+
+        // Lanes with holes already have all their runs
+        let (wrap_lines, wrap_columns) = (wrap_lines && !holes, wrap_columns && !holes);
+
+        if wrap_lines || wrap_columns {
+            model.push_str("\n% Runs going over the end of the lanes\n");
+        }
+
+        if wrap_lines {
+            model.push_str(concat!(
+                "constraint forall(i in 1..height, j in width - run + 1..width)(\n",
+                "  sum(m in 0..run)(grid[i, (j + m - 1) mod width + 1]) in 1..run\n",
+                ");\n",
+            ));
+        }
+
+        if wrap_columns {
+            model.push_str(concat!(
+                "constraint forall(i in height - run + 1..height, j in 1..width)(\n",
+                "  sum(m in 0..run)(grid[(i + m - 1) mod height + 1, j]) in 1..run\n",
+                ");\n",
+            ));
+        }
+
+        if rules.has_diagonals() && self.width() == self.height() {
+            let diagonal_rules = rules.diagonal_rules();
+
+            if diagonal_rules.enforces(Rule::Adjacency) {
+                model.push_str(DIAGONAL_ADJACENCY);
+            }
+
+            if diagonal_rules.enforces(Rule::Balance) {
+                model.push_str(DIAGONAL_BALANCE);
+            }
+        }
+
+        if rules.enforces(Rule::Balance) && self.regions().next().is_some() {
+            model.push_str("\n% Regions hold their share of each value\n");
+        }
+
+        for (_, region) in self.regions().filter(|_| rules.enforces(Rule::Balance)) {
+            let cells: Vec<String> = region
+                .iter()
+                .filter(|idx| !self.is_hole(**idx))
+                .map(|idx| format!("grid[{}, {}]", idx.0 + 1, idx.1 + 1))
+                .collect();
+
+            let _ = writeln!(
+                model,
+                "constraint sum([{}]) in {}..{};",
+                cells.join(", "),
+                cells.len() - rules.max_count(cells.len(), Cell::Zero),
+                rules.max_count(cells.len(), Cell::One)
+            );
+        }
+
+        let sections = [
+            (ADJACENCY, adjacency && !holes),
+            (BALANCE, rules.enforces(Rule::Balance) && !counted),
+            (UNIQUE_LINES, rules.is_unique(Lane::Line(0))),
+            (UNIQUE_COLUMNS, rules.is_unique(Lane::Column(0))),
+        ];
+
+        for (section, _) in sections.iter().filter(|(_, enforced)| *enforced) {
+            model.push_str(section);
+        }
+
+        model.push_str(SOLVE);
+        model
+    }
+
+    // The count of ones of a lane, from its margin clue or its share
+    fn write_count(&self, model: &mut String, lane: Lane) {
+        if !self.rules().enforces(Rule::Balance) && self.target(lane).is_none() {
+            return;
+        }
+
+        let cells: Vec<String> = self
+            .lane_cells(lane)
+            .iter()
+            .map(|idx| format!("grid[{}, {}]", idx.0 + 1, idx.1 + 1))
+            .collect();
+
+        let _ = writeln!(
+            model,
+            "constraint sum([{}]) in {}..{};",
+            cells.join(", "),
+            cells.len() - self.lane_max(lane, Cell::Zero),
+            self.lane_max(lane, Cell::One)
+        );
+    }
+
+    fn lane_cells(&self, lane: Lane) -> Vec<Index> {
+        let size = match lane {
+            Lane::Line(_) => self.width(),
+            Lane::Column(_) => self.height(),
+        };
+
+        (0..size)
+            .map(|k| lane.index(k))
+            .filter(|idx| !self.is_hole(*idx))
+            .collect()
+    }
+
+    // Every run over the limit among the cells of a lane that are not holes
+    fn write_runs(&self, model: &mut String, lane: Lane) {
+        let lane = self.lane_cells(lane);
+
+        let rules = self.rules();
+        let limit = rules.run_limit();
+        let starts = if rules.wraps(lane.len()) {
+            lane.len()
+        } else {
+            lane.len().saturating_sub(limit)
+        };
+
+        for k in 0..starts {
+            let cells: Vec<String> = (0..=limit)
+                .map(|m| lane[(k + m) % lane.len()])
+                .map(|idx| format!("grid[{}, {}]", idx.0 + 1, idx.1 + 1))
+                .collect();
+
+            let _ = writeln!(
+                model,
+                "constraint sum([{}]) in 1..{};",
+                cells.join(", "),
+                limit
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export() {
+        let grid = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+        let model = grid.unwrap().to_minizinc();
+
+        assert!(model.starts_with("% binero 4x4\nint: width = 4;\nint: height = 4;\n"));
+        assert!(model.contains("constraint grid[1, 2] = 1;\nconstraint grid[1, 4] = 0;\n"));
+        assert_eq!(model.matches("constraint grid[").count(), 7);
+        assert!(model.ends_with("];\n"));
+    }
+}
@@ -0,0 +1,302 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::ParseError;
+use crate::generator::Generator;
+use crate::grid::Grid;
+use crate::metadata::Metadata;
+use crate::rating::Difficulty;
+
+// Named puzzles with their metadata, and their solution when known
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Pack {
+    puzzles: Vec<PackEntry>,
+}
+
+// Grids are stored with their compact encoding
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(try_from = "RawEntry", into = "RawEntry")]
+pub struct PackEntry {
+    name: String,
+    puzzle: Grid,
+    solution: Option<Grid>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawEntry {
+    name: String,
+    #[serde(flatten)]
+    metadata: Metadata,
+    puzzle: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    solution: Option<String>,
+}
+
+impl Pack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn puzzles(&self) -> &[PackEntry] {
+        &self.puzzles
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &PackEntry> {
+        self.puzzles.iter()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&PackEntry> {
+        self.puzzles.iter().find(|entry| entry.name == name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.puzzles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.puzzles.is_empty()
+    }
+
+    // Entries whose name is already taken are renamed after their position
+    pub fn push(&mut self, mut entry: PackEntry) {
+        if entry.name.is_empty() || self.get(&entry.name).is_some() {
+            let mut k = self.puzzles.len() + 1;
+            while self.get(&k.to_string()).is_some() {
+                k += 1;
+            }
+
+            entry.name = k.to_string();
+        }
+
+        self.puzzles.push(entry);
+    }
+
+    pub fn append(&mut self, other: Pack) {
+        for entry in other.puzzles {
+            self.push(entry);
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("packs are always serializable")
+    }
+
+    pub fn from_json(s: &str) -> Result<Pack, ParseError> {
+        serde_json::from_str(s).map_err(|_| ParseError::InvalidEncoding)
+    }
+
+    pub fn load<P>(path: P) -> io::Result<Pack>
+    where
+        P: AsRef<Path>,
+    {
+        let content = fs::read_to_string(path)?;
+        Pack::from_json(&content).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    pub fn save<P>(&self, path: P) -> io::Result<()>
+    where
+        P: AsRef<Path>,
+    {
+        fs::write(path, self.to_json())
+    }
+}
+
+impl<'a> IntoIterator for &'a Pack {
+    type Item = &'a PackEntry;
+    type IntoIter = std::slice::Iter<'a, PackEntry>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.puzzles.iter()
+    }
+}
+
+impl PackEntry {
+    pub fn new<S: Into<String>>(name: S, puzzle: Grid) -> Self {
+        Self {
+            name: name.into(),
+            puzzle,
+            solution: None,
+        }
+    }
+
+    pub fn with_solution(mut self, solution: Grid) -> Self {
+        self.solution = Some(solution);
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn metadata(&self) -> &Metadata {
+        self.puzzle.metadata()
+    }
+
+    pub fn seed(&self) -> Option<u64> {
+        self.metadata().seed()
+    }
+
+    pub fn difficulty(&self) -> Option<Difficulty> {
+        self.metadata().difficulty()
+    }
+
+    pub fn puzzle(&self) -> &Grid {
+        &self.puzzle
+    }
+
+    pub fn solution(&self) -> Option<&Grid> {
+        self.solution.as_ref()
+    }
+}
+
+impl Generator {
+    pub fn generate_pack(&self, count: usize) -> Pack {
+        let mut rng = self.rng();
+        let mut seen = HashSet::new();
+        let mut puzzles = Vec::new();
+
+        while puzzles.len() < count {
+            // Each puzzle gets its own seed, so it can be generated again alone
+            let seeds: Vec<_> = (puzzles.len()..count).map(|_| rng.next_u64()).collect();
+            let before = puzzles.len();
+
+            // Skip puzzles that only differ by a rotation, reflection or swap
+            for entry in self.generate_entries(&seeds) {
+                if seen.insert(entry.puzzle.canonical_form().to_compact_string()) {
+                    puzzles.push(entry);
+                }
+            }
+
+            // Small grids may not have enough different puzzles
+            if puzzles.len() == before {
+                break;
+            }
+        }
+
+        for (k, entry) in puzzles.iter_mut().enumerate() {
+            entry.name = (k + 1).to_string();
+        }
+
+        Pack { puzzles }
+    }
+
+    fn generate_entries(&self, seeds: &[u64]) -> Vec<PackEntry> {
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+
+            seeds.par_iter().map(|seed| self.entry(*seed)).collect()
+        }
+
+        #[cfg(not(feature = "parallel"))]
+        seeds.iter().map(|seed| self.entry(*seed)).collect()
+    }
+
+    fn entry(&self, seed: u64) -> PackEntry {
+        let puzzle = self.clone().seed(seed).generate();
+
+        let mut solution = puzzle.clone();
+        solution.solve().expect("generated puzzles are solvable");
+
+        PackEntry::new(String::new(), puzzle).with_solution(solution)
+    }
+}
+
+impl TryFrom<RawEntry> for PackEntry {
+    type Error = ParseError;
+
+    fn try_from(raw: RawEntry) -> Result<Self, Self::Error> {
+        let mut puzzle = Grid::from_compact_string(&raw.puzzle)?;
+        puzzle.set_metadata(raw.metadata);
+
+        let solution = match raw.solution {
+            Some(solution) => Some(Grid::from_compact_string(&solution)?),
+            None => None,
+        };
+
+        Ok(Self {
+            name: raw.name,
+            puzzle,
+            solution,
+        })
+    }
+}
+
+impl From<PackEntry> for RawEntry {
+    fn from(entry: PackEntry) -> Self {
+        Self {
+            name: entry.name,
+            metadata: entry.puzzle.metadata().clone(),
+            puzzle: entry.puzzle.to_compact_string(),
+            solution: entry.solution.map(|solution| solution.to_compact_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pack_round_trip() {
+        let pack = Generator::new(6, 6).unwrap().seed(1).generate_pack(3);
+        assert_eq!(pack.puzzles().len(), 3);
+
+        for entry in pack.puzzles() {
+            let solution = entry.solution().unwrap();
+            assert!(entry.puzzle().verify_solution(solution).is_ok());
+            assert_eq!(
+                entry.puzzle(),
+                &Generator::new(6, 6)
+                    .unwrap()
+                    .seed(entry.seed().unwrap())
+                    .generate()
+            );
+        }
+
+        // Packs never hold the same puzzle twice, even transformed
+        let small = Generator::new(4, 4).unwrap().seed(1).generate_pack(50);
+        let forms: HashSet<_> = small
+            .puzzles()
+            .iter()
+            .map(|entry| entry.puzzle().canonical_form().to_compact_string())
+            .collect();
+        assert_eq!(forms.len(), small.puzzles().len());
+
+        let loaded = Pack::from_json(&pack.to_json()).unwrap();
+        assert_eq!(loaded.puzzles()[2].puzzle(), pack.puzzles()[2].puzzle());
+        assert_eq!(loaded.puzzles()[2].name(), "3");
+
+        assert_eq!(loaded.puzzles()[2].metadata(), pack.puzzles()[2].metadata());
+
+        assert!(Pack::from_json("{\"puzzles\": [{}]}").is_err());
+    }
+
+    #[test]
+    fn editing() {
+        let puzzle = Generator::new(4, 4).unwrap().seed(2).generate();
+        let mut pack = Pack::new();
+
+        pack.push(PackEntry::new("daily", puzzle));
+        pack.append(Generator::new(4, 4).unwrap().seed(3).generate_pack(2));
+        pack.push(PackEntry::new(
+            "daily",
+            Grid::parse(vec!["- -", "- -"].into_iter()).unwrap(),
+        ));
+
+        let names: Vec<_> = pack.iter().map(PackEntry::name).collect();
+        assert_eq!(names, ["daily", "1", "2", "4"]);
+        assert!(pack.get("daily").unwrap().solution().is_none());
+
+        // Solutions are optional, and so is everything but the name and puzzle
+        let loaded =
+            Pack::from_json(r#"{"puzzles": [{"name": "a", "title": "A", "puzzle": "2x2.b"}]}"#);
+        let loaded = loaded.unwrap();
+        assert_eq!(loaded.get("a").unwrap().metadata().title(), Some("A"));
+        assert!(loaded.get("a").unwrap().solution().is_none());
+    }
+}
@@ -0,0 +1,230 @@
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::cell::Cell;
+use crate::error::ParseError;
+use crate::rules::Rules;
+
+// Default limits of parsing, far beyond what the solver handles in a
+// reasonable time, while keeping huge inputs out
+const MAX_SIDE: usize = 1024;
+const MAX_CELLS: usize = 1 << 16;
+
+#[derive(Clone, Debug)]
+pub struct ParseOptions {
+    zero: char,
+    one: char,
+    empty: Vec<char>,
+    hole: char,
+    margins: bool,
+    comment: String,
+    rules: Rules,
+    // Largest width and height, and number of cells, read before giving up
+    max_size: (usize, usize),
+    max_cells: usize,
+    unicode_digits: bool,
+}
+
+impl ParseOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn zero(mut self, c: char) -> Self {
+        self.zero = c;
+        self
+    }
+
+    pub fn one(mut self, c: char) -> Self {
+        self.one = c;
+        self
+    }
+
+    pub fn empty<I>(mut self, chars: I) -> Self
+    where
+        I: IntoIterator<Item = char>,
+    {
+        self.empty = chars.into_iter().collect();
+        self
+    }
+
+    // Cells outside of the puzzle, in masked grids
+    pub fn hole(mut self, c: char) -> Self {
+        self.hole = c;
+        self
+    }
+
+    // The first line counts the ones of each column, and each line of cells
+    // starts with its count of ones
+    pub fn margins(mut self, margins: bool) -> Self {
+        self.margins = margins;
+        self
+    }
+
+    pub fn comment<S>(mut self, prefix: S) -> Self
+    where
+        S: Into<String>,
+    {
+        let prefix = prefix.into();
+        assert!(!prefix.is_empty(), "empty comment prefix");
+
+        self.comment = prefix;
+        self
+    }
+
+    pub fn rules(mut self, rules: Rules) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    // Refuse wider or taller grids as soon as they are seen, without reading
+    // the rest of the input
+    pub fn max_size(mut self, width: usize, height: usize) -> Self {
+        self.max_size = (width, height);
+        self
+    }
+
+    // Refuse grids with more cells, whatever their shape
+    pub fn max_cells(mut self, cells: usize) -> Self {
+        self.max_cells = cells;
+        self
+    }
+
+    // Read full-width characters and the digits of other scripts, such as
+    // '０' or '١', as their ASCII counterparts
+    pub fn unicode_digits(mut self, unicode_digits: bool) -> Self {
+        self.unicode_digits = unicode_digits;
+        self
+    }
+
+    // Refuse grids, or the part of one read so far, that are too large
+    pub(crate) fn check_size(&self, width: usize, height: usize) -> Result<(), ParseError> {
+        let (max_width, max_height) = self.max_size;
+
+        if width > max_width || height > max_height || width.saturating_mul(height) > self.max_cells
+        {
+            return Err(ParseError::TooLarge {
+                max_width,
+                max_height,
+                max_cells: self.max_cells,
+            });
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn cell(&self, c: char) -> Result<Option<Cell>, ParseError> {
+        match c {
+            _ if c == self.zero => Ok(Some(Cell::Zero)),
+            _ if c == self.one => Ok(Some(Cell::One)),
+            _ if self.empty.contains(&c) => Ok(None),
+            _ => Err(ParseError::invalid_char(c)),
+        }
+    }
+
+    pub(crate) fn grid_rules(&self) -> Rules {
+        self.rules
+    }
+
+    pub(crate) fn is_empty_char(&self, c: char) -> bool {
+        self.empty.contains(&c)
+    }
+
+    pub(crate) fn has_margins(&self) -> bool {
+        self.margins
+    }
+
+    pub(crate) fn is_hole_char(&self, c: char) -> bool {
+        c == self.hole
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn symbol(&self, cell: Cell) -> char {
+        match cell {
+            Cell::Zero => self.zero,
+            Cell::One => self.one,
+        }
+    }
+
+    // Header lines are comments starting with an exclamation mark
+    pub(crate) fn metadata<'a>(&self, line: &'a str) -> Option<&'a str> {
+        line.trim_start()
+            .strip_prefix(self.comment.as_str())?
+            .strip_prefix('!')
+    }
+
+    // Content of a line with its Unicode variants replaced, keeping every
+    // character at its column
+    pub(crate) fn normalize<'a>(&self, content: &'a str) -> Cow<'a, str> {
+        if !self.unicode_digits || content.is_ascii() {
+            return Cow::Borrowed(content);
+        }
+
+        let ascii = |c| ascii_variant(c).unwrap_or(c);
+        Cow::Owned(content.chars().map(ascii).collect())
+    }
+
+    // The part of a line before its comment
+    pub fn strip_comment<'a>(&self, line: &'a str) -> &'a str {
+        line.split_once(self.comment.as_str())
+            .map_or(line, |(content, _)| content)
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self {
+            zero: '0',
+            one: '1',
+            empty: vec!['-', '.', '_', '?'],
+            hole: '*',
+            margins: false,
+            comment: String::from("#"),
+            rules: Rules::default(),
+            max_size: (MAX_SIDE, MAX_SIDE),
+            max_cells: MAX_CELLS,
+            unicode_digits: false,
+        }
+    }
+}
+
+// Zeros of the scripts whose digits are read as ASCII ones: Arabic-Indic,
+// Persian, Devanagari, Bengali and Thai
+const ZEROS: [char; 5] = ['\u{660}', '\u{6f0}', '\u{966}', '\u{9e6}', '\u{e50}'];
+
+// ASCII character a Unicode one stands for, when it is a full-width form or
+// a digit of another script
+pub(crate) fn ascii_variant(c: char) -> Option<char> {
+    match c {
+        '\u{3000}' => Some(' '),
+        '\u{ff01}'..='\u{ff5e}' => char::from_u32(c as u32 - 0xfee0),
+        _ => ZEROS
+            .iter()
+            .find_map(|zero| (c as u32).checked_sub(*zero as u32).filter(|d| *d < 10))
+            .and_then(|d| char::from_digit(d, 10)),
+    }
+}
+
+// Lines as other systems write them: files often start with a byte order
+// mark, and lines end with CR LF, or only with CR. Parsing already goes
+// through it, programs reading text themselves may too
+pub fn split_lines<I, S>(lines: I) -> impl Iterator<Item = String>
+where
+    I: Iterator<Item = S>,
+    S: AsRef<str>,
+{
+    lines.enumerate().flat_map(|(n, line)| {
+        let line = line.as_ref();
+        let line = match n {
+            0 => line.strip_prefix('\u{feff}').unwrap_or(line),
+            _ => line,
+        };
+
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        line.split('\r')
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+    })
+}
@@ -0,0 +1,126 @@
+use std::io::Cursor;
+
+use image::{ImageFormat, Rgb, RgbImage};
+
+use crate::cell::{Cell, CellState};
+use crate::grid::Grid;
+use crate::index::Index;
+
+const BACKGROUND: Rgb<u8> = Rgb([255, 255, 255]);
+const BORDER: Rgb<u8> = Rgb([0, 0, 0]);
+const LINES: Rgb<u8> = Rgb([128, 128, 128]);
+const GIVEN: Rgb<u8> = Rgb([0, 0, 0]);
+const SOLVED: Rgb<u8> = Rgb([70, 130, 180]);
+
+// Digits on a 5x7 bitmap, one row per byte
+const ZERO: [u8; 7] = [
+    0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110,
+];
+const ONE: [u8; 7] = [
+    0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110,
+];
+
+impl Grid {
+    pub fn to_png(&self, cell_size: u32) -> Vec<u8> {
+        assert!(cell_size > 0, "cells need a size");
+
+        let width = (self.width() as u32 * cell_size).max(1);
+        let height = (self.height() as u32 * cell_size).max(1);
+        let mut image = RgbImage::from_pixel(width, height, BACKGROUND);
+
+        for i in self.lines() {
+            for j in self.columns() {
+                let (x, y) = (j as u32 * cell_size, i as u32 * cell_size);
+
+                draw_rect(&mut image, x, y, cell_size, LINES);
+
+                let Some(cell) = self[(i, j)] else {
+                    continue;
+                };
+
+                // Givens stand out from the cells filled by the solver
+                let color = match self.state(Index(i, j)) {
+                    Some(CellState::Given) => GIVEN,
+                    _ => SOLVED,
+                };
+
+                let glyph = match cell {
+                    Cell::Zero => &ZERO,
+                    Cell::One => &ONE,
+                };
+
+                draw_glyph(&mut image, x, y, cell_size, glyph, color);
+            }
+        }
+
+        // Outer border, two pixels thick
+        for (x, y, pixel) in image.enumerate_pixels_mut() {
+            if x < 2 || y < 2 || x + 2 >= width || y + 2 >= height {
+                *pixel = BORDER;
+            }
+        }
+
+        let mut png = Cursor::new(Vec::new());
+        image
+            .write_to(&mut png, ImageFormat::Png)
+            .expect("encoding to memory cannot fail");
+
+        png.into_inner()
+    }
+}
+
+// Outline of a square
+fn draw_rect(image: &mut RgbImage, x: u32, y: u32, size: u32, color: Rgb<u8>) {
+    for k in 0..size {
+        image.put_pixel(x + k, y, color);
+        image.put_pixel(x + k, y + size - 1, color);
+        image.put_pixel(x, y + k, color);
+        image.put_pixel(x + size - 1, y + k, color);
+    }
+}
+
+// Glyph scaled to about two thirds of the cell, and centered
+fn draw_glyph(image: &mut RgbImage, x: u32, y: u32, size: u32, glyph: &[u8; 7], color: Rgb<u8>) {
+    let scale = (size * 2 / 3 / 7).max(1);
+    let x = x + size.saturating_sub(5 * scale) / 2;
+    let y = y + size.saturating_sub(7 * scale) / 2;
+
+    for (row, bits) in glyph.iter().enumerate() {
+        for col in 0..5 {
+            if bits & (0b10000 >> col) == 0 {
+                continue;
+            }
+
+            for dy in 0..scale {
+                for dx in 0..scale {
+                    let (px, py) = (x + col * scale + dx, y + row as u32 * scale + dy);
+
+                    // Cells too small for the glyph get it clipped
+                    if px < image.width() && py < image.height() {
+                        image.put_pixel(px, py, color);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export() {
+        let input = vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"];
+        let mut grid = Grid::parse(input.into_iter()).unwrap();
+        grid.solve().unwrap();
+
+        let png = grid.to_png(16);
+        assert!(png.starts_with(b"\x89PNG"));
+
+        let image = image::load_from_memory(&png).unwrap().to_rgb8();
+        assert_eq!(image.dimensions(), (64, 64));
+        assert_eq!(*image.get_pixel(0, 0), BORDER);
+        assert!(image.pixels().any(|pixel| *pixel == SOLVED));
+    }
+}
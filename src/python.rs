@@ -0,0 +1,173 @@
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+use crate::cell::Cell;
+use crate::error::ParseError;
+use crate::generator::{Generator, GeneratorConfig};
+use crate::grid::{Grid, GridCell};
+use crate::rating::Difficulty;
+
+// Lines of 0, 1 or None on the Python side
+type Rows = Vec<Vec<Option<u8>>>;
+
+#[pyclass(name = "Grid", module = "binero", frozen)]
+struct PyGrid(Grid);
+
+fn value_error(err: impl ToString) -> PyErr {
+    PyValueError::new_err(err.to_string().trim_start_matches("error: ").to_string())
+}
+
+#[pymethods]
+impl PyGrid {
+    #[new]
+    fn new(rows: Rows) -> PyResult<Self> {
+        let cells = rows
+            .into_iter()
+            .map(|line| {
+                line.into_iter()
+                    .map(|cell| match cell {
+                        None => Ok(None),
+                        Some(0) => Ok(Some(Cell::Zero)),
+                        Some(1) => Ok(Some(Cell::One)),
+                        Some(_) => Err(ParseError::InvalidEncoding),
+                    })
+                    .collect::<Result<Vec<GridCell>, _>>()
+            })
+            .collect::<Result<_, _>>()
+            .map_err(value_error)?;
+
+        Grid::from_cells(cells).map(Self).map_err(value_error)
+    }
+
+    // A grid in the text format
+    #[staticmethod]
+    fn parse(text: &str) -> PyResult<Self> {
+        Grid::parse(text.lines()).map(Self).map_err(value_error)
+    }
+
+    #[getter]
+    fn width(&self) -> usize {
+        self.0.width()
+    }
+
+    #[getter]
+    fn height(&self) -> usize {
+        self.0.height()
+    }
+
+    #[getter]
+    fn rows(&self) -> Rows {
+        self.0
+            .lines()
+            .map(|i| {
+                self.0
+                    .line(i)
+                    .map(|cell| cell.map(|cell| (cell == Cell::One) as u8))
+                    .collect()
+            })
+            .collect()
+    }
+
+    // The solved grid, the grid itself is left untouched
+    fn solve(&self, py: Python<'_>) -> PyResult<Self> {
+        let mut grid = self.0.clone();
+
+        py.detach(|| grid.solve()).map_err(value_error)?;
+        Ok(Self(grid))
+    }
+
+    fn rate(&self, py: Python<'_>) -> PyResult<String> {
+        let difficulty = py.detach(|| self.0.rate()).map_err(value_error)?;
+        Ok(difficulty.to_string())
+    }
+
+    fn __str__(&self) -> String {
+        self.0.to_string()
+    }
+
+    // Written the way the grid is built from Python
+    fn __repr__(&self) -> String {
+        let lines: Vec<String> = self
+            .rows()
+            .iter()
+            .map(|line| {
+                let cells: Vec<_> = line
+                    .iter()
+                    .map(|cell| cell.map_or("None".to_string(), |cell| cell.to_string()))
+                    .collect();
+                format!("[{}]", cells.join(", "))
+            })
+            .collect();
+
+        format!("Grid([{}])", lines.join(", "))
+    }
+
+    fn __eq__(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+// Grids can be given as they are, or as lists of lines
+#[derive(FromPyObject)]
+enum GridArg {
+    Grid(Py<PyGrid>),
+    Rows(Rows),
+}
+
+impl GridArg {
+    fn into_grid(self) -> PyResult<PyGrid> {
+        match self {
+            Self::Grid(grid) => Ok(PyGrid(grid.get().0.clone())),
+            Self::Rows(rows) => PyGrid::new(rows),
+        }
+    }
+}
+
+#[pyfunction]
+fn solve(py: Python<'_>, grid: GridArg) -> PyResult<PyGrid> {
+    grid.into_grid()?.solve(py)
+}
+
+#[pyfunction]
+fn rate(py: Python<'_>, grid: GridArg) -> PyResult<String> {
+    grid.into_grid()?.rate(py)
+}
+
+#[pyfunction]
+#[pyo3(signature = (width, height, difficulty = None, seed = None))]
+fn generate(
+    py: Python<'_>,
+    width: usize,
+    height: usize,
+    difficulty: Option<&str>,
+    seed: Option<u64>,
+) -> PyResult<PyGrid> {
+    let mut config = GeneratorConfig::new();
+
+    if let Some(name) = difficulty {
+        let difficulty = Difficulty::iter()
+            .find(|d| d.to_string() == name)
+            .ok_or_else(|| value_error(format!("unknown difficulty '{}'", name)))?;
+        config = config.difficulty(difficulty);
+    }
+
+    let mut generator = Generator::new(width, height)
+        .map_err(value_error)?
+        .config(config);
+
+    if let Some(seed) = seed {
+        generator = generator.seed(seed);
+    }
+
+    Ok(PyGrid(py.detach(|| generator.generate())))
+}
+
+#[pymodule]
+fn binero(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<PyGrid>()?;
+    module.add_function(wrap_pyfunction!(solve, module)?)?;
+    module.add_function(wrap_pyfunction!(rate, module)?)?;
+    module.add_function(wrap_pyfunction!(generate, module)?)?;
+
+    Ok(())
+}
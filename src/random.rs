@@ -0,0 +1,55 @@
+#[cfg(feature = "std")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rand_chacha::ChaCha8Rng;
+use rand_core::{RngCore, SeedableRng};
+
+// ChaCha gives the same sequence for a seed on every platform and release
+#[derive(Clone, Debug)]
+pub(crate) struct Rng(ChaCha8Rng);
+
+impl Rng {
+    pub(crate) fn new(seed: u64) -> Self {
+        Self(ChaCha8Rng::seed_from_u64(seed))
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn from_time() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_nanos() as u64);
+
+        Self::new(nanos)
+    }
+
+    // Without a clock, unseeded sequences always start the same way
+    #[cfg(not(feature = "std"))]
+    pub(crate) fn from_time() -> Self {
+        Self::new(0)
+    }
+
+    // Position in the sequence, to carry on from there later
+    #[cfg(feature = "std")]
+    pub(crate) fn drawn(&self) -> u128 {
+        self.0.get_word_pos()
+    }
+
+    #[cfg(feature = "std")]
+    pub(crate) fn set_drawn(&mut self, drawn: u128) {
+        self.0.set_word_pos(drawn);
+    }
+
+    pub(crate) fn next_u64(&mut self) -> u64 {
+        self.0.next_u64()
+    }
+
+    pub(crate) fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    pub(crate) fn shuffle<T>(&mut self, items: &mut [T]) {
+        for k in (1..items.len()).rev() {
+            items.swap(k, self.below(k + 1));
+        }
+    }
+}
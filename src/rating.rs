@@ -0,0 +1,309 @@
+use alloc::vec::Vec;
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Limit, SolveError};
+use crate::grid::Grid;
+use crate::solver::{SolveStats, SolverConfig};
+use crate::technique::Technique;
+
+// Effort of a guess, compared to a cell filled by a technique
+const GUESS_WEIGHT: usize = 10;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+    Expert,
+}
+
+impl Difficulty {
+    pub fn iter() -> impl Iterator<Item = Difficulty> {
+        [Self::Easy, Self::Medium, Self::Hard, Self::Expert].into_iter()
+    }
+
+    // Weakest solver able to solve puzzles of this difficulty
+    fn config(&self) -> SolverConfig {
+        match self {
+            Self::Easy => SolverConfig::new().heuristics(false).max_depth(0),
+            Self::Medium => SolverConfig::new().max_depth(0),
+            Self::Hard => SolverConfig::new().lookahead(1).max_depth(1),
+            Self::Expert => SolverConfig::new(),
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Easy => write!(fmt, "easy"),
+            Self::Medium => write!(fmt, "medium"),
+            Self::Hard => write!(fmt, "hard"),
+            Self::Expert => write!(fmt, "expert"),
+        }
+    }
+}
+
+// Skill of a human solver, by the techniques they know and how far they
+// look ahead
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Beginner,
+    Intermediate,
+    Advanced,
+    Expert,
+}
+
+impl Level {
+    pub fn iter() -> impl Iterator<Item = Level> {
+        [
+            Self::Beginner,
+            Self::Intermediate,
+            Self::Advanced,
+            Self::Expert,
+        ]
+        .into_iter()
+    }
+
+    pub fn techniques(&self) -> Vec<Technique> {
+        let count = match self {
+            Self::Beginner => 3,
+            Self::Intermediate => 5,
+            Self::Advanced | Self::Expert => 7,
+        };
+
+        // Each level knows the techniques of the ones below
+        [
+            Technique::Adjacency,
+            Technique::Balance,
+            Technique::Sign,
+            Technique::Uniqueness,
+            Technique::UniqueCompletion,
+            Technique::Completion,
+            Technique::Crossing,
+        ]
+        .into_iter()
+        .take(count)
+        .collect()
+    }
+
+    // Guesses tried in a row before giving up, only experts try any
+    pub fn lookahead(&self) -> usize {
+        match self {
+            Self::Expert => 1,
+            _ => 0,
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Beginner => write!(fmt, "beginner"),
+            Self::Intermediate => write!(fmt, "intermediate"),
+            Self::Advanced => write!(fmt, "advanced"),
+            Self::Expert => write!(fmt, "expert"),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rating {
+    difficulty: Difficulty,
+    score: usize,
+    techniques: Vec<Technique>,
+    guesses: usize,
+    guessing: bool,
+    unique: bool,
+}
+
+impl Rating {
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    // Effort needed to solve the grid, to compare grids of a same difficulty
+    pub fn score(&self) -> usize {
+        self.score
+    }
+
+    pub fn techniques(&self) -> &[Technique] {
+        &self.techniques
+    }
+
+    pub fn guesses(&self) -> usize {
+        self.guesses
+    }
+
+    // Whether deductions alone cannot finish the grid
+    pub fn requires_guessing(&self) -> bool {
+        self.guessing
+    }
+
+    pub fn is_unique(&self) -> bool {
+        self.unique
+    }
+}
+
+impl Grid {
+    pub fn rate(&self) -> Result<Difficulty, SolveError> {
+        self.rate_with_stats(&SolverConfig::default())
+            .map(|(difficulty, _)| difficulty)
+    }
+
+    pub fn rate_report(&self) -> Result<Rating, SolveError> {
+        self.rate_report_with(&SolverConfig::default())
+    }
+
+    // Rate within the node and time limits of a configuration, for each
+    // solver tried and for the uniqueness check
+    pub fn rate_report_with(&self, config: &SolverConfig) -> Result<Rating, SolveError> {
+        let (difficulty, stats) = self.rate_with_stats(config)?;
+        let limits = SolverConfig::new().limited_by(config);
+
+        // Harder techniques weigh more for each cell they fill
+        let score = Technique::iter()
+            .map(|technique| technique.weight() * stats.filled(technique))
+            .sum::<usize>()
+            + GUESS_WEIGHT * stats.guesses();
+
+        Ok(Rating {
+            difficulty,
+            score,
+            techniques: stats.techniques().collect(),
+            guesses: stats.guesses(),
+            // Medium puzzles are the hardest ones solved without guessing
+            guessing: difficulty > Difficulty::Medium,
+            unique: self.count_solutions_with(2, &limits)? == 1,
+        })
+    }
+
+    // Whether the grid cannot be finished by deduction alone, which also
+    // holds for grids without any solution
+    pub fn requires_guessing(&self) -> bool {
+        let config = SolverConfig::new().max_depth(0);
+        self.clone().solve_with(&config).is_err()
+    }
+
+    // Lowest level of solver able to solve the grid, if any
+    pub fn level(&self) -> Result<Option<Level>, SolveError> {
+        for level in Level::iter() {
+            match self.clone().solve_with(&SolverConfig::new().level(level)) {
+                Ok(()) => return Ok(Some(level)),
+                Err(SolveError::LimitExceeded(_)) => continue,
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn rate_with_stats(
+        &self,
+        config: &SolverConfig,
+    ) -> Result<(Difficulty, SolveStats), SolveError> {
+        // Try stronger and stronger solvers until one succeeds, only giving
+        // up on the whole rating when the limits are reached
+        for difficulty in Difficulty::iter() {
+            match self
+                .clone()
+                .solve_with_stats(&difficulty.config().limited_by(config))
+            {
+                Ok(stats) => return Ok((difficulty, stats)),
+                Err(SolveError::LimitExceeded(Limit::Depth(_))) => continue,
+                Err(err) => return Err(err),
+            }
+        }
+
+        Err(SolveError::NoSolution(None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::Generator;
+
+    #[test]
+    fn ratings() {
+        let grid = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+        assert_eq!(grid.unwrap().rate().unwrap(), Difficulty::Easy);
+
+        // Nothing can be deduced on an empty grid
+        let grid = Grid::parse(vec!["- - - -"; 4].into_iter());
+        assert!(grid.unwrap().rate().unwrap() >= Difficulty::Hard);
+
+        let grid = Grid::parse(vec!["0 - - 0", "0 - - 0", "- - - -", "- - - -"].into_iter());
+        assert!(matches!(
+            grid.unwrap().rate(),
+            Err(SolveError::NoSolution(_))
+        ));
+    }
+
+    #[test]
+    fn guessing() {
+        let grid = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+        assert!(!grid.unwrap().requires_guessing());
+
+        let grid = Grid::parse(vec!["- - - -"; 4].into_iter());
+        assert!(grid.unwrap().requires_guessing());
+
+        // Grids without a solution cannot be finished either
+        let grid = Grid::parse(vec!["0 - - 0", "0 - - 0", "- - - -", "- - - -"].into_iter());
+        assert!(grid.unwrap().requires_guessing());
+    }
+
+    #[test]
+    fn levels() {
+        let grid = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+        assert_eq!(grid.unwrap().level().unwrap(), Some(Level::Beginner));
+
+        // Levels follow the difficulties of generated puzzles
+        for (seed, level) in [
+            (1, Level::Intermediate),
+            (5, Level::Advanced),
+            (3, Level::Expert),
+        ] {
+            let grid = Generator::new(6, 6).unwrap().seed(seed).generate();
+            assert_eq!(grid.level().unwrap(), Some(level));
+        }
+
+        // Nothing can be deduced on an empty grid
+        let grid = Grid::parse(vec!["- - - - - -"; 6].into_iter());
+        assert_eq!(grid.unwrap().level().unwrap(), None);
+    }
+
+    #[test]
+    fn reports() {
+        let grid = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+        let report = grid.unwrap().rate_report().unwrap();
+
+        assert_eq!(report.difficulty(), Difficulty::Easy);
+        assert_eq!(report.guesses(), 0);
+        assert!(!report.requires_guessing());
+        assert!(report.is_unique());
+        assert!(report.techniques().contains(&Technique::Adjacency));
+        assert!(!report.techniques().contains(&Technique::Completion));
+        assert!(report.score() >= 9);
+
+        let grid = Grid::parse(vec!["- - - -"; 4].into_iter()).unwrap();
+        let report = grid.rate_report().unwrap();
+
+        assert!(report.guesses() > 0);
+        assert!(report.requires_guessing());
+        assert!(!report.is_unique());
+        assert!(report.score() >= GUESS_WEIGHT);
+
+        // Limits are kept through every solver tried
+        let grid = Grid::parse(vec!["- - - - - - - - - -"; 10].into_iter()).unwrap();
+        let config = SolverConfig::new().max_nodes(1);
+        assert!(matches!(
+            grid.rate_report_with(&config),
+            Err(SolveError::LimitExceeded(Limit::Nodes(1)))
+        ));
+    }
+}
@@ -0,0 +1,213 @@
+use alloc::collections::BTreeMap;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::mem;
+
+use crate::cell::Cell;
+use crate::error::ParseError;
+use crate::grid::{Grid, GridCell};
+use crate::index::Index;
+use crate::parse::ParseOptions;
+
+// The label of the region of each cell, line by line, and the cells of each
+// region; both are empty on grids without regions
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Regions {
+    labels: Vec<char>,
+    cells: BTreeMap<char, Vec<Index>>,
+}
+
+impl Regions {
+    pub(crate) fn new(labels: Vec<char>, width: usize) -> Self {
+        let mut cells: BTreeMap<char, Vec<Index>> = BTreeMap::new();
+
+        for (k, label) in labels.iter().enumerate() {
+            cells
+                .entry(*label)
+                .or_default()
+                .push(Index(k / width, k % width));
+        }
+
+        Self { labels, cells }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+}
+
+impl Grid {
+    // One letter or digit per cell, line by line, the cells with the same one
+    // making a region that holds its share of each value, like lanes do
+    pub fn parse_regions<I, S>(&mut self, lines: I) -> Result<(), ParseError>
+    where
+        I: Iterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let options = ParseOptions::default();
+        let mut labels = Vec::new();
+        let mut height = 0;
+
+        for (n, line) in lines.enumerate().map(|(n, line)| (n + 1, line)) {
+            let line: Vec<(usize, char)> = options
+                .strip_comment(line.as_ref())
+                .chars()
+                .enumerate()
+                .filter(|(_, c)| !c.is_whitespace())
+                .collect();
+
+            if line.is_empty() {
+                continue;
+            } else if let Some((p, c)) = line.iter().find(|(_, c)| !c.is_alphanumeric()) {
+                return Err(ParseError::invalid_char(*c).at(n, p + 1));
+            } else if line.len() != self.width() {
+                return Err(ParseError::WidthMismatch {
+                    line: n,
+                    expected: self.width(),
+                    found: line.len(),
+                });
+            }
+
+            labels.extend(line.into_iter().map(|(_, c)| c));
+            height += 1;
+        }
+
+        if height != self.height() {
+            return Err(ParseError::SizeMismatch {
+                expected: (self.width(), self.height()),
+                found: (self.width(), height),
+            });
+        }
+
+        let regions = Regions::new(labels, self.width());
+        let rules = self.rules();
+
+        if let Some((label, _)) = regions
+            .cells
+            .iter()
+            .find(|(_, cells)| !rules.fits(self.usable(cells)))
+        {
+            return Err(ParseError::UnbalancedRegion(*label));
+        }
+
+        // The grid is left as it was when its cells break the new regions
+        let previous = mem::replace(&mut self.regions, regions);
+
+        self.is_valid().map_err(|violation| {
+            self.regions = previous;
+            ParseError::InvalidGrid(violation)
+        })
+    }
+
+    pub fn region<I>(&self, idx: I) -> Option<char>
+    where
+        I: Into<Index>,
+    {
+        let Index(i, j) = idx.into();
+        self.regions.labels.get(i * self.width() + j).copied()
+    }
+
+    // Each region with its cells, in the order of their labels
+    pub fn regions(&self) -> impl Iterator<Item = (char, &[Index])> + '_ {
+        self.regions
+            .cells
+            .iter()
+            .map(|(label, cells)| (*label, cells.as_slice()))
+    }
+
+    // Cells in the same region as a cell, itself included
+    pub(crate) fn region_cells(&self, idx: Index) -> &[Index] {
+        self.region(idx)
+            .map_or(&[], |label| self.regions.cells[&label].as_slice())
+    }
+
+    // Check every region, or only the one of a move, giving the cells of the
+    // one holding too many cells of a value
+    pub(crate) fn check_regions(&self, mv: Option<(Index, Cell)>) -> Result<(), &[Index]> {
+        let value = |idx: Index| -> GridCell {
+            match mv {
+                Some((other, cell)) if other == idx => Some(cell),
+                _ => self[idx],
+            }
+        };
+
+        let regions: Vec<&[Index]> = match mv {
+            Some((idx, _)) => vec![self.region_cells(idx)],
+            None => self.regions().map(|(_, cells)| cells).collect(),
+        };
+
+        for cells in regions {
+            for cell in Cell::iter() {
+                let count = cells
+                    .iter()
+                    .filter(|idx| value(**idx) == Some(cell))
+                    .count();
+
+                if count > self.rules().max_count(self.usable(cells), cell) {
+                    return Err(cells);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Holes take no value, only the other cells of a region are balanced
+    pub(crate) fn usable(&self, cells: &[Index]) -> usize {
+        cells.iter().filter(|idx| !self.is_hole(**idx)).count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Rule;
+
+    #[test]
+    fn regions() {
+        let mut grid = Grid::parse(["- - - -"; 4].iter()).unwrap();
+        let regions = ["A A B B", "A A B B", "C C D D", "C C D D"];
+        let count = grid.count_solutions(usize::MAX).unwrap();
+
+        grid.parse_regions(regions.iter()).unwrap();
+        assert_eq!(grid.region((1, 2)), Some('B'));
+        assert_eq!(grid.regions().count(), 4);
+
+        // Solutions are also balanced in each box, which rules some out
+        assert!(grid.count_solutions(usize::MAX).unwrap() < count);
+
+        let mut solution = grid.clone();
+        solution.solve().unwrap();
+        assert!(solution.regions().all(|(_, cells)| {
+            cells
+                .iter()
+                .filter(|idx| solution[**idx] == Some(Cell::One))
+                .count()
+                == 2
+        }));
+
+        // A box of three zeros, or of an odd size
+        let mut grid = Grid::parse(["0 0 - -", "0 1 - -", "- - - -", "- - - -"].iter()).unwrap();
+        let Err(ParseError::InvalidGrid(violation)) = grid.clone().parse_regions(regions.iter())
+        else {
+            panic!("the first box holds three zeros");
+        };
+        assert!(violation.lanes().is_empty());
+        assert_eq!(violation.cells(), [Index(0, 0), Index(0, 1), Index(1, 0)]);
+        assert!(matches!(
+            grid.clone().parse_regions(regions.iter()),
+            Err(ParseError::InvalidGrid(violation)) if violation.rule() == Rule::Balance
+        ));
+        assert!(matches!(
+            grid.parse_regions(["A A A B", "A A B B", "C C D D", "C C D D"].iter()),
+            Err(ParseError::UnbalancedRegion('A'))
+        ));
+
+        // Regions can also follow the cells of a text grid
+        let input = ["0 - - -", "- - - -", "- - - -", "- - - 1"];
+        let text = [&input[..], &regions[..]].concat();
+        let parsed = Grid::parse(text.iter()).unwrap();
+        assert_eq!(parsed.regions().count(), 4);
+        assert_eq!(Grid::parse(parsed.to_string().lines()).unwrap(), parsed);
+    }
+}
@@ -0,0 +1,245 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cell::Cell;
+use crate::deduction::Step;
+use crate::error::{SolveError, VerificationError};
+use crate::grid::Grid;
+use crate::index::Index;
+use crate::technique::Technique;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Move {
+    Deduction(Index, Cell, Technique),
+    Guess(Index, Cell),
+    // The latest guess led to a contradiction, its cells are emptied
+    Backtrack,
+}
+
+// Every cell set while solving a grid, in order, with the guesses undone
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "Vec<RawMove>", try_from = "Vec<RawMove>")]
+pub struct Replay {
+    moves: Vec<Move>,
+}
+
+// Cells are (line, column) pairs and values are 0 or 1, like in JSON grids
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum RawMove {
+    Deduction {
+        cell: (usize, usize),
+        value: u8,
+        technique: Technique,
+    },
+    Guess {
+        cell: (usize, usize),
+        value: u8,
+    },
+    Backtrack,
+}
+
+impl Replay {
+    pub fn moves(&self) -> &[Move] {
+        &self.moves
+    }
+
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+}
+
+impl From<Vec<Step>> for Replay {
+    fn from(steps: Vec<Step>) -> Self {
+        let moves = steps
+            .into_iter()
+            .map(|step| match step {
+                Step::Deduction(deduction) => {
+                    Move::Deduction(deduction.index(), deduction.value(), deduction.technique())
+                }
+                Step::Guess(idx, cell) => Move::Guess(idx, cell),
+                Step::Backtrack => Move::Backtrack,
+            })
+            .collect();
+
+        Self { moves }
+    }
+}
+
+impl From<Replay> for Vec<RawMove> {
+    fn from(replay: Replay) -> Self {
+        let value = |cell| (cell == Cell::One) as u8;
+
+        replay
+            .moves
+            .into_iter()
+            .map(|step| match step {
+                Move::Deduction(idx, cell, technique) => RawMove::Deduction {
+                    cell: (idx.0, idx.1),
+                    value: value(cell),
+                    technique,
+                },
+                Move::Guess(idx, cell) => RawMove::Guess {
+                    cell: (idx.0, idx.1),
+                    value: value(cell),
+                },
+                Move::Backtrack => RawMove::Backtrack,
+            })
+            .collect()
+    }
+}
+
+impl TryFrom<Vec<RawMove>> for Replay {
+    type Error = String;
+
+    fn try_from(raw: Vec<RawMove>) -> Result<Self, Self::Error> {
+        let cell = |value| match value {
+            0 => Ok(Cell::Zero),
+            1 => Ok(Cell::One),
+            _ => Err(alloc::format!("invalid cell value {}", value)),
+        };
+
+        let moves = raw
+            .into_iter()
+            .map(|step| match step {
+                RawMove::Deduction {
+                    cell: idx,
+                    value,
+                    technique,
+                } => Ok(Move::Deduction(idx.into(), cell(value)?, technique)),
+                RawMove::Guess { cell: idx, value } => Ok(Move::Guess(idx.into(), cell(value)?)),
+                RawMove::Backtrack => Ok(Move::Backtrack),
+            })
+            .collect::<Result<_, String>>()?;
+
+        Ok(Self { moves })
+    }
+}
+
+impl Grid {
+    pub fn replay(&self) -> Result<Replay, SolveError> {
+        self.explain().map(Replay::from)
+    }
+
+    // Play the first moves of a replay, such as to resume solving from there
+    pub fn apply_replay(&mut self, replay: &Replay, steps: usize) -> Result<(), VerificationError> {
+        // Cells set since each guess still standing, with the guessed cell
+        let mut frames: Vec<(Index, Vec<Index>)> = Vec::new();
+
+        for step in replay.moves.iter().take(steps) {
+            let (idx, cell) = match *step {
+                Move::Deduction(idx, cell, _) => (idx, cell),
+                Move::Guess(idx, cell) => {
+                    // Guessing again a cell means going back to its frame
+                    if let Some(k) = frames.iter().position(|(guess, _)| *guess == idx) {
+                        for (_, cells) in frames.drain(k..) {
+                            cells.into_iter().for_each(|idx| {
+                                self.set(idx, None);
+                            });
+                        }
+                    }
+
+                    frames.push((idx, Vec::new()));
+                    (idx, cell)
+                }
+                Move::Backtrack => {
+                    if let Some((_, cells)) = frames.pop() {
+                        cells.into_iter().for_each(|idx| {
+                            self.set(idx, None);
+                        });
+                    }
+
+                    continue;
+                }
+            };
+
+            if idx.0 >= self.height() || idx.1 >= self.width() {
+                return Err(VerificationError::DimensionMismatch);
+            }
+
+            if self[idx].is_some() || self.is_hole(idx) {
+                return Err(VerificationError::ClueMismatch(idx));
+            }
+
+            self.set(idx, Some(cell));
+
+            if let Some((_, cells)) = frames.last_mut() {
+                cells.push(idx);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replays() {
+        let grid = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+        let grid = grid.unwrap();
+        let replay = grid.replay().unwrap();
+
+        assert_eq!(replay.len(), 9);
+        assert_eq!(
+            replay.moves()[0],
+            Move::Deduction(Index(0, 2), Cell::Zero, Technique::Adjacency)
+        );
+
+        let mut solution = grid.clone();
+        solution.solve().unwrap();
+
+        let mut replayed = grid.clone();
+        replayed.apply_replay(&replay, replay.len()).unwrap();
+        assert_eq!(replayed, solution);
+
+        // Resuming from a step leaves the cells after it empty
+        let mut partial = grid.clone();
+        partial.apply_replay(&replay, 1).unwrap();
+        assert_eq!(partial[(0, 2)], Some(Cell::Zero));
+        assert_eq!(partial[(1, 0)], None);
+
+        // Replays do not fit grids whose cells they would overwrite
+        assert!(matches!(
+            solution.apply_replay(&replay, 1),
+            Err(VerificationError::ClueMismatch(Index(0, 2)))
+        ));
+    }
+
+    #[test]
+    fn backtracks() {
+        // The solver runs into contradictions on larger empty grids
+        let grid = Grid::parse(vec!["- - - - - - - - - -"; 10].into_iter()).unwrap();
+        let replay = grid.replay().unwrap();
+        assert!(replay.moves().contains(&Move::Backtrack));
+
+        let mut solution = grid.clone();
+        solution.solve().unwrap();
+
+        let mut replayed = grid.clone();
+        replayed.apply_replay(&replay, replay.len()).unwrap();
+        assert_eq!(replayed, solution);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn serialization() {
+        let grid = Grid::parse(vec!["- - - -"; 4].into_iter()).unwrap();
+        let replay = grid.replay().unwrap();
+
+        let json = serde_json::to_string(&replay).unwrap();
+        assert!(json.starts_with(r#"[{"kind":"guess","cell":[0,0],"value":"#));
+        assert_eq!(serde_json::from_str::<Replay>(&json).unwrap(), replay);
+
+        let json = r#"[{"kind":"guess","cell":[0,0],"value":2}]"#;
+        assert!(serde_json::from_str::<Replay>(json).is_err());
+    }
+}
@@ -0,0 +1,445 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cell::Cell;
+use crate::error::Rule;
+use crate::index::{Index, Lane};
+
+// Rules followed by a grid, the standard ones unless a variant is chosen
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rules {
+    // Lanes are cyclic, their last cell being next to their first one
+    toroidal: bool,
+    // Lanes of odd length, or not fitting the ratio, round it either way
+    odd_dimensions: bool,
+    // Ones in every so many cells of a lane, the others are zeros
+    ratio: (u32, u32),
+    // Most identical cells next to each other
+    max_run: usize,
+    // Both diagonals of square grids follow the adjacency rule, and the
+    // balance one when balanced
+    diagonals: bool,
+    diagonal_balance: bool,
+    // Rules some puzzles do without
+    adjacency: bool,
+    balance: bool,
+    unique_lines: bool,
+    unique_columns: bool,
+}
+
+impl Rules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn ratio(mut self, ones: u32, cells: u32) -> Self {
+        assert!(0 < ones && ones < cells, "lanes hold both values");
+
+        self.ratio = (ones, cells);
+        self
+    }
+
+    pub fn max_run(mut self, run: usize) -> Self {
+        assert!(0 < run && run < 64, "runs hold from 1 to 63 cells");
+
+        self.max_run = run;
+        self
+    }
+
+    pub fn toroidal(mut self, toroidal: bool) -> Self {
+        self.toroidal = toroidal;
+        self
+    }
+
+    pub fn odd_dimensions(mut self, odd_dimensions: bool) -> Self {
+        self.odd_dimensions = odd_dimensions;
+        self
+    }
+
+    pub fn diagonals(mut self, diagonals: bool) -> Self {
+        self.diagonals = diagonals;
+        self
+    }
+
+    pub fn diagonal_balance(mut self, balance: bool) -> Self {
+        self.diagonal_balance = balance;
+        self
+    }
+
+    pub fn adjacency(mut self, adjacency: bool) -> Self {
+        self.adjacency = adjacency;
+        self
+    }
+
+    pub fn balance(mut self, balance: bool) -> Self {
+        self.balance = balance;
+        self
+    }
+
+    pub fn unique_lines(mut self, unique: bool) -> Self {
+        self.unique_lines = unique;
+        self
+    }
+
+    pub fn unique_columns(mut self, unique: bool) -> Self {
+        self.unique_columns = unique;
+        self
+    }
+
+    // Whether grids must follow a rule, uniqueness being enforced as soon as
+    // lines or columns must be unique
+    pub fn enforces(&self, rule: Rule) -> bool {
+        match rule {
+            Rule::Adjacency => self.adjacency,
+            Rule::Balance => self.balance,
+            Rule::Uniqueness => self.unique_lines || self.unique_columns,
+            Rule::Sign => true,
+        }
+    }
+
+    // Whether lanes of this kind must all be different
+    pub fn is_unique(&self, lane: Lane) -> bool {
+        match lane {
+            Lane::Line(_) => self.unique_lines,
+            Lane::Column(_) => self.unique_columns,
+        }
+    }
+
+    pub fn is_toroidal(&self) -> bool {
+        self.toroidal
+    }
+
+    pub fn allows_odd_dimensions(&self) -> bool {
+        self.odd_dimensions
+    }
+
+    // As ones in every so many cells
+    pub fn ones_ratio(&self) -> (u32, u32) {
+        self.ratio
+    }
+
+    pub fn run_limit(&self) -> usize {
+        self.max_run
+    }
+
+    pub fn has_diagonals(&self) -> bool {
+        self.diagonals
+    }
+
+    pub fn is_standard(&self) -> bool {
+        *self == Self::default()
+    }
+
+    // Whether lanes of this size can follow the ratio, rounding it either way
+    // when odd dimensions are allowed
+    pub(crate) fn fits(&self, size: usize) -> bool {
+        let (ones, cells) = (self.ratio.0 as usize, self.ratio.1 as usize);
+        !self.balance || self.odd_dimensions || (size * ones).is_multiple_of(cells)
+    }
+
+    // Most cells of a value a lane of this size can hold, all of them
+    // without the balance rule
+    pub(crate) fn max_count(&self, size: usize, cell: Cell) -> usize {
+        let (ones, cells) = (self.ratio.0 as usize, self.ratio.1 as usize);
+
+        match cell {
+            _ if !self.balance => size,
+            Cell::One => (size * ones).div_ceil(cells),
+            Cell::Zero => size - size * ones / cells,
+        }
+    }
+
+    // Whether a lane of this size wraps around, too short ones cannot hold
+    // a run over the limit without going through one of its cells twice
+    pub(crate) fn wraps(&self, size: usize) -> bool {
+        self.toroidal && size > self.max_run
+    }
+
+    // Cells of both diagonals, from the top, when the grid has them
+    pub(crate) fn diagonal_cells(&self, width: usize, height: usize) -> Vec<Vec<Index>> {
+        if !self.diagonals || width != height {
+            return Vec::new();
+        }
+
+        vec![
+            (0..width).map(|k| Index(k, k)).collect(),
+            (0..width).map(|k| Index(k, width - 1 - k)).collect(),
+        ]
+    }
+
+    pub(crate) fn on_diagonal(&self, idx: Index, width: usize, height: usize) -> bool {
+        self.diagonals && width == height && (idx.0 == idx.1 || idx.0 + idx.1 == width - 1)
+    }
+
+    // Rules followed along diagonals, which never wrap around
+    pub(crate) fn diagonal_rules(&self) -> Rules {
+        Self {
+            toroidal: false,
+            balance: self.balance && self.diagonal_balance,
+            ..*self
+        }
+    }
+
+    // The cell of a lane at an offset from another one, if any
+    pub(crate) fn neighbor(&self, k: usize, offset: isize, size: usize) -> Option<usize> {
+        let m = k as isize + offset;
+
+        if self.wraps(size) {
+            Some(m.rem_euclid(size as isize) as usize)
+        } else {
+            usize::try_from(m).ok().filter(|m| *m < size)
+        }
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            toroidal: false,
+            odd_dimensions: false,
+            ratio: (1, 2),
+            max_run: 2,
+            diagonals: false,
+            diagonal_balance: false,
+            adjacency: true,
+            balance: true,
+            unique_lines: true,
+            unique_columns: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::deduction::Step;
+    use crate::error::ParseError;
+    use crate::grid::Grid;
+    use crate::index::Index;
+    use crate::parse::ParseOptions;
+    use crate::solver::SolverConfig;
+
+    #[test]
+    fn toroidal() {
+        let torus = Rules::new().toroidal(true);
+        let options = ParseOptions::new().rules(torus);
+
+        // The last cell of the line is next to the first two
+        let input = ["0 0 1 1 0 1 1 0", "- - - - - - - -"];
+        assert!(Grid::parse(input.iter()).is_ok());
+        assert!(Grid::parse_with(input.iter(), &options).is_err());
+
+        let mut grid = Grid::parse(["- - - - - - - -"; 8].iter()).unwrap();
+        grid.solve_with(&SolverConfig::new().rules(torus)).unwrap();
+        assert_eq!(grid.rules(), torus);
+        assert!(grid.is_valid().is_ok());
+
+        for lane in grid.lines().map(|i| grid.line(i).collect::<Vec<_>>()) {
+            assert!((0..8).all(|k| lane[k] != lane[(k + 1) % 8] || lane[k] != lane[(k + 2) % 8]));
+        }
+
+        assert_eq!(torus.neighbor(0, -1, 8), Some(7));
+        assert_eq!(Rules::new().neighbor(0, -1, 8), None);
+    }
+
+    #[test]
+    fn odd_dimensions() {
+        let odd = Rules::new().odd_dimensions(true);
+        let options = ParseOptions::new().rules(odd);
+        let empty = ["- - -"; 5];
+
+        assert!(matches!(
+            Grid::parse(empty.iter()),
+            Err(ParseError::OddDimension {
+                width: 3,
+                height: 5
+            })
+        ));
+
+        // Every complete grid following the rules, found one by one
+        let valid = (0..1u32 << 15)
+            .filter(|bits| {
+                let cells = (0..5)
+                    .map(|i| {
+                        (0..3)
+                            .map(|j| match bits >> (3 * i + j) & 1 {
+                                0 => Some(Cell::Zero),
+                                _ => Some(Cell::One),
+                            })
+                            .collect()
+                    })
+                    .collect();
+
+                Grid::from_cells_with(cells, odd).is_ok()
+            })
+            .count();
+
+        let grid = Grid::parse_with(empty.iter(), &options).unwrap();
+        assert_eq!(grid.count_solutions(usize::MAX).unwrap(), valid);
+
+        // Lanes of five cells hold two or three ones
+        assert!(Grid::parse_with(["0 0 1 0 1", "- - - - -"].iter(), &options).is_ok());
+        assert!(matches!(
+            Grid::parse_with(["0 0 1 0 0", "- - - - -"].iter(), &options),
+            Err(ParseError::InvalidGrid(violation)) if violation.rule() == Rule::Balance
+        ));
+    }
+
+    #[test]
+    fn toggles() {
+        let loose = Rules::new().unique_lines(false).unique_columns(false);
+        let options = ParseOptions::new().rules(loose);
+
+        let input = ["0 0 1 1", "1 1 0 0", "0 0 1 1", "1 1 0 0"];
+        assert!(matches!(
+            Grid::parse(input.iter()),
+            Err(ParseError::InvalidGrid(violation)) if violation.rule() == Rule::Uniqueness
+        ));
+        assert!(Grid::parse_with(input.iter(), &options).is_ok());
+
+        // Every complete grid following the other rules, found one by one
+        let valid = (0..1u32 << 16)
+            .filter(|bits| {
+                let cells = (0..4)
+                    .map(|i| {
+                        (0..4)
+                            .map(|j| match bits >> (4 * i + j) & 1 {
+                                0 => Some(Cell::Zero),
+                                _ => Some(Cell::One),
+                            })
+                            .collect()
+                    })
+                    .collect();
+
+                Grid::from_cells_with(cells, loose).is_ok()
+            })
+            .count();
+
+        let mut grid = Grid::parse(["- - - -"; 4].iter()).unwrap();
+        assert!(valid > grid.count_solutions(usize::MAX).unwrap());
+
+        grid.set_rules(loose);
+        assert_eq!(grid.count_solutions(usize::MAX).unwrap(), valid);
+
+        // Three identical cells in a row, and lanes of any balance
+        let free = Rules::new().adjacency(false).balance(false);
+        let input = ["0 0 0 -", "- 1 1 1", "- - - -"];
+        assert!(Grid::parse(input.iter()).is_err());
+
+        let mut grid = Grid::parse_with(input.iter(), &ParseOptions::new().rules(free)).unwrap();
+        grid.set_rules(Rules::new());
+        grid.solve_with(&SolverConfig::new().rules(free)).unwrap();
+        assert_eq!(grid.rules(), free);
+        assert!(grid.is_valid().is_ok());
+    }
+
+    #[test]
+    fn max_run() {
+        let rules = Rules::new().max_run(3);
+        let options = ParseOptions::new().rules(rules.unique_columns(false));
+
+        // Every balanced line of six cells, as no value fills four of them
+        let mut grid = Grid::parse(["- - - - - -"; 2].iter()).unwrap();
+        grid.set_rules(rules.unique_columns(false));
+        assert_eq!(grid.count_solutions(usize::MAX).unwrap(), 20);
+
+        grid.set_rules(Rules::new().unique_columns(false));
+        assert_eq!(grid.count_solutions(usize::MAX).unwrap(), 14);
+
+        let input = ["0 0 0 - - - - -", "- - - - - - - -"];
+        assert!(Grid::parse(input.iter()).is_err());
+
+        let grid = Grid::parse_with(input.iter(), &options).unwrap();
+        let Step::Deduction(deduction) = &grid.explain().unwrap()[0] else {
+            panic!("the cell after the run is deduced");
+        };
+
+        assert_eq!(deduction.index(), Index(0, 3));
+        assert_eq!(deduction.value(), Cell::One);
+        assert_eq!(
+            deduction.premises(),
+            [Index(0, 0), Index(0, 1), Index(0, 2)]
+        );
+    }
+
+    #[test]
+    fn diagonals() {
+        let rules = Rules::new().diagonals(true).diagonal_balance(true);
+        let options = ParseOptions::new().rules(rules);
+
+        // The main diagonal holds three zeros in a row
+        let input = ["0 1 - -", "- 0 - -", "- - 0 -", "- - - -"];
+        assert!(Grid::parse(input.iter()).is_ok());
+        assert!(matches!(
+            Grid::parse_with(input.iter(), &options),
+            Err(ParseError::InvalidGrid(violation)) if violation.rule() == Rule::Adjacency
+        ));
+
+        // Every complete grid following the rules, found one by one
+        let valid = (0..1u32 << 16)
+            .filter(|bits| {
+                let cells = (0..4)
+                    .map(|i| {
+                        (0..4)
+                            .map(|j| match bits >> (4 * i + j) & 1 {
+                                0 => Some(Cell::Zero),
+                                _ => Some(Cell::One),
+                            })
+                            .collect()
+                    })
+                    .collect();
+
+                Grid::from_cells_with(cells, rules).is_ok()
+            })
+            .count();
+
+        let grid = Grid::parse_with(["- - - -"; 4].iter(), &options).unwrap();
+        assert!(
+            valid
+                < Grid::parse(["- - - -"; 4].iter())
+                    .unwrap()
+                    .count_solutions(usize::MAX)
+                    .unwrap()
+        );
+        assert_eq!(grid.count_solutions(usize::MAX).unwrap(), valid);
+
+        assert!(matches!(
+            Grid::parse_with(["- - - -"; 2].iter(), &options),
+            Err(ParseError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn ratio() {
+        let third = Rules::new().ratio(1, 3);
+        let options = ParseOptions::new().rules(third);
+
+        // Each lane holds a single one, like the permutations of three items
+        let grid = Grid::parse_with(["- - -"; 3].iter(), &options).unwrap();
+        assert_eq!(grid.count_solutions(usize::MAX).unwrap(), 6);
+
+        assert!(matches!(
+            Grid::parse_with(["- - - -"; 4].iter(), &options),
+            Err(ParseError::RatioMismatch {
+                width: 4,
+                height: 4
+            })
+        ));
+
+        let options = ParseOptions::new().rules(Rules::new().ratio(2, 5));
+        let mut grid = Grid::parse_with(["- - - - - - - - - -"; 10].iter(), &options).unwrap();
+        grid.solve().unwrap();
+
+        for i in grid.lines() {
+            assert_eq!(
+                grid.line(i)
+                    .filter(|cell| **cell == Some(Cell::One))
+                    .count(),
+                4
+            );
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use std::io;
+use std::io::Read;
+use std::net::ToSocketAddrs;
+use std::sync::Arc;
+use std::thread;
+
+use serde_json::{json, Value};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::error::{ParseError, SolveError};
+use crate::generator::{Generator, GeneratorConfig};
+use crate::grid::Grid;
+use crate::rating::Difficulty;
+use crate::solver::SolverConfig;
+
+// Generating is not bounded by the solver limits, so neither is the size
+const MAX_GENERATED_SIZE: usize = 20;
+
+// Largest body read, far more than the JSON of the largest grid takes
+const MAX_BODY: u64 = 1 << 20;
+
+// Answer requests until the process is stopped, on one worker per core:
+// requests coming while all of them are busy wait for the next free one
+pub fn serve<A>(addr: A, config: SolverConfig) -> io::Result<()>
+where
+    A: ToSocketAddrs,
+{
+    let server = Arc::new(Server::http(addr).map_err(io::Error::other)?);
+    let config = Arc::new(config);
+    let workers = thread::available_parallelism().map_or(1, usize::from);
+
+    let workers: Vec<_> = (0..workers)
+        .map(|_| {
+            let (server, config) = (Arc::clone(&server), Arc::clone(&config));
+            thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    reply(request, &config);
+                }
+            })
+        })
+        .collect();
+
+    for worker in workers {
+        worker
+            .join()
+            .map_err(|_| io::Error::other("a worker panicked"))?;
+    }
+
+    Ok(())
+}
+
+fn reply(mut request: Request, config: &SolverConfig) {
+    let mut body = String::new();
+
+    let mut reader = request.as_reader().take(MAX_BODY + 1);
+
+    let (status, value) = match reader.read_to_string(&mut body) {
+        Ok(read) if read as u64 > MAX_BODY => (413, error("the body is too large")),
+        Ok(_) => route(request.method(), request.url(), &body, config),
+        Err(_) => (400, error("the body is not valid UTF-8")),
+    };
+
+    let header = Header::from_bytes("Content-Type", "application/json")
+        .expect("the content type header is valid");
+    let response = Response::from_string(value.to_string())
+        .with_status_code(status)
+        .with_header(header);
+
+    // The client may be gone already, there is no one left to tell
+    let _ = request.respond(response);
+}
+
+// The status code and JSON body answering a request
+fn route(method: &Method, url: &str, body: &str, config: &SolverConfig) -> (u16, Value) {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+
+    match (method, path) {
+        (Method::Post, "/solve") => solve(body, config),
+        (Method::Post, "/rate") => rate(body, config),
+        (Method::Get, "/generate") => generate(query),
+        (_, "/solve" | "/rate" | "/generate") => (405, error("method not allowed")),
+        _ => (404, error("not found")),
+    }
+}
+
+fn solve(body: &str, config: &SolverConfig) -> (u16, Value) {
+    let mut grid = match parse(body) {
+        Ok(grid) => grid,
+        Err(reply) => return reply,
+    };
+
+    match grid.solve_with_stats(config) {
+        Ok(stats) => (
+            200,
+            json!({
+                "solution": to_value(&grid),
+                "guesses": stats.guesses(),
+                "backtracks": stats.backtracks(),
+            }),
+        ),
+        Err(err) => solve_error(err),
+    }
+}
+
+fn rate(body: &str, config: &SolverConfig) -> (u16, Value) {
+    let grid = match parse(body) {
+        Ok(grid) => grid,
+        Err(reply) => return reply,
+    };
+
+    match grid.rate_report_with(config) {
+        Ok(rating) => {
+            let techniques: Vec<_> = rating.techniques().iter().map(|t| t.to_string()).collect();
+
+            (
+                200,
+                json!({
+                    "difficulty": rating.difficulty(),
+                    "score": rating.score(),
+                    "guesses": rating.guesses(),
+                    "guessing": rating.requires_guessing(),
+                    "unique": rating.is_unique(),
+                    "techniques": techniques,
+                }),
+            )
+        }
+        Err(err) => solve_error(err),
+    }
+}
+
+fn generate(query: &str) -> (u16, Value) {
+    let mut size = (6, 6);
+    let mut config = GeneratorConfig::new();
+    let mut seed = None;
+
+    for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+        match key {
+            "size" => {
+                // A single number stands for a square grid
+                let (width, height) = value.split_once('x').unwrap_or((value, value));
+
+                match (width.parse(), height.parse()) {
+                    (Ok(width), Ok(height)) => size = (width, height),
+                    _ => return (400, error(format!("invalid size '{}'", value))),
+                }
+            }
+            "difficulty" => match Difficulty::iter().find(|d| d.to_string() == value) {
+                Some(difficulty) => config = config.difficulty(difficulty),
+                None => return (400, error(format!("unknown difficulty '{}'", value))),
+            },
+            "seed" => match value.parse() {
+                Ok(value) => seed = Some(value),
+                Err(_) => return (400, error(format!("invalid seed '{}'", value))),
+            },
+            _ => return (400, error(format!("unknown parameter '{}'", key))),
+        }
+    }
+
+    let (width, height) = size;
+
+    if width.max(height) > MAX_GENERATED_SIZE {
+        return (400, error(format!("invalid size {}x{}", width, height)));
+    }
+
+    let mut generator = match Generator::new(width, height) {
+        Ok(generator) => generator.config(config),
+        Err(err) => return (400, error(err)),
+    };
+
+    if let Some(seed) = seed {
+        generator = generator.seed(seed);
+    }
+
+    (200, json!({ "puzzle": to_value(&generator.generate()) }))
+}
+
+// Grids breaking a rule are well-formed requests, that cannot be solved
+fn parse(body: &str) -> Result<Grid, (u16, Value)> {
+    Grid::from_json(body).map_err(|err| match err {
+        ParseError::InvalidGrid(_) => (422, error(err)),
+        _ => (400, error(err)),
+    })
+}
+
+fn solve_error(err: SolveError) -> (u16, Value) {
+    let status = match err {
+        SolveError::LimitExceeded(_) => 503,
+        SolveError::InvalidGrid(_)
+        | SolveError::NoSolution(_)
+        | SolveError::MultipleSolutions(_) => 422,
+    };
+
+    (status, error(err))
+}
+
+// Messages drop their prefix, the JSON key already says what they are
+fn error(message: impl ToString) -> Value {
+    let message = message.to_string();
+    json!({ "error": message.trim_start_matches("error: ") })
+}
+
+fn to_value(grid: &Grid) -> Value {
+    serde_json::from_str(&grid.to_json()).expect("grids are valid JSON")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes() {
+        let config = SolverConfig::new();
+        let puzzle = r#"{"width":4,"height":4,"cells":[[1,1,null,0],[null,0,null,null],[null,null,0,null],[null,1,null,0]]}"#;
+
+        let (status, value) = route(&Method::Post, "/solve", puzzle, &config);
+        assert_eq!(status, 200);
+        assert_eq!(value["solution"]["cells"][0], json!([1, 1, 0, 0]));
+
+        let (status, value) = route(&Method::Post, "/rate", puzzle, &config);
+        assert_eq!(status, 200);
+        assert_eq!(value["unique"], json!(true));
+
+        // Rating is bounded by the limits of the server
+        let empty = json!({"width": 10, "height": 10, "cells": vec![vec![Value::Null; 10]; 10]});
+        let limited = SolverConfig::new().max_nodes(1);
+        let (status, _) = route(&Method::Post, "/rate", &empty.to_string(), &limited);
+        assert_eq!(status, 503);
+
+        let (status, value) = route(&Method::Get, "/generate?size=6x4&seed=1", "", &config);
+        assert_eq!(status, 200);
+        assert_eq!(value["puzzle"]["width"], json!(6));
+        assert_eq!(value["puzzle"]["seed"], json!(1));
+
+        let invalid = r#"{"width":2,"height":2,"cells":[[1,1],[null,null]]}"#;
+        assert_eq!(route(&Method::Post, "/solve", invalid, &config).0, 422);
+        assert_eq!(route(&Method::Post, "/solve", "{}", &config).0, 400);
+
+        let huge = r#"{"width":100000,"height":100000,"cells":[]}"#;
+        assert_eq!(route(&Method::Post, "/solve", huge, &config).0, 400);
+        assert_eq!(route(&Method::Get, "/generate?size=3", "", &config).0, 400);
+        assert_eq!(route(&Method::Get, "/solve", "", &config).0, 405);
+        assert_eq!(route(&Method::Get, "/", "", &config).0, 404);
+    }
+}
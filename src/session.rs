@@ -0,0 +1,259 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cell::{Cell, CellSet, CellState};
+use crate::error::ParseError;
+use crate::grid::{Grid, GridCell};
+use crate::index::Index;
+use crate::journal::Edit;
+
+// A puzzle being played by hand, which can be saved and carried on later,
+// such as on another machine
+#[derive(Clone, Debug)]
+pub struct Session {
+    puzzle: Grid,
+    grid: Grid,
+    elapsed: Duration,
+}
+
+// The puzzle as given, then what the player added to it; cells are (line,
+// column) pairs and values are 0 or 1, like in snapshots
+#[derive(Serialize, Deserialize)]
+struct RawSession {
+    puzzle: serde_json::Value,
+    entries: Vec<RawEntry>,
+    marks: Vec<RawMarks>,
+    elapsed_ms: u64,
+    history: Vec<RawEdit>,
+    undone: Vec<RawEdit>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawEntry {
+    cell: (usize, usize),
+    value: u8,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawMarks {
+    cell: (usize, usize),
+    values: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum RawEdit {
+    Cell {
+        cell: (usize, usize),
+        old: Option<u8>,
+        new: Option<u8>,
+    },
+    Marks {
+        cell: (usize, usize),
+        old: Option<Vec<u8>>,
+        new: Option<Vec<u8>>,
+    },
+}
+
+impl Session {
+    pub fn new(puzzle: Grid) -> Self {
+        // Every move of the player can be taken back
+        let mut grid = puzzle.clone();
+        grid.start_journal();
+
+        Self {
+            puzzle,
+            grid,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    pub fn puzzle(&self) -> &Grid {
+        &self.puzzle
+    }
+
+    // The puzzle with the entries and pencil marks of the player
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    // Moves are played on the grid, with set_cell, toggle_mark or undo
+    pub fn grid_mut(&mut self) -> &mut Grid {
+        &mut self.grid
+    }
+
+    // Time spent playing, over every sitting
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    pub fn set_elapsed(&mut self, elapsed: Duration) {
+        self.elapsed = elapsed;
+    }
+
+    pub fn is_solved(&self) -> bool {
+        self.puzzle.verify_solution(&self.grid).is_ok()
+    }
+
+    pub fn to_json(&self) -> String {
+        let value = |cell: Cell| (cell == Cell::One) as u8;
+        let values = |marks: CellSet| marks.iter().map(value).collect();
+        let (history, undone) = self
+            .grid
+            .journal
+            .as_ref()
+            .map(|journal| (&journal.done[..], &journal.undone[..]))
+            .unwrap_or_default();
+
+        let raw_edit = |edit: &Edit| match *edit {
+            Edit::Cell(idx, old, new) => RawEdit::Cell {
+                cell: (idx.0, idx.1),
+                old: old.map(value),
+                new: new.map(value),
+            },
+            Edit::Marks(idx, old, new) => RawEdit::Marks {
+                cell: (idx.0, idx.1),
+                old: old.map(values),
+                new: new.map(values),
+            },
+        };
+
+        let raw = RawSession {
+            puzzle: serde_json::from_str(&self.puzzle.to_json()).unwrap_or_default(),
+            entries: self
+                .grid
+                .lines()
+                .flat_map(|i| self.grid.columns().map(move |j| Index(i, j)))
+                .filter(|idx| self.grid.state(*idx) == Some(CellState::Derived))
+                .filter_map(|idx| {
+                    self.grid[idx].map(|cell| RawEntry {
+                        cell: (idx.0, idx.1),
+                        value: value(cell),
+                    })
+                })
+                .collect(),
+            marks: self
+                .grid
+                .marked_cells()
+                .map(|(idx, marks)| RawMarks {
+                    cell: (idx.0, idx.1),
+                    values: values(marks),
+                })
+                .collect(),
+            elapsed_ms: self.elapsed.as_millis() as u64,
+            history: history.iter().map(raw_edit).collect(),
+            undone: undone.iter().map(raw_edit).collect(),
+        };
+
+        serde_json::to_string(&raw).unwrap_or_default()
+    }
+
+    pub fn from_json(s: &str) -> Result<Self, ParseError> {
+        let raw: RawSession = serde_json::from_str(s).map_err(|_| ParseError::InvalidEncoding)?;
+        let puzzle = Grid::from_json(&raw.puzzle.to_string())?;
+
+        // The player only ever changes the empty cells of the puzzle
+        let index = |(i, j): (usize, usize)| {
+            let idx = Index(i, j);
+
+            (i < puzzle.height() && j < puzzle.width())
+                .then_some(idx)
+                .filter(|idx| puzzle[*idx].is_none() && !puzzle.is_hole(*idx))
+                .ok_or(ParseError::InvalidEncoding)
+        };
+        let cell = |value| match value {
+            0 => Ok(Cell::Zero),
+            1 => Ok(Cell::One),
+            _ => Err(ParseError::InvalidEncoding),
+        };
+        let grid_cell =
+            |value: Option<u8>| -> Result<GridCell, ParseError> { value.map(cell).transpose() };
+        let marks = |values: Option<Vec<u8>>| -> Result<Option<CellSet>, ParseError> {
+            values
+                .map(|values| values.into_iter().map(cell).collect())
+                .transpose()
+        };
+        let edit = |edit: RawEdit| match edit {
+            RawEdit::Cell { cell, old, new } => {
+                Ok(Edit::Cell(index(cell)?, grid_cell(old)?, grid_cell(new)?))
+            }
+            RawEdit::Marks { cell, old, new } => {
+                Ok(Edit::Marks(index(cell)?, marks(old)?, marks(new)?))
+            }
+        };
+
+        let mut session = Self::new(puzzle.clone());
+        let grid = &mut session.grid;
+
+        for entry in raw.entries {
+            grid.set(index(entry.cell)?, Some(cell(entry.value)?));
+        }
+
+        for entry in raw.marks {
+            let idx = (entry.cell.0 < puzzle.height() && entry.cell.1 < puzzle.width())
+                .then_some(Index(entry.cell.0, entry.cell.1))
+                .ok_or(ParseError::InvalidEncoding)?;
+
+            grid.marks
+                .insert(idx, marks(Some(entry.values))?.unwrap_or_default());
+        }
+
+        if let Some(journal) = &mut grid.journal {
+            journal.done = raw
+                .history
+                .into_iter()
+                .map(edit)
+                .collect::<Result<_, _>>()?;
+            journal.undone = raw.undone.into_iter().map(edit).collect::<Result<_, _>>()?;
+        }
+
+        session.elapsed = Duration::from_millis(raw.elapsed_ms);
+        Ok(session)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn save_and_load() {
+        let puzzle = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+        let mut session = Session::new(puzzle.unwrap());
+
+        let grid = session.grid_mut();
+        grid.set_cell((0, 2), Some(Cell::Zero));
+        grid.toggle_mark((1, 0), Cell::One);
+        grid.set_cell((1, 2), Some(Cell::One));
+        grid.undo();
+        session.set_elapsed(Duration::from_secs(90));
+
+        let loaded = Session::from_json(&session.to_json()).unwrap();
+        assert_eq!(loaded.puzzle(), session.puzzle());
+        assert_eq!(loaded.grid(), session.grid());
+        assert_eq!(loaded.grid().state((0, 2)), Some(CellState::Derived));
+        assert_eq!(loaded.grid().marks((1, 0)), session.grid().marks((1, 0)));
+        assert_eq!(loaded.elapsed(), Duration::from_secs(90));
+        assert!(!loaded.is_solved());
+
+        // The undo history goes on where it was left
+        let mut loaded = loaded;
+        assert!(loaded.grid_mut().redo());
+        assert_eq!(loaded.grid()[(1, 2)], Some(Cell::One));
+        assert!(loaded.grid_mut().undo());
+        assert!(loaded.grid_mut().undo());
+        assert!(loaded.grid_mut().undo());
+        assert_eq!(loaded.grid(), loaded.puzzle());
+        assert!(loaded.grid().marks((1, 0)).is_none());
+
+        // Entries never replace the givens of the puzzle
+        let json = session
+            .to_json()
+            .replace("\"cell\":[0,2]", "\"cell\":[0,0]");
+        assert!(matches!(
+            Session::from_json(&json),
+            Err(ParseError::InvalidEncoding)
+        ));
+    }
+}
@@ -0,0 +1,85 @@
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cell::Cell;
+
+// Marks between two adjacent cells of Binairo+ puzzles
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Sign {
+    Equal,
+    Different,
+}
+
+impl Sign {
+    pub(crate) fn from_char(c: char) -> Option<Sign> {
+        match c {
+            '=' => Some(Self::Equal),
+            'x' | '×' => Some(Self::Different),
+            _ => None,
+        }
+    }
+
+    // The only value the other cell can take
+    pub fn forced(&self, cell: Cell) -> Cell {
+        match self {
+            Self::Equal => cell,
+            Self::Different => !cell,
+        }
+    }
+}
+
+impl fmt::Display for Sign {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        // Padded, so signs can stand in for separators of any width
+        match self {
+            Self::Equal => fmt.pad("="),
+            Self::Different => fmt.pad("x"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::Grid;
+
+    #[test]
+    fn binairo_plus() {
+        let input = vec![
+            "- - - - 1 -",
+            "  =",
+            "- 0 - - - -",
+            "          x",
+            "- -x- - - -",
+            "- - - 1 - -",
+            "=",
+            "- - - - -=-",
+            "- 1 - - - -",
+        ];
+        let grid = Grid::parse(input.iter()).unwrap();
+
+        assert_eq!(grid.sign((1, 2), (2, 2)), None);
+        assert_eq!(grid.sign((2, 2), (2, 1)), Some(Sign::Different));
+        assert_eq!(grid.to_string(), input.join("\n"));
+        assert_eq!(Grid::from_json(&grid.to_json()).unwrap(), grid);
+        assert!(grid.has_unique_solution());
+
+        let mut solution = grid.clone();
+        solution.solve().unwrap();
+        assert!(grid.verify_solution(&solution).is_ok());
+        assert_eq!(solution[(3, 0)], solution[(4, 0)]);
+
+        // Signs must sit between two cells
+        assert!(Grid::parse(vec!["1 0x", "0 1"].into_iter()).is_err());
+        assert!(Grid::parse(vec!["1 0", " =", "0 1"].into_iter()).is_err());
+
+        // Cells breaking a sign are given with it
+        let err = Grid::parse(vec!["1=0", "0 1"].into_iter()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "error: grid is invalid: line 1, column 1: two cells disagree with the sign between them"
+        );
+    }
+}
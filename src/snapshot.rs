@@ -0,0 +1,293 @@
+use serde::{Deserialize, Serialize};
+
+use crate::cell::{Cell, CellSet};
+use crate::error::{Limit, ParseError, SolveError};
+use crate::grid::Grid;
+use crate::index::{Index, Lane};
+use crate::solver::{Change, Frame, SearchState, SolveStats, Solver, SolverConfig};
+
+// A search stopped by a time or node limit, which can be saved and
+// carried on later, such as after a restart
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    grid: Grid,
+    state: SearchState,
+}
+
+#[derive(Clone, Debug)]
+pub enum Progress {
+    Solved(SolveStats),
+    // The limit that stopped the search, and where it was
+    Paused(Box<Snapshot>, Limit),
+}
+
+// Cells are (line, column) pairs and values are 0 or 1, like in replays
+#[derive(Serialize, Deserialize)]
+struct RawSnapshot {
+    grid: serde_json::Value,
+    trail: Vec<RawChange>,
+    frames: Vec<RawFrame>,
+    worklist: Vec<RawLane>,
+    paused: Option<RawGuess>,
+    stats: SolveStats,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    drawn: Option<u128>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+enum RawChange {
+    Fill { cell: (usize, usize) },
+    Eliminate { cell: (usize, usize), value: u8 },
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawFrame {
+    cell: (usize, usize),
+    untried: Vec<u8>,
+    mark: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum RawLane {
+    Line(usize),
+    Column(usize),
+}
+
+#[derive(Serialize, Deserialize)]
+struct RawGuess {
+    cell: (usize, usize),
+    value: u8,
+}
+
+impl Snapshot {
+    // The grid as the search left it
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    // Work done until the search was stopped
+    pub fn stats(&self) -> &SolveStats {
+        &self.state.stats
+    }
+
+    pub fn resume(&self, config: &SolverConfig) -> Result<(Grid, Progress), SolveError> {
+        let mut grid = self.grid.clone();
+        let mut solver = Solver::restore(&mut grid, config, self.state.clone());
+        let result = solver.resume();
+        let state = solver.into_state();
+        let progress = progress(&grid, result, state)?;
+
+        Ok((grid, progress))
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let value = |cell| (cell == Cell::One) as u8;
+        let state = &self.state;
+
+        let raw = RawSnapshot {
+            grid: serde_json::from_str(&self.grid.to_json()).unwrap_or_default(),
+            trail: state
+                .trail
+                .iter()
+                .map(|change| match *change {
+                    Change::Fill(idx) => RawChange::Fill {
+                        cell: (idx.0, idx.1),
+                    },
+                    Change::Eliminate(idx, cell) => RawChange::Eliminate {
+                        cell: (idx.0, idx.1),
+                        value: value(cell),
+                    },
+                })
+                .collect(),
+            frames: state
+                .frames
+                .iter()
+                .map(|frame| RawFrame {
+                    cell: (frame.idx.0, frame.idx.1),
+                    untried: frame.untried.iter().map(value).collect(),
+                    mark: frame.mark,
+                })
+                .collect(),
+            worklist: state
+                .worklist
+                .iter()
+                .map(|lane| match *lane {
+                    Lane::Line(i) => RawLane::Line(i),
+                    Lane::Column(j) => RawLane::Column(j),
+                })
+                .collect(),
+            paused: state.paused.map(|(idx, cell)| RawGuess {
+                cell: (idx.0, idx.1),
+                value: value(cell),
+            }),
+            stats: state.stats.clone(),
+            drawn: state.drawn,
+        };
+
+        serde_json::to_vec(&raw).unwrap_or_default()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, ParseError> {
+        let raw: RawSnapshot =
+            serde_json::from_slice(bytes).map_err(|_| ParseError::InvalidEncoding)?;
+        let mut grid = Grid::from_json(&raw.grid.to_string())?;
+
+        // Everything must fit the grid, as the search relies on it
+        let index = |(i, j): (usize, usize)| {
+            (i < grid.height() && j < grid.width())
+                .then_some(Index(i, j))
+                .ok_or(ParseError::InvalidEncoding)
+        };
+        let cell = |value| match value {
+            0 => Ok(Cell::Zero),
+            1 => Ok(Cell::One),
+            _ => Err(ParseError::InvalidEncoding),
+        };
+
+        let trail = raw
+            .trail
+            .into_iter()
+            .map(|change| match change {
+                RawChange::Fill { cell } => index(cell).map(Change::Fill),
+                RawChange::Eliminate { cell: idx, value } => {
+                    Ok(Change::Eliminate(index(idx)?, cell(value)?))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let frames = raw
+            .frames
+            .into_iter()
+            .map(|frame| {
+                let mut untried = CellSet::all();
+
+                for value in [0, 1] {
+                    if !frame.untried.contains(&value) {
+                        untried.remove(cell(value)?);
+                    }
+                }
+
+                (frame.mark <= trail.len())
+                    .then_some(())
+                    .ok_or(ParseError::InvalidEncoding)?;
+
+                Ok(Frame {
+                    idx: index(frame.cell)?,
+                    untried,
+                    mark: frame.mark,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let worklist = raw
+            .worklist
+            .into_iter()
+            .map(|lane| match lane {
+                RawLane::Line(i) => index((i, 0)).map(|_| Lane::Line(i)),
+                RawLane::Column(j) => index((0, j)).map(|_| Lane::Column(j)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let paused = raw
+            .paused
+            .map(|guess| Ok((index(guess.cell)?, cell(guess.value)?)))
+            .transpose()?;
+
+        // Cells filled by the search are not clues of the puzzle
+        for change in &trail {
+            if let Change::Fill(idx) = *change {
+                let value = grid[idx].ok_or(ParseError::InvalidEncoding)?;
+                grid.set_clue(idx, None);
+                grid.set(idx, Some(value));
+            }
+        }
+
+        let state = SearchState {
+            trail,
+            frames,
+            worklist,
+            paused,
+            stats: raw.stats,
+            drawn: raw.drawn,
+        };
+
+        Ok(Self { grid, state })
+    }
+}
+
+impl Grid {
+    // Solve like solve_with_stats, but keep where the search was when a
+    // time or node limit stops it, so it can be resumed
+    pub fn solve_pausable(&mut self, config: &SolverConfig) -> Result<Progress, SolveError> {
+        if let Some(rules) = config.grid_rules() {
+            self.set_rules(rules);
+        }
+
+        self.is_valid().map_err(SolveError::InvalidGrid)?;
+
+        let mut solver = Solver::new(self, config).pausable();
+        let result = solver.resume();
+        let state = solver.into_state();
+
+        progress(self, result, state)
+    }
+}
+
+fn progress(
+    grid: &Grid,
+    result: Result<(), SolveError>,
+    state: SearchState,
+) -> Result<Progress, SolveError> {
+    match result {
+        Ok(()) => Ok(Progress::Solved(state.stats)),
+        Err(SolveError::LimitExceeded(limit)) if state.paused.is_some() => {
+            let snapshot = Snapshot {
+                grid: grid.clone(),
+                state,
+            };
+
+            Ok(Progress::Paused(Box::new(snapshot), limit))
+        }
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pauses() {
+        let puzzle = Grid::parse(vec!["- - - - - - - - - -"; 10].into_iter()).unwrap();
+        let config = SolverConfig::new().max_nodes(3);
+
+        let mut grid = puzzle.clone();
+        let Progress::Paused(mut snapshot, Limit::Nodes(3)) = grid.solve_pausable(&config).unwrap()
+        else {
+            panic!("the search should run out of nodes");
+        };
+        assert_eq!(snapshot.grid(), &grid);
+        assert_eq!(snapshot.stats().guesses(), 3);
+
+        // Snapshots go through bytes unchanged, until the search is over
+        let solved = loop {
+            let bytes = snapshot.to_bytes();
+            assert_eq!(Snapshot::from_bytes(&bytes).unwrap(), *snapshot);
+
+            match snapshot.resume(&config).unwrap() {
+                (_, Progress::Paused(next, _)) => snapshot = next,
+                (grid, Progress::Solved(stats)) => {
+                    assert!(stats.guesses() > 3);
+                    break grid;
+                }
+            }
+        };
+
+        assert!(puzzle.verify_solution(&solved).is_ok());
+        assert_eq!(solved.givens().count(), 0);
+
+        assert!(Snapshot::from_bytes(b"{}").is_err());
+    }
+}
@@ -0,0 +1,2509 @@
+use alloc::boxed::Box;
+use alloc::collections::{BTreeMap, BTreeSet, VecDeque};
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::mem;
+#[cfg(feature = "parallel")]
+use core::sync::atomic::AtomicBool;
+use core::sync::atomic::{AtomicUsize, Ordering};
+#[cfg(feature = "std")]
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::board::Board;
+use crate::cell::{Cell, CellSet};
+use crate::deduction::{Deduction, Step};
+use crate::error::{DeadEnd, Limit, Rule, SolveError};
+use crate::grid::{Grid, GridCell, Histogram};
+use crate::index::{Index, Lane};
+use crate::random::Rng;
+use crate::rating::Level;
+use crate::rules::Rules;
+use crate::technique::Technique;
+
+// Most empty cells of a lane whose completions are all tried
+const MAX_COMPLETED_CELLS: usize = 8;
+
+// Most completions of a lane tried when only its counts are known
+const MAX_COMPLETIONS: usize = 512;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GuessStrategy {
+    FirstEmpty,
+    #[default]
+    MostConstrained,
+}
+
+#[derive(Clone, Debug)]
+pub struct SolverConfig {
+    guess: GuessStrategy,
+    max_depth: Option<usize>,
+    max_nodes: Option<usize>,
+    // Values tried in a row before ruling one out, without guessing
+    lookahead: usize,
+    // Settle for the first solution in reading order, with zeros first
+    smallest: bool,
+    // Fail when a second solution is found, instead of giving the first one
+    unique: bool,
+    // Time limits need a clock, only found in std
+    #[cfg(feature = "std")]
+    max_time: Option<Duration>,
+    // Deductions made between guesses
+    techniques: BTreeSet<Technique>,
+    seed: Option<u64>,
+    // Rules of a variant, given to grids before solving them
+    rules: Option<Rules>,
+    #[cfg(feature = "parallel")]
+    parallel_depth: usize,
+}
+
+impl SolverConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn guess_strategy(mut self, guess: GuessStrategy) -> Self {
+        self.guess = guess;
+        self
+    }
+
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    pub fn max_nodes(mut self, nodes: usize) -> Self {
+        self.max_nodes = Some(nodes);
+        self
+    }
+
+    // Give the solution that comes first in reading order, zeros before
+    // ones, when there are several: guesses go through the cells in order,
+    // on one thread, and the uniqueness of the solution is not assumed
+    pub fn smallest_solution(mut self, smallest: bool) -> Self {
+        self.smallest = smallest;
+        self
+    }
+
+    // Make sure the solution is the only one, and report grids with several
+    // as an error: the search goes on after the first solution, on one thread
+    pub fn require_unique(mut self, unique: bool) -> Self {
+        self.unique = unique;
+        self
+    }
+
+    // Rule out values leading to a contradiction within this many trials
+    pub fn lookahead(mut self, depth: usize) -> Self {
+        self.lookahead = depth;
+        self
+    }
+
+    #[cfg(feature = "std")]
+    pub fn max_time(mut self, time: Duration) -> Self {
+        self.max_time = Some(time);
+        self
+    }
+
+    // Also deduce what a second solution would otherwise follow from, which
+    // is only right for puzzles with a single solution
+    pub fn assume_unique(mut self, unique: bool) -> Self {
+        if unique {
+            self.techniques.insert(Technique::DeadlyPattern);
+        } else {
+            self.techniques.remove(&Technique::DeadlyPattern);
+        }
+
+        self
+    }
+
+    pub fn heuristics(mut self, heuristics: bool) -> Self {
+        // Crossing lanes are matched against their completions too
+        for technique in [Technique::Completion, Technique::Crossing] {
+            if heuristics {
+                self.techniques.insert(technique);
+            } else {
+                self.techniques.remove(&technique);
+            }
+        }
+
+        self
+    }
+
+    // Deduce like a solver of this level, giving up past its lookahead
+    pub fn level(self, level: Level) -> Self {
+        self.techniques(level.techniques())
+            .max_depth(level.lookahead())
+    }
+
+    pub fn techniques<I>(mut self, techniques: I) -> Self
+    where
+        I: IntoIterator<Item = Technique>,
+    {
+        self.techniques = techniques.into_iter().collect();
+        self
+    }
+
+    pub(crate) fn allows(&self, technique: Technique) -> bool {
+        self.techniques.contains(&technique)
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn rules(mut self, rules: Rules) -> Self {
+        self.rules = Some(rules);
+        self
+    }
+
+    pub(crate) fn grid_rules(&self) -> Option<Rules> {
+        self.rules
+    }
+
+    // The node and time limits of another configuration, keeping the rest
+    pub(crate) fn limited_by(mut self, other: &SolverConfig) -> Self {
+        self.max_nodes = other.max_nodes;
+        #[cfg(feature = "std")]
+        {
+            self.max_time = other.max_time;
+        }
+        self
+    }
+
+    #[cfg(feature = "parallel")]
+    pub fn parallel_depth(mut self, depth: usize) -> Self {
+        self.parallel_depth = depth;
+        self
+    }
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        Self {
+            guess: GuessStrategy::default(),
+            max_depth: None,
+            max_nodes: None,
+            lookahead: 0,
+            smallest: false,
+            unique: false,
+            #[cfg(feature = "std")]
+            max_time: None,
+            techniques: Technique::iter()
+                .filter(|technique| !technique.assumes_uniqueness())
+                .collect(),
+            seed: None,
+            rules: None,
+            #[cfg(feature = "parallel")]
+            parallel_depth: 4,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SolveStats {
+    filled: BTreeMap<Technique, usize>,
+    guesses: usize,
+    propagations: usize,
+    backtracks: usize,
+    max_depth: usize,
+}
+
+impl SolveStats {
+    pub fn filled(&self, technique: Technique) -> usize {
+        self.filled.get(&technique).copied().unwrap_or(0)
+    }
+
+    pub fn techniques(&self) -> impl Iterator<Item = Technique> + '_ {
+        self.filled.keys().copied()
+    }
+
+    pub fn guesses(&self) -> usize {
+        self.guesses
+    }
+
+    pub fn propagations(&self) -> usize {
+        self.propagations
+    }
+
+    pub fn backtracks(&self) -> usize {
+        self.backtracks
+    }
+
+    pub fn max_depth(&self) -> usize {
+        self.max_depth
+    }
+
+    // Whether a cell was filled assuming the puzzle has a single solution
+    pub fn assumes_uniqueness(&self) -> bool {
+        self.techniques()
+            .any(|technique| technique.assumes_uniqueness())
+    }
+
+    // Add up the work of several searches
+    pub fn merge(&mut self, other: &SolveStats) {
+        for (technique, count) in &other.filled {
+            *self.filled.entry(*technique).or_default() += count;
+        }
+
+        self.guesses += other.guesses;
+        self.propagations += other.propagations;
+        self.backtracks += other.backtracks;
+        self.max_depth = self.max_depth.max(other.max_depth);
+    }
+}
+
+// The limits of a configuration and the statistics of a search, for grids
+// searched outside of the solver
+pub(crate) struct Budget<'a> {
+    config: &'a SolverConfig,
+    nodes: usize,
+    #[cfg(feature = "std")]
+    deadline: Option<Instant>,
+    stats: SolveStats,
+}
+
+impl<'a> Budget<'a> {
+    pub(crate) fn new(config: &'a SolverConfig) -> Self {
+        Self {
+            config,
+            nodes: 0,
+            #[cfg(feature = "std")]
+            deadline: config.max_time.map(|time| Instant::now() + time),
+            stats: SolveStats::default(),
+        }
+    }
+
+    // Count a guess made with this many others before it
+    pub(crate) fn guess(&mut self, depth: usize) -> Result<(), SolveError> {
+        if let Some(limit) = self.config.max_depth.filter(|limit| depth >= *limit) {
+            return Err(SolveError::LimitExceeded(Limit::Depth(limit)));
+        }
+
+        self.nodes += 1;
+
+        if let Some(limit) = self.config.max_nodes.filter(|limit| self.nodes > *limit) {
+            return Err(SolveError::LimitExceeded(Limit::Nodes(limit)));
+        }
+
+        #[cfg(feature = "std")]
+        if let (Some(limit), Some(deadline)) = (self.config.max_time, self.deadline) {
+            if Instant::now() > deadline {
+                return Err(SolveError::LimitExceeded(Limit::Time(limit)));
+            }
+        }
+
+        self.stats.guesses += 1;
+        self.stats.max_depth = self.stats.max_depth.max(depth + 1);
+        Ok(())
+    }
+
+    pub(crate) fn backtrack(&mut self) {
+        self.stats.backtracks += 1;
+    }
+
+    pub(crate) fn propagate(&mut self) {
+        self.stats.propagations += 1;
+    }
+
+    pub(crate) fn into_stats(self) -> SolveStats {
+        self.stats
+    }
+}
+
+// Check the whole grid once, moves are then checked individually
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(
+        name = "solve",
+        level = "debug",
+        skip_all,
+        fields(width = board.width(), height = board.height())
+    )
+)]
+pub(crate) fn solve<B>(board: &mut B, config: &SolverConfig) -> Result<SolveStats, SolveError>
+where
+    B: Board,
+{
+    board.is_valid().map_err(SolveError::InvalidGrid)?;
+
+    let mut solver = Solver::new(board, config);
+    solver.search()?;
+
+    // The grid is given back its first solution once no other one is found
+    let first = config.unique.then(|| solver.grid.clone());
+
+    if let Some(first) = &first {
+        if solver.another()? {
+            let solutions = [first.to_grid(), solver.grid.to_grid()];
+            return Err(SolveError::MultipleSolutions(Box::new(solutions)));
+        }
+    }
+
+    let stats = solver.into_stats();
+
+    if let Some(first) = first {
+        *board = first;
+    }
+
+    #[cfg(feature = "tracing")]
+    for technique in stats.techniques() {
+        tracing::debug!(%technique, filled = stats.filled(technique), "cells filled");
+    }
+
+    Ok(stats)
+}
+
+pub fn solve_all<I>(grids: I) -> Vec<Result<Grid, SolveError>>
+where
+    I: IntoIterator<Item = Grid>,
+{
+    solve_all_with(grids, &SolverConfig::default())
+}
+
+pub fn solve_all_with<I>(grids: I, config: &SolverConfig) -> Vec<Result<Grid, SolveError>>
+where
+    I: IntoIterator<Item = Grid>,
+{
+    let solve = |mut grid: Grid| grid.solve_with(config).map(|_| grid);
+
+    // Each puzzle is solved on its own thread, results keep the input order
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+
+        let grids: Vec<_> = grids.into_iter().collect();
+        grids.into_par_iter().map(solve).collect()
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    grids.into_iter().map(solve).collect()
+}
+
+// Changes made while searching, undone on backtrack
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Change {
+    Fill(Index),
+    Eliminate(Index, Cell),
+}
+
+// A guess being explored, with the values left to try
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct Frame {
+    pub(crate) idx: Index,
+    pub(crate) untried: CellSet,
+    pub(crate) mark: usize,
+}
+
+// Where a search stopped by a limit was, apart from its grid
+#[cfg(feature = "std")]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct SearchState {
+    pub(crate) trail: Vec<Change>,
+    pub(crate) frames: Vec<Frame>,
+    pub(crate) worklist: Vec<Lane>,
+    pub(crate) paused: Option<(Index, Cell)>,
+    pub(crate) stats: SolveStats,
+    // Words drawn from the generator of a seeded search
+    pub(crate) drawn: Option<u128>,
+}
+
+// Cell left without any value, with the lane ruling out its last one when
+// there is one
+type Stuck = (Index, Option<Lane>, Rule);
+
+// The contradiction reached with the most cells filled, and these cells
+struct Furthest {
+    filled: usize,
+    cells: Vec<(Index, Cell)>,
+    stuck: Stuck,
+}
+
+// Buffers reused by every heuristic pass
+#[derive(Default)]
+struct Scratch {
+    lane: Vec<GridCell>,
+    empties: Vec<usize>,
+    possible: Vec<CellSet>,
+}
+
+pub(crate) struct Solver<'a, B = Grid> {
+    grid: &'a mut B,
+    config: &'a SolverConfig,
+    candidates: Vec<CellSet>,
+    // Cells filled before solving, which no other solution can change
+    fixed: Vec<bool>,
+    trail: Vec<Change>,
+    // Lanes whose cells must be checked again after a change
+    worklist: VecDeque<Lane>,
+    queued: Vec<bool>,
+    frames: Vec<Frame>,
+    // Guess a limit kept the search from making, made first when resuming
+    paused: Option<(Index, Cell)>,
+    // Latest contradiction, and the one that went the furthest
+    stuck: Option<Stuck>,
+    furthest: Option<Furthest>,
+    // Searches that may be paused stay on one thread, to keep their frames
+    #[cfg(feature = "parallel")]
+    pausable: bool,
+    scratch: Scratch,
+    depth: usize,
+    // Picks the order values are guessed in, when seeded
+    rng: Option<Rng>,
+    stats: SolveStats,
+    // Every step taken, when the search has to be explained
+    log: Option<Vec<Step>>,
+    // Shared with parallel branches, so the limit holds for the whole search
+    nodes: Arc<AtomicUsize>,
+    #[cfg(feature = "std")]
+    deadline: Option<Instant>,
+    #[cfg(feature = "parallel")]
+    found: Arc<AtomicBool>,
+}
+
+impl Scratch {
+    fn with_capacity(size: usize) -> Self {
+        Self {
+            lane: Vec::with_capacity(size),
+            empties: Vec::with_capacity(size),
+            possible: Vec::with_capacity(size),
+        }
+    }
+}
+
+impl<'a, B> Solver<'a, B>
+where
+    B: Board,
+{
+    pub(crate) fn new(grid: &'a mut B, config: &'a SolverConfig) -> Self {
+        // Every value is possible until proven otherwise
+        let candidates = vec![CellSet::all(); grid.width() * grid.height()];
+        let mut solver = Self::with_candidates(grid, config, candidates);
+
+        // Every lane has to be looked at once
+        solver.queue_lines();
+        solver.queue_columns();
+
+        solver
+    }
+
+    fn with_candidates(
+        grid: &'a mut B,
+        config: &'a SolverConfig,
+        candidates: Vec<CellSet>,
+    ) -> Self {
+        // Buffers are sized for the worst case, so the search never grows
+        // them: a cell is filled once and each of its values eliminated once
+        let cells = candidates.len();
+        let lanes = grid.width() + grid.height();
+        let size = grid.width().max(grid.height());
+        let fixed = (0..cells)
+            .map(|offset| grid[Index(offset / grid.width(), offset % grid.width())].is_some())
+            .collect();
+
+        Self {
+            grid,
+            config,
+            candidates,
+            fixed,
+            trail: Vec::with_capacity(cells * 3),
+            worklist: VecDeque::with_capacity(lanes),
+            queued: vec![false; lanes],
+            frames: Vec::with_capacity(cells),
+            paused: None,
+            stuck: None,
+            furthest: None,
+            #[cfg(feature = "parallel")]
+            pausable: false,
+            scratch: Scratch::with_capacity(size),
+            depth: 0,
+            rng: config.seed.filter(|_| !config.smallest).map(Rng::new),
+            stats: SolveStats::default(),
+            log: None,
+            nodes: Arc::new(AtomicUsize::new(0)),
+            #[cfg(feature = "std")]
+            deadline: config.max_time.map(|time| Instant::now() + time),
+            #[cfg(feature = "parallel")]
+            found: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    // Keep the search on one thread, so it can be paused by a limit
+    #[cfg(feature = "std")]
+    pub(crate) fn pausable(self) -> Self {
+        #[cfg(feature = "parallel")]
+        return Self {
+            pausable: true,
+            ..self
+        };
+
+        #[cfg(not(feature = "parallel"))]
+        self
+    }
+
+    // Pick up a paused search on the grid it left
+    #[cfg(feature = "std")]
+    pub(crate) fn restore(grid: &'a mut B, config: &'a SolverConfig, state: SearchState) -> Self {
+        let width = grid.width();
+        let mut candidates = vec![CellSet::all(); width * grid.height()];
+
+        for change in &state.trail {
+            if let Change::Eliminate(idx, cell) = change {
+                candidates[idx.0 * width + idx.1].remove(*cell);
+            }
+        }
+
+        let mut solver = Self::with_candidates(grid, config, candidates).pausable();
+
+        // Cells filled by the search were not there to begin with
+        for change in &state.trail {
+            if let Change::Fill(idx) = change {
+                let offset = solver.offset(*idx);
+                solver.fixed[offset] = false;
+            }
+        }
+
+        for lane in state.worklist {
+            solver.queue(lane);
+        }
+
+        if let (Some(rng), Some(drawn)) = (&mut solver.rng, state.drawn) {
+            rng.set_drawn(drawn);
+        }
+
+        solver.trail = state.trail;
+        solver.frames = state.frames;
+        solver.paused = state.paused;
+        solver.stats = state.stats;
+        solver
+    }
+
+    // What is needed to resume the search later
+    #[cfg(feature = "std")]
+    pub(crate) fn into_state(self) -> SearchState {
+        SearchState {
+            trail: self.trail,
+            frames: self.frames,
+            worklist: self.worklist.into_iter().collect(),
+            paused: self.paused,
+            stats: self.stats,
+            drawn: self.rng.map(|rng| rng.drawn()),
+        }
+    }
+
+    // Share the flag telling that a solution was found elsewhere
+    #[cfg(feature = "parallel")]
+    pub(crate) fn cancel_with(mut self, found: Arc<AtomicBool>) -> Self {
+        self.found = found;
+        self
+    }
+
+    pub(crate) fn search(&mut self) -> Result<(), SolveError> {
+        match self.explore(1) {
+            Ok(0) => Err(self.no_solution()),
+            Ok(_) => Ok(()),
+            Err(err) => {
+                // Only keep what follows from the givens when giving up
+                if let Some(frame) = self.frames.first() {
+                    self.backtrack(frame.mark);
+                }
+
+                Err(err)
+            }
+        }
+    }
+
+    // Look for a solution other than the one just found
+    fn another(&mut self) -> Result<bool, SolveError> {
+        match self.explore_from(1, false) {
+            Ok(solutions) => Ok(solutions > 0),
+            Err(err) => {
+                if let Some(frame) = self.frames.first() {
+                    self.backtrack(frame.mark);
+                }
+
+                Err(err)
+            }
+        }
+    }
+
+    pub(crate) fn with_log(mut self) -> Self {
+        self.log = Some(Vec::new());
+        self
+    }
+
+    pub(crate) fn into_log(self) -> Vec<Step> {
+        self.log.unwrap_or_default()
+    }
+
+    pub(crate) fn into_stats(self) -> SolveStats {
+        self.stats
+    }
+
+    pub(crate) fn count(&mut self, limit: usize) -> Result<usize, SolveError> {
+        self.explore(limit)
+    }
+
+    // Fill what follows from the rules alone, and tell the first deduction
+    pub(crate) fn deduce(&mut self) -> Result<Option<Index>, SolveError> {
+        if self.propagate().is_err() {
+            self.note_dead_end();
+            return Err(self.no_solution());
+        }
+
+        Ok(self.trail.iter().find_map(|change| match change {
+            Change::Fill(idx) => Some(*idx),
+            Change::Eliminate(..) => None,
+        }))
+    }
+
+    // Look for solutions until the limit, the grid is left on the last one
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "bruteforce",
+            level = "debug",
+            skip(self),
+            fields(depth = self.depth)
+        )
+    )]
+    fn explore(&mut self, limit: usize) -> Result<usize, SolveError> {
+        if limit == 0 {
+            return Ok(0);
+        }
+
+        self.frames.clear();
+
+        // A contradiction while filling means the current guesses are wrong
+        let consistent = self.propagate().is_ok();
+        self.explore_from(limit, consistent)
+    }
+
+    // Carry on a search stopped by a limit, starting with the guess it was
+    // about to make
+    #[cfg(feature = "std")]
+    pub(crate) fn resume(&mut self) -> Result<(), SolveError> {
+        let consistent = match self.paused.take() {
+            Some((idx, cell)) => self.guess(idx, cell),
+            None => self.propagate().is_ok(),
+        };
+
+        match self.explore_from(1, consistent) {
+            Ok(0) => Err(self.no_solution()),
+            Ok(_) => Ok(()),
+            Err(err) => {
+                // Only keep what follows from the givens, unless there is a
+                // guess to resume from
+                if let (None, Some(frame)) = (self.paused, self.frames.first()) {
+                    self.backtrack(frame.mark);
+                }
+
+                Err(err)
+            }
+        }
+    }
+
+    fn explore_from(&mut self, limit: usize, mut consistent: bool) -> Result<usize, SolveError> {
+        let mut solutions = 0;
+
+        loop {
+            // Give up if another branch already found a solution
+            #[cfg(feature = "parallel")]
+            if self.found.load(Ordering::Relaxed) {
+                return Ok(solutions);
+            }
+
+            if consistent {
+                // Bruteforce remaining empty cells
+                if let Some(idx) = self.select_guess() {
+                    let depth = self.depth + self.frames.len();
+
+                    if let Some(limit) = self.config.max_depth.filter(|limit| depth >= *limit) {
+                        return Err(SolveError::LimitExceeded(Limit::Depth(limit)));
+                    }
+
+                    // Branches only look for the first solution, and a seeded or
+                    // logged search stays on one thread to remain reproducible
+                    #[cfg(feature = "parallel")]
+                    if limit == 1
+                        && self.rng.is_none()
+                        && self.log.is_none()
+                        && !self.config.smallest
+                        && !self.config.unique
+                        && !self.pausable
+                        && depth < self.config.parallel_depth
+                    {
+                        match self.fill_parallel(idx, depth) {
+                            Ok(()) => return Ok(1),
+                            Err(SolveError::NoSolution(_)) => consistent = false,
+                            Err(err) => return Err(err),
+                        }
+                    }
+
+                    if consistent {
+                        let untried = self.candidates[self.offset(idx)];
+                        let mark = self.trail.len();
+
+                        self.frames.push(Frame { idx, untried, mark });
+                    }
+                } else {
+                    // Every cell is filled, keep looking for other solutions
+                    solutions += 1;
+                    log::debug!("solution {} found", solutions);
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(solutions, "solution found");
+
+                    if solutions == limit {
+                        return Ok(solutions);
+                    }
+                }
+            }
+
+            if !consistent {
+                self.note_dead_end();
+                log::debug!("contradiction at depth {}", self.frames.len());
+                #[cfg(feature = "tracing")]
+                tracing::debug!(depth = self.frames.len(), "contradiction");
+                self.stats.backtracks += 1;
+                self.record(|_| Step::Backtrack);
+            }
+
+            // Try the next value of the latest guess, or go back to the previous one
+            loop {
+                let Some(frame) = self.frames.last_mut() else {
+                    return Ok(solutions);
+                };
+
+                let (idx, mark) = (frame.idx, frame.mark);
+                let next = match (&mut self.rng, frame.untried.len()) {
+                    (Some(rng), len) if len > 1 => frame.untried.iter().nth(rng.below(len)),
+                    _ => frame.untried.iter().next(),
+                };
+
+                if let Some(cell) = next {
+                    frame.untried.remove(cell);
+                }
+
+                // Undo everything the previous value implied
+                self.backtrack(mark);
+
+                if let Some(cell) = next {
+                    if let Err(err) = self.visit() {
+                        self.paused = Some((idx, cell));
+                        return Err(err);
+                    }
+
+                    consistent = self.guess(idx, cell);
+                    break;
+                }
+
+                self.frames.pop();
+            }
+        }
+    }
+
+    // Remember the contradiction just reached if no other one went as far
+    fn note_dead_end(&mut self) {
+        if let Some(stuck) = self.stuck.take() {
+            Self::reach(&mut self.furthest, &*self.grid, stuck);
+        }
+    }
+
+    // Dead end of a parallel branch, which started from the current grid
+    #[cfg(feature = "parallel")]
+    fn keep_dead_end(&mut self, dead_end: &DeadEnd) {
+        let stuck = (dead_end.index, dead_end.lane, dead_end.rule);
+        Self::reach(&mut self.furthest, &dead_end.grid, stuck);
+    }
+
+    fn reach<G>(furthest: &mut Option<Furthest>, grid: &G, stuck: Stuck)
+    where
+        G: Board,
+    {
+        let filled = grid.lines().map(|i| grid.line_count(i).filled()).sum();
+
+        if furthest
+            .as_ref()
+            .is_some_and(|furthest| furthest.filled >= filled)
+        {
+            return;
+        }
+
+        let cells = grid
+            .lines()
+            .flat_map(|i| grid.columns().map(move |j| Index(i, j)))
+            .filter_map(|idx| grid[idx].map(|cell| (idx, cell)))
+            .collect();
+
+        *furthest = Some(Furthest {
+            filled,
+            cells,
+            stuck,
+        });
+    }
+
+    // The search is over without any solution, tell where it went the furthest
+    fn no_solution(&mut self) -> SolveError {
+        let dead_end = self.furthest.take().map(|furthest| {
+            let mut grid = self.grid.to_grid();
+
+            for (idx, cell) in furthest.cells {
+                Board::set(&mut grid, idx, Some(cell));
+            }
+
+            let (index, lane, rule) = furthest.stuck;
+            Box::new(DeadEnd {
+                grid,
+                index,
+                lane,
+                rule,
+            })
+        });
+
+        SolveError::NoSolution(dead_end)
+    }
+
+    // Fill a cell with a value to try, and tell whether it holds for now
+    fn guess(&mut self, idx: Index, cell: Cell) -> bool {
+        self.stats.guesses += 1;
+        self.stats.max_depth = self.stats.max_depth.max(self.depth + self.frames.len());
+        self.record(|_| Step::Guess(idx, cell));
+        log::debug!(
+            "guess line {}, column {}: {} at depth {}",
+            idx.0 + 1,
+            idx.1 + 1,
+            cell,
+            self.frames.len()
+        );
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            line = idx.0,
+            column = idx.1,
+            value = %cell,
+            depth = self.frames.len(),
+            "guess"
+        );
+
+        self.assign(idx, cell).is_ok() && self.propagate().is_ok()
+    }
+
+    fn visit(&self) -> Result<(), SolveError> {
+        let nodes = self.nodes.fetch_add(1, Ordering::Relaxed) + 1;
+
+        if let Some(limit) = self.config.max_nodes.filter(|limit| nodes > *limit) {
+            return Err(SolveError::LimitExceeded(Limit::Nodes(limit)));
+        }
+
+        #[cfg(feature = "std")]
+        if let (Some(limit), Some(deadline)) = (self.config.max_time, self.deadline) {
+            if Instant::now() > deadline {
+                return Err(SolveError::LimitExceeded(Limit::Time(limit)));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn select_guess(&self) -> Option<Index> {
+        let mut empty = self.grid.empty_cells();
+
+        match self.config.guess {
+            _ if self.config.smallest => empty.next(),
+            GuessStrategy::FirstEmpty => empty.next(),
+            GuessStrategy::MostConstrained => {
+                // Prefer cells whose line and column are the most filled
+                empty.min_by_key(|idx| {
+                    (self.grid.width() - self.grid.line_count(idx.0).filled())
+                        + (self.grid.height() - self.grid.column_count(idx.1).filled())
+                })
+            }
+        }
+    }
+
+    fn assign<I>(&mut self, idx: I, cell: Cell) -> Result<bool, Rule>
+    where
+        I: Into<Index>,
+    {
+        let idx = idx.into();
+
+        if let Err(rule) = self.grid.check_move(idx, cell) {
+            self.stuck = Some((idx, None, rule));
+            return Err(rule);
+        }
+
+        // Keep track of filled cells, so they can be emptied on backtrack
+        if self.grid[idx].is_none() {
+            self.trail.push(Change::Fill(idx));
+        }
+
+        let changed = self.grid.set(idx, Some(cell));
+
+        if changed {
+            self.queue(Lane::Line(idx.0));
+            self.queue(Lane::Column(idx.1));
+
+            // A complete lane forbids its duplicates in every other lane
+            if self.grid.line_count(idx.0).filled() == self.grid.width() {
+                self.queue_lines();
+            }
+
+            if self.grid.column_count(idx.1).filled() == self.grid.height() {
+                self.queue_columns();
+            }
+
+            // Other cells of a diagonal are checked along with their lines
+            let (width, height) = (self.grid.width(), self.grid.height());
+
+            if self.grid.rules().on_diagonal(idx, width, height) {
+                self.queue_lines();
+            }
+
+            // So are the other cells of its region
+            let lines: BTreeSet<usize> = self
+                .grid
+                .region_cells(idx)
+                .iter()
+                .map(|other| other.0)
+                .collect();
+
+            for i in lines {
+                self.queue(Lane::Line(i));
+            }
+        }
+
+        Ok(changed)
+    }
+
+    fn eliminate(
+        &mut self,
+        idx: Index,
+        cell: Cell,
+        technique: Technique,
+        lane: Lane,
+    ) -> Result<(), Rule> {
+        let offset = self.offset(idx);
+
+        if self.grid[idx].is_some() || !self.candidates[offset].contains(cell) {
+            return Ok(());
+        }
+
+        self.candidates[offset].remove(cell);
+        self.trail.push(Change::Eliminate(idx, cell));
+
+        // Fill the cell when only one value remains
+        match self.candidates[offset].single() {
+            Some(last) => {
+                *self.stats.filled.entry(technique).or_default() += 1;
+                log::trace!(
+                    "line {}, column {}: {} by {}",
+                    idx.0 + 1,
+                    idx.1 + 1,
+                    last,
+                    technique
+                );
+                #[cfg(feature = "tracing")]
+                tracing::trace!(
+                    line = idx.0,
+                    column = idx.1,
+                    value = %last,
+                    %technique,
+                    "cell filled"
+                );
+                self.record(|solver| {
+                    let (lane, premises) = solver.premises(idx, cell, technique, lane);
+
+                    Step::Deduction(Deduction {
+                        index: idx,
+                        value: last,
+                        technique,
+                        lane,
+                        premises,
+                    })
+                });
+
+                self.assign(idx, last).map(|_| ())
+            }
+            None if self.candidates[offset].is_empty() => {
+                self.stuck = Some((idx, Some(lane), technique.rule()));
+                Err(technique.rule())
+            }
+            None => Ok(()),
+        }
+    }
+
+    // Steps are only built when someone asked for them
+    fn record<F>(&mut self, step: F)
+    where
+        F: FnOnce(&Self) -> Step,
+    {
+        if self.log.is_none() {
+            return;
+        }
+
+        let step = step(self);
+
+        if let Some(log) = &mut self.log {
+            log.push(step);
+        }
+    }
+
+    // Lane and filled cells explaining why a value cannot go in a cell
+    fn premises(
+        &self,
+        idx: Index,
+        cell: Cell,
+        technique: Technique,
+        lane: Lane,
+    ) -> (Lane, Vec<Index>) {
+        let crossing = match lane {
+            Lane::Line(_) => Lane::Column(idx.1),
+            Lane::Column(_) => Lane::Line(idx.0),
+        };
+
+        [lane, crossing]
+            .into_iter()
+            .find_map(|lane| {
+                self.premises_in(idx, cell, technique, lane)
+                    .map(|premises| (lane, premises))
+            })
+            .or_else(|| {
+                self.diagonal_premises(idx, cell, technique)
+                    .or_else(|| self.region_premises(idx, cell, technique))
+                    .map(|premises| (lane, premises))
+            })
+            .unwrap_or((lane, Vec::new()))
+    }
+
+    // Diagonals are not lanes of their own, but force cells all the same
+    fn diagonal_premises(
+        &self,
+        idx: Index,
+        cell: Cell,
+        technique: Technique,
+    ) -> Option<Vec<Index>> {
+        let rules = self.grid.rules().diagonal_rules();
+        let diagonals = rules.diagonal_cells(self.grid.width(), self.grid.height());
+
+        diagonals.into_iter().find_map(|mut diagonal| {
+            diagonal.retain(|other| *other == idx || !self.grid.is_hole(*other));
+
+            let size = diagonal.len();
+            let k = diagonal.iter().position(|other| *other == idx)?;
+            let value = |m: usize| {
+                if m == k {
+                    Some(cell)
+                } else {
+                    self.grid[diagonal[m]]
+                }
+            };
+
+            let premises = match technique {
+                Technique::Adjacency if rules.enforces(Rule::Adjacency) => {
+                    Self::run_around(k, size, cell, &rules, value)?
+                }
+                Technique::Balance if rules.enforces(Rule::Balance) => {
+                    let cells: Vec<_> = (0..size)
+                        .filter(|m| *m != k && value(*m) == Some(cell))
+                        .collect();
+
+                    (cells.len() == rules.max_count(size, cell)).then_some(cells)?
+                }
+                _ => return None,
+            };
+
+            Some(premises.into_iter().map(|m| diagonal[m]).collect())
+        })
+    }
+
+    // Regions are saturated like lanes, once they hold all their cells of a value
+    fn region_premises(&self, idx: Index, cell: Cell, technique: Technique) -> Option<Vec<Index>> {
+        let region = self.grid.region_cells(idx);
+
+        if region.is_empty()
+            || technique != Technique::Balance
+            || !self.grid.rules().enforces(Rule::Balance)
+        {
+            return None;
+        }
+
+        let cells: Vec<_> = region
+            .iter()
+            .copied()
+            .filter(|other| *other != idx && self.grid[*other] == Some(cell))
+            .collect();
+
+        let usable = region
+            .iter()
+            .filter(|other| !self.grid.is_hole(**other))
+            .count();
+
+        (cells.len() == self.grid.rules().max_count(usable, cell)).then_some(cells)
+    }
+
+    // Cells of a run at the limit next to the cell at k, or around it
+    fn run_around<F>(
+        k: usize,
+        size: usize,
+        cell: Cell,
+        rules: &Rules,
+        value: F,
+    ) -> Option<Vec<usize>>
+    where
+        F: Fn(usize) -> GridCell,
+    {
+        let limit = rules.run_limit() as isize;
+
+        (-limit..=0).find_map(|start| {
+            (start..=start + limit)
+                .filter(|offset| *offset != 0)
+                .map(|offset| {
+                    rules
+                        .neighbor(k, offset, size)
+                        .filter(|m| value(*m) == Some(cell))
+                })
+                .collect()
+        })
+    }
+
+    fn premises_in(
+        &self,
+        idx: Index,
+        cell: Cell,
+        technique: Technique,
+        lane: Lane,
+    ) -> Option<Vec<Index>> {
+        let size = self.lane_len(lane);
+        let k = match lane {
+            Lane::Line(_) => idx.1,
+            Lane::Column(_) => idx.0,
+        };
+
+        // Rules left out of the grid force nothing
+        let enforced = match technique.rule() {
+            Rule::Uniqueness => self.grid.rules().is_unique(lane),
+            // Margin clues count the ones of their lane anyway
+            Rule::Balance if self.grid.target(lane).is_some() => true,
+            rule => self.grid.rules().enforces(rule),
+        };
+
+        if !enforced {
+            return None;
+        }
+
+        let value = |m: usize| {
+            if m == k {
+                Some(cell)
+            } else {
+                self.grid[lane.index(m)]
+            }
+        };
+
+        match technique {
+            // A run at the limit next to the cell, or around it, across holes
+            Technique::Adjacency => {
+                let cells: Vec<usize> = (0..size)
+                    .filter(|m| *m == k || !self.grid.is_hole(lane.index(*m)))
+                    .collect();
+                let k = cells.iter().position(|m| *m == k)?;
+
+                Self::run_around(k, cells.len(), cell, &self.grid.rules(), |m| {
+                    value(cells[m])
+                })
+                .map(|found| found.into_iter().map(|m| lane.index(cells[m])).collect())
+            }
+            // Every cell of the value is already there
+            Technique::Balance => {
+                let cells: Vec<_> = (0..size)
+                    .filter(|m| *m != k && value(*m) == Some(cell))
+                    .map(|m| lane.index(m))
+                    .collect();
+
+                (cells.len() == self.grid.max_count(lane, cell)).then_some(cells)
+            }
+            // The lane would repeat another complete one
+            Technique::Uniqueness => {
+                if (0..size).any(|m| value(m).is_none() && !self.grid.is_hole(lane.index(m))) {
+                    return None;
+                }
+
+                self.other_lanes(lane)
+                    .into_iter()
+                    .find(|other| {
+                        (0..size).all(|m| {
+                            self.grid[other.index(m)] == value(m)
+                                && self.grid.is_hole(other.index(m)) == self.grid.is_hole(lane.index(m))
+                        })
+                    })
+                    .map(|other| (0..size).map(|m| other.index(m)).collect())
+            }
+            // A complete lane agrees with the other cells, and has the value
+            Technique::UniqueCompletion => self
+                .other_lanes(lane)
+                .into_iter()
+                .find(|other| {
+                    (0..size).all(|m| {
+                        let filled = self.grid[other.index(m)];
+                        filled.is_some() && value(m).is_none_or(|value| filled == Some(value))
+                    })
+                })
+                .map(|other| (0..size).map(|m| other.index(m)).collect()),
+            Technique::Completion | Technique::Crossing | Technique::Lookahead => {
+                Some(Vec::new())
+            }
+            // Cells the other value would let the cell swap values with
+            Technique::DeadlyPattern => self.deadly_pattern(idx, cell).map(Vec::from),
+            // A filled neighbor across a sign forces the other value
+            Technique::Sign => [k.checked_sub(1), k.checked_add(1)]
+                .into_iter()
+                .flatten()
+                .filter(|m| *m < size)
+                .map(|m| lane.index(m))
+                .find(|other| {
+                    let sign = self.grid.sign(idx, *other);
+                    matches!((sign, self.grid[*other]), (Some(sign), Some(value)) if sign.forced(value) != cell)
+                })
+                .map(|other| vec![other]),
+        }
+    }
+
+    // Lanes of the same kind as a lane, itself left out
+    fn other_lanes(&self, lane: Lane) -> Vec<Lane> {
+        match lane {
+            Lane::Line(i) => self
+                .grid
+                .lines()
+                .filter(|o| *o != i)
+                .map(Lane::Line)
+                .collect(),
+            Lane::Column(j) => self
+                .grid
+                .columns()
+                .filter(|o| *o != j)
+                .map(Lane::Column)
+                .collect(),
+        }
+    }
+
+    fn backtrack(&mut self, mark: usize) {
+        let width = self.grid.width();
+
+        // Lanes left over by a contradiction are back to their checked state
+        self.worklist.clear();
+        self.queued.fill(false);
+
+        for change in self.trail.drain(mark..).rev() {
+            match change {
+                Change::Fill(idx) => {
+                    self.grid.set(idx, None);
+                }
+                Change::Eliminate(idx, cell) => {
+                    self.candidates[idx.0 * width + idx.1].insert(cell);
+                }
+            }
+        }
+    }
+
+    fn queue(&mut self, lane: Lane) {
+        let k = match lane {
+            Lane::Line(i) => i,
+            Lane::Column(j) => self.grid.height() + j,
+        };
+
+        if !self.queued[k] {
+            self.queued[k] = true;
+            self.worklist.push_back(lane);
+        }
+    }
+
+    fn queue_lines(&mut self) {
+        for i in self.grid.lines() {
+            self.queue(Lane::Line(i));
+        }
+    }
+
+    fn queue_columns(&mut self) {
+        for j in self.grid.columns() {
+            self.queue(Lane::Column(j));
+        }
+    }
+
+    fn propagate(&mut self) -> Result<(), Rule> {
+        self.stats.propagations += 1;
+        let mark = self.trail.len();
+        let result = self
+            .propagate_lanes()
+            .and_then(|_| self.fill_deadly_patterns())
+            .and_then(|_| self.look_ahead(self.config.lookahead));
+
+        if log::log_enabled!(log::Level::Debug) {
+            let filled = self.trail[mark..]
+                .iter()
+                .filter(|change| matches!(change, Change::Fill(_)))
+                .count();
+
+            match &result {
+                Ok(()) => log::debug!("propagation filled {} cells", filled),
+                Err(rule) => log::debug!("propagation filled {} cells, then {}", filled, rule),
+            }
+        }
+
+        result
+    }
+
+    fn propagate_lanes(&mut self) -> Result<(), Rule> {
+        while let Some(lane) = self.worklist.pop_front() {
+            match lane {
+                Lane::Line(i) => self.queued[i] = false,
+                Lane::Column(j) => self.queued[self.grid.height() + j] = false,
+            }
+
+            self.fill_candidates(lane)?;
+
+            if self.config.allows(Technique::Completion) {
+                self.fill_heuristics(lane)?;
+            }
+
+            if self.config.allows(Technique::UniqueCompletion) {
+                self.fill_unique_completions(lane)?;
+            }
+
+            if self.config.allows(Technique::Crossing) {
+                self.fill_crossings(lane)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    // Rule out values that would complete a rectangle of cells whose values
+    // can be swapped, on puzzles assumed to have a single solution
+    fn fill_deadly_patterns(&mut self) -> Result<(), Rule> {
+        // Searches looking for other solutions cannot rule them out
+        if !self.config.allows(Technique::DeadlyPattern)
+            || self.config.smallest
+            || self.config.unique
+        {
+            return Ok(());
+        }
+
+        'scan: loop {
+            let empty: Vec<Index> = self.grid.empty_cells().collect();
+
+            for idx in empty {
+                for cell in Cell::iter() {
+                    if self.grid[idx].is_none() && self.deadly_pattern(idx, cell).is_some() {
+                        self.eliminate(idx, cell, Technique::DeadlyPattern, Lane::Line(idx.0))?;
+                        self.propagate_lanes()?;
+
+                        continue 'scan;
+                    }
+                }
+            }
+
+            return Ok(());
+        }
+    }
+
+    // Other corners of a rectangle the value would let the empty cell swap
+    // values with, the rules holding whatever the cells still empty become
+    fn deadly_pattern(&self, idx: Index, cell: Cell) -> Option<[Index; 3]> {
+        let Index(i, j) = idx;
+        let (width, height) = (self.grid.width(), self.grid.height());
+        let limit = self.grid.rules().run_limit();
+
+        // Only standard rules are sure to hold once the cells are swapped
+        if !self.grid.rules().is_standard() {
+            return None;
+        }
+
+        let plain = |idx: Index| {
+            !self.fixed[self.offset(idx)]
+                && self.grid.target(Lane::Line(idx.0)).is_none()
+                && self.grid.target(Lane::Column(idx.1)).is_none()
+                && self.grid.region_cells(idx).is_empty()
+                && [(0, 1), (1, 0), (0, -1), (-1, 0)]
+                    .into_iter()
+                    .all(|(di, dj)| {
+                        let other =
+                            Index(idx.0.wrapping_add_signed(di), idx.1.wrapping_add_signed(dj));
+
+                        other.0 >= height
+                            || other.1 >= width
+                            || self.grid.sign(idx, other).is_none()
+                    })
+        };
+
+        // Runs through a swapped cell are known once the cells around are
+        let settled = |corner: Index| {
+            let column = corner.0.saturating_sub(limit)..(corner.0 + limit + 1).min(height);
+            let line = corner.1.saturating_sub(limit)..(corner.1 + limit + 1).min(width);
+
+            column
+                .map(|m| Index(m, corner.1))
+                .chain(line.map(|n| Index(corner.0, n)))
+                .all(|other| {
+                    other == idx || (self.grid[other].is_some() && !self.grid.is_hole(other))
+                })
+        };
+
+        if !plain(idx) || !settled(idx) {
+            return None;
+        }
+
+        for m in self.grid.lines().filter(|m| *m != i) {
+            for n in self.grid.columns().filter(|n| *n != j) {
+                let corners = [Index(m, n), Index(m, j), Index(i, n)];
+
+                // Opposite corners hold the same value, which the swap flips
+                if self.grid[corners[0]] != Some(cell)
+                    || self.grid[corners[1]] != Some(!cell)
+                    || self.grid[corners[2]] != Some(!cell)
+                    || !corners
+                        .iter()
+                        .all(|corner| plain(*corner) && settled(*corner))
+                {
+                    continue;
+                }
+
+                // Lanes once swapped, the empty cell holding the value
+                let swapped = |lane: Lane| -> Vec<GridCell> {
+                    (0..self.lane_len(lane))
+                        .map(|k| {
+                            let other = lane.index(k);
+
+                            if other == idx || other == corners[0] {
+                                Some(!cell)
+                            } else if corners[1..].contains(&other) {
+                                Some(cell)
+                            } else {
+                                self.grid[other]
+                            }
+                        })
+                        .collect()
+                };
+
+                let lanes = [
+                    Lane::Line(m),
+                    Lane::Line(i),
+                    Lane::Column(n),
+                    Lane::Column(j),
+                ];
+
+                if lanes
+                    .iter()
+                    .all(|lane| self.fits_swap(*lane, &swapped(*lane), &lanes))
+                {
+                    return Some(corners);
+                }
+            }
+        }
+
+        None
+    }
+
+    // Whether a swapped lane keeps its runs short, and is sure to differ
+    // from the lanes of its kind left as they are
+    fn fits_swap(&self, lane: Lane, cells: &[GridCell], swapped: &[Lane]) -> bool {
+        let limit = self.grid.rules().run_limit();
+        let long_run = cells
+            .windows(limit + 1)
+            .any(|run| run[0].is_some() && run.iter().all(|cell| *cell == run[0]));
+
+        if long_run {
+            return false;
+        }
+
+        // Both swapped lanes of a kind differ where they were swapped
+        self.other_lanes(lane)
+            .into_iter()
+            .filter(|other| !swapped.contains(other))
+            .all(|other| {
+                (0..cells.len()).any(|k| {
+                    let value = self.grid[other.index(k)];
+                    value.is_some() && cells[k].is_some() && value != cells[k]
+                })
+            })
+    }
+
+    // Try each value left in each empty cell, ruling out the ones that
+    // contradict the rules once filled in
+    fn look_ahead(&mut self, depth: usize) -> Result<(), Rule> {
+        if depth == 0 || !self.config.allows(Technique::Lookahead) {
+            return Ok(());
+        }
+
+        'scan: loop {
+            let empty: Vec<Index> = self.grid.empty_cells().collect();
+
+            for idx in empty {
+                let candidates = self.candidates[self.offset(idx)];
+
+                for cell in candidates.iter() {
+                    if self.grid[idx].is_none() && self.contradicts(idx, cell, depth) {
+                        self.eliminate(idx, cell, Technique::Lookahead, Lane::Line(idx.0))?;
+                        self.propagate_lanes()?;
+
+                        // Earlier cells may now be ruled out too
+                        continue 'scan;
+                    }
+                }
+            }
+
+            return Ok(());
+        }
+    }
+
+    // Whether filling the cell leads to a contradiction, leaving the
+    // search as it was
+    fn contradicts(&mut self, idx: Index, cell: Cell, depth: usize) -> bool {
+        let mark = self.trail.len();
+        let stats = self.stats.clone();
+        let steps = self.log.as_ref().map(Vec::len);
+
+        let contradiction = self.assign(idx, cell).is_err()
+            || self.propagate_lanes().is_err()
+            || self.look_ahead(depth - 1).is_err();
+
+        self.backtrack(mark);
+        self.stats = stats;
+
+        if let (Some(log), Some(steps)) = (&mut self.log, steps) {
+            log.truncate(steps);
+        }
+
+        contradiction
+    }
+
+    fn offset(&self, idx: Index) -> usize {
+        idx.0 * self.grid.width() + idx.1
+    }
+
+    fn lane_len(&self, lane: Lane) -> usize {
+        match lane {
+            Lane::Line(_) => self.grid.width(),
+            Lane::Column(_) => self.grid.height(),
+        }
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self)))]
+    fn fill_candidates(&mut self, lane: Lane) -> Result<(), Rule> {
+        // Remove values that would break a rule: saturated lanes, pairs of
+        // identical cells, identical cells around, or duplicated lanes
+        for k in 0..self.lane_len(lane) {
+            let idx = lane.index(k);
+
+            for cell in Cell::iter() {
+                if self.grid[idx].is_some() || self.grid.is_hole(idx) {
+                    break;
+                }
+
+                if let Err(rule) = self.grid.check_move(idx, cell) {
+                    // The broken rule may not be allowed, while another one is
+                    let technique = Some(Technique::from(rule))
+                        .filter(|technique| self.config.allows(*technique))
+                        .or_else(|| self.allowed_technique(idx, cell));
+
+                    if let Some(technique) = technique {
+                        self.eliminate(idx, cell, technique, lane)?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn allowed_technique(&self, idx: Index, cell: Cell) -> Option<Technique> {
+        let lanes = [Lane::Line(idx.0), Lane::Column(idx.1)];
+
+        Technique::iter()
+            .filter(|technique| {
+                !matches!(
+                    technique,
+                    Technique::Completion
+                        | Technique::Crossing
+                        | Technique::Lookahead
+                        | Technique::DeadlyPattern
+                )
+            })
+            .filter(|technique| self.config.allows(*technique))
+            .find(|technique| {
+                lanes
+                    .iter()
+                    .any(|lane| self.premises_in(idx, cell, *technique, *lane).is_some())
+                    || self.diagonal_premises(idx, cell, *technique).is_some()
+                    || self.region_premises(idx, cell, *technique).is_some()
+            })
+    }
+
+    fn lane_cells(&self, lane: Lane) -> Vec<GridCell> {
+        (0..self.lane_len(lane))
+            .map(|k| self.grid[lane.index(k)])
+            .collect()
+    }
+
+    // Lanes cut by holes or counted by a margin clue are left to the
+    // techniques checking moves
+    fn is_irregular(&self, lane: Lane) -> bool {
+        self.grid.target(lane).is_some()
+            || (0..self.lane_len(lane)).any(|k| self.grid.is_hole(lane.index(k)))
+    }
+
+    // Call a function on every completion following the rules of its lane
+    fn for_each_completion<F>(cells: &mut [GridCell], empties: &[usize], rules: &Rules, mut f: F)
+    where
+        F: FnMut(&[GridCell]),
+    {
+        for bits in 0..1u32 << empties.len() {
+            for (m, k) in empties.iter().enumerate() {
+                cells[*k] = Some(if (bits >> m) & 1 == 1 {
+                    Cell::One
+                } else {
+                    Cell::Zero
+                });
+            }
+
+            if Grid::check_lane(cells.iter(), rules).is_ok() {
+                f(cells);
+            }
+        }
+    }
+
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self)))]
+    fn fill_heuristics(&mut self, lane: Lane) -> Result<(), Rule> {
+        if self.is_irregular(lane) {
+            return Ok(());
+        }
+
+        // Buffers are moved out while the grid is being changed
+        let mut scratch = mem::take(&mut self.scratch);
+
+        scratch.lane.clear();
+        scratch
+            .lane
+            .extend((0..self.lane_len(lane)).map(|k| self.grid[lane.index(k)]));
+
+        let map = match lane {
+            Lane::Line(i) => self.grid.line_count(i),
+            Lane::Column(j) => self.grid.column_count(j),
+        };
+
+        // Rule out the values an empty cell takes in no completion
+        Self::try_completions(&mut scratch, &map, &self.grid.rules());
+
+        let result = scratch
+            .empties
+            .iter()
+            .zip(&scratch.possible)
+            .try_for_each(|(k, set)| {
+                Cell::iter()
+                    .filter(|cell| !set.contains(*cell))
+                    .try_for_each(|cell| {
+                        self.eliminate(lane.index(*k), cell, Technique::Completion, lane)
+                    })
+            });
+
+        self.scratch = scratch;
+        result
+    }
+
+    // Rule out the values that only completions repeating a complete lane
+    // give to an empty cell, like a human would before filling the lane
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self)))]
+    fn fill_unique_completions(&mut self, lane: Lane) -> Result<(), Rule> {
+        let size = self.lane_len(lane);
+        let rules = self.grid.rules();
+
+        // Lanes counted apart are not checked like the others
+        if !rules.is_unique(lane) || self.is_irregular(lane) {
+            return Ok(());
+        }
+
+        let mut cells = self.lane_cells(lane);
+        let empties: Vec<usize> = (0..size).filter(|k| cells[*k].is_none()).collect();
+
+        // A single empty cell is left to the check of its moves
+        if empties.len() < 2 || empties.len() > MAX_COMPLETED_CELLS {
+            return Ok(());
+        }
+
+        // Complete lanes which agree with every filled cell of this one
+        let others: Vec<Vec<GridCell>> = self
+            .other_lanes(lane)
+            .into_iter()
+            .map(|other| (0..size).map(|k| self.grid[other.index(k)]).collect())
+            .filter(|other: &Vec<GridCell>| {
+                other
+                    .iter()
+                    .zip(&cells)
+                    .all(|(a, b)| a.is_some() && (b.is_none() || a == b))
+            })
+            .collect();
+
+        if others.is_empty() {
+            return Ok(());
+        }
+
+        // Values of the empty cells in the completions following the rules
+        // of the lane, and in the ones which do not repeat another lane
+        let mut possible = vec![CellSet::default(); empties.len()];
+        let mut unique = vec![CellSet::default(); empties.len()];
+
+        Self::for_each_completion(&mut cells, &empties, &rules, |cells| {
+            let repeated = others.iter().any(|other| other == cells);
+
+            for (m, k) in empties.iter().enumerate() {
+                let value = cells[*k].expect("completions fill every cell");
+                possible[m].insert(value);
+
+                if !repeated {
+                    unique[m].insert(value);
+                }
+            }
+        });
+
+        for (m, k) in empties.iter().enumerate() {
+            for cell in Cell::iter() {
+                if possible[m].contains(cell) && !unique[m].contains(cell) {
+                    self.eliminate(lane.index(*k), cell, Technique::UniqueCompletion, lane)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Rule out the values that only go with completions of a lane where some
+    // crossing lane could not be completed anymore
+    #[cfg_attr(feature = "tracing", tracing::instrument(level = "trace", skip(self)))]
+    fn fill_crossings(&mut self, lane: Lane) -> Result<(), Rule> {
+        if self.is_irregular(lane) {
+            return Ok(());
+        }
+
+        let mut cells = self.lane_cells(lane);
+        let empties: Vec<usize> = (0..cells.len()).filter(|k| cells[*k].is_none()).collect();
+
+        if empties.is_empty() || empties.len() > MAX_COMPLETED_CELLS {
+            return Ok(());
+        }
+
+        // Values of each empty cell that its crossing lane can go with
+        let crossed: Vec<CellSet> = empties
+            .iter()
+            .map(|k| {
+                Cell::iter()
+                    .filter(|cell| self.fits_crossing(lane, lane.index(*k), *cell))
+                    .collect()
+            })
+            .collect();
+
+        if crossed.iter().all(|set| *set == CellSet::all()) {
+            return Ok(());
+        }
+
+        // Values of the empty cells in the completions of the lane, and in the
+        // ones every crossing lane goes with
+        let mut possible = vec![CellSet::default(); empties.len()];
+        let mut kept = vec![CellSet::default(); empties.len()];
+
+        Self::for_each_completion(&mut cells, &empties, &self.grid.rules(), |cells| {
+            let fits = empties
+                .iter()
+                .zip(&crossed)
+                .all(|(k, set)| cells[*k].is_some_and(|value| set.contains(value)));
+
+            for (m, k) in empties.iter().enumerate() {
+                let value = cells[*k].expect("completions fill every cell");
+                possible[m].insert(value);
+
+                if fits {
+                    kept[m].insert(value);
+                }
+            }
+        });
+
+        for (m, k) in empties.iter().enumerate() {
+            for cell in Cell::iter() {
+                if possible[m].contains(cell) && !kept[m].contains(cell) {
+                    self.eliminate(lane.index(*k), cell, Technique::Crossing, lane)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // Whether the lane crossing another one at a cell can still be completed
+    // with a value there
+    fn fits_crossing(&self, lane: Lane, idx: Index, cell: Cell) -> bool {
+        let (crossing, k) = match lane {
+            Lane::Line(_) => (Lane::Column(idx.1), idx.0),
+            Lane::Column(_) => (Lane::Line(idx.0), idx.1),
+        };
+
+        if self.is_irregular(crossing) {
+            return true;
+        }
+
+        let mut cells = self.lane_cells(crossing);
+        cells[k] = Some(cell);
+        let empties: Vec<usize> = (0..cells.len()).filter(|m| cells[*m].is_none()).collect();
+
+        // Long completions are not tried, the lane may go with anything
+        if empties.len() > MAX_COMPLETED_CELLS {
+            return true;
+        }
+
+        let mut found = false;
+        Self::for_each_completion(&mut cells, &empties, &self.grid.rules(), |_| found = true);
+        found
+    }
+
+    #[cfg(feature = "parallel")]
+    fn fill_parallel(&mut self, idx: Index, depth: usize) -> Result<(), SolveError> {
+        let offset = self.offset(idx);
+
+        // Explore a guess on its own copy of the grid
+        let branch = |cell: Cell| {
+            if !self.candidates[offset].contains(cell) {
+                return Err(SolveError::NoSolution(None));
+            }
+
+            self.visit()?;
+
+            let mut grid = self.grid.clone();
+            let mut solver =
+                Solver::with_candidates(&mut grid, self.config, self.candidates.clone());
+            solver.depth = depth + 1;
+            solver.fixed = self.fixed.clone();
+            solver.nodes = self.nodes.clone();
+            #[cfg(feature = "std")]
+            {
+                solver.deadline = self.deadline;
+            }
+            solver.found = self.found.clone();
+
+            solver.stats.guesses += 1;
+            solver.stats.max_depth = depth + 1;
+            if solver.assign(idx, cell).is_err() {
+                solver.note_dead_end();
+                return Err(solver.no_solution());
+            }
+
+            solver.search()?;
+
+            // Stop the other branches
+            self.found.store(true, Ordering::Relaxed);
+
+            let stats = solver.into_stats();
+            Ok((grid, stats))
+        };
+
+        let (zero, one) = rayon::join(|| branch(Cell::Zero), || branch(Cell::One));
+
+        match (zero, one) {
+            (Ok((grid, stats)), _) | (_, Ok((grid, stats))) => {
+                *self.grid = grid;
+                self.stats.merge(&stats);
+                Ok(())
+            }
+            (Err(SolveError::NoSolution(zero)), Err(SolveError::NoSolution(one))) => {
+                // Either branch may have gone the furthest
+                for dead_end in zero.iter().chain(&one) {
+                    self.keep_dead_end(dead_end);
+                }
+
+                Err(SolveError::NoSolution(None))
+            }
+            (Err(SolveError::NoSolution(_)), Err(err)) | (Err(err), _) => Err(err),
+        }
+    }
+
+    // Values each empty cell takes in the completions of a lane with the
+    // counts of its values, when there are not too many of them
+    fn try_completions(scratch: &mut Scratch, map: &Histogram, rules: &Rules) {
+        let Scratch {
+            lane,
+            empties,
+            possible,
+        } = scratch;
+        let size = lane.len();
+
+        empties.clear();
+        empties.extend((0..size).filter(|k| lane[*k].is_none()));
+        possible.clear();
+
+        // Place the value missing the least, the others get the opposite one
+        let missing = |cell| rules.max_count(size, cell).saturating_sub(map[cell]);
+        let cell = Cell::iter()
+            .min_by_key(|cell| missing(*cell))
+            .expect("cells have values");
+        let n = empties.len();
+        let counts = n.saturating_sub(missing(!cell))..=missing(cell).min(n);
+
+        let total = counts
+            .clone()
+            .try_fold(0usize, |total, r| total.checked_add(binomial(n, r)?));
+
+        if n == 0 || total.is_none_or(|total| total > MAX_COMPLETIONS) {
+            return;
+        }
+
+        possible.resize(n, CellSet::default());
+
+        for r in counts {
+            // Positions among the empty cells given the placed value
+            let mut picks: Vec<usize> = (0..r).collect();
+
+            loop {
+                for k in empties.iter() {
+                    lane[*k] = Some(!cell);
+                }
+
+                for m in picks.iter() {
+                    lane[empties[*m]] = Some(cell);
+                }
+
+                if Grid::check_lane(lane.iter(), rules).is_ok() {
+                    for (m, k) in empties.iter().enumerate() {
+                        possible[m].insert(lane[*k].expect("completions fill every cell"));
+                    }
+                }
+
+                // Move on to the next positions, in lexicographic order
+                let Some(m) = (0..r).rev().find(|m| picks[*m] < n - r + m) else {
+                    break;
+                };
+
+                picks[m] += 1;
+
+                for next in m + 1..r {
+                    picks[next] = picks[next - 1] + 1;
+                }
+            }
+        }
+    }
+}
+
+// Number of ways to pick some items among others, unless it overflows
+fn binomial(n: usize, r: usize) -> Option<usize> {
+    let r = r.min(n - r);
+
+    (0..r).try_fold(1usize, |count, i| Some(count.checked_mul(n - i)? / (i + 1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guess_strategies() {
+        // Deductions alone do not finish this grid, with a single solution
+        let input = vec![
+            "1 - - - - -",
+            "- - - - 1 -",
+            "- 1 0 - - -",
+            "- - - 0 - -",
+            "0 - - - - 1",
+            "- - - 0 0 -",
+        ];
+        let grid = Grid::parse(input.into_iter()).unwrap();
+        assert!(grid.requires_guessing());
+
+        let mut first = grid.clone();
+        let config = SolverConfig::new().guess_strategy(GuessStrategy::FirstEmpty);
+        let first_stats = first.solve_with_stats(&config).unwrap();
+
+        let mut constrained = grid.clone();
+        let config = SolverConfig::new().guess_strategy(GuessStrategy::MostConstrained);
+        let constrained_stats = constrained.solve_with_stats(&config).unwrap();
+
+        // Both find the solution, the most constrained cell in fewer guesses
+        assert_eq!(first, constrained);
+        assert_eq!(first_stats.guesses(), 3);
+        assert_eq!(constrained_stats.guesses(), 1);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn portfolio() {
+        let input = vec![
+            "1 - - - - -",
+            "- - - - 1 -",
+            "- 1 0 - - -",
+            "- - - 0 - -",
+            "0 - - - - 1",
+            "- - - 0 0 -",
+        ];
+        let grid = Grid::parse(input.into_iter()).unwrap();
+
+        let mut expected = grid.clone();
+        expected.solve().unwrap();
+
+        // A single node is too few for the first empty cell, which needs
+        // more guesses, but enough for the most constrained one
+        let first = SolverConfig::new()
+            .guess_strategy(GuessStrategy::FirstEmpty)
+            .max_nodes(1);
+        let constrained = SolverConfig::new()
+            .guess_strategy(GuessStrategy::MostConstrained)
+            .max_nodes(1);
+
+        let mut solved = grid.clone();
+        assert!(matches!(
+            solved.solve_portfolio(core::slice::from_ref(&first)),
+            Err(SolveError::LimitExceeded(Limit::Nodes(1)))
+        ));
+
+        let mut solved = grid.clone();
+        solved.solve_portfolio(&[first, constrained]).unwrap();
+        assert_eq!(solved, expected);
+
+        // Without any configuration, nothing can be found
+        let mut solved = grid.clone();
+        assert!(matches!(
+            solved.solve_portfolio(&[]),
+            Err(SolveError::NoSolution(_))
+        ));
+    }
+
+    #[test]
+    fn batch() {
+        let solvable = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+        let unsolvable = Grid::parse(vec!["0 - - 0", "0 - - 0", "- - - -", "- - - -"].into_iter());
+
+        let results = solve_all(vec![solvable.unwrap(), unsolvable.unwrap()]);
+
+        assert_eq!(results.len(), 2);
+        assert!(results[0]
+            .as_ref()
+            .is_ok_and(|grid| grid.empty_cells().next().is_none()));
+        assert!(matches!(results[1], Err(SolveError::NoSolution(_))));
+    }
+
+    #[test]
+    fn unique_solutions() {
+        let config = SolverConfig::new().require_unique(true);
+
+        let input = vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"];
+        let mut grid = Grid::parse(input.into_iter()).unwrap();
+        let mut expected = grid.clone();
+        expected.solve().unwrap();
+        assert!(grid.solve_with(&config).is_ok());
+        assert_eq!(grid, expected);
+
+        // Two solutions are given back, telling a cell apart
+        let grid = Grid::parse(vec!["- - - -"; 4].into_iter()).unwrap();
+        let err = grid.clone().solve_with(&config).unwrap_err();
+        let SolveError::MultipleSolutions(solutions) = &err else {
+            panic!("the grid has several solutions");
+        };
+
+        let idx = err.ambiguous_cell().unwrap();
+        assert_ne!(solutions[0][idx], solutions[1][idx]);
+        assert!(solutions
+            .iter()
+            .all(|solution| grid.verify_solution(solution).is_ok()));
+
+        // Deductions assuming a single solution are left out
+        let config = config.assume_unique(true);
+        assert!(grid.clone().solve_with(&config).is_err());
+    }
+
+    #[test]
+    fn dead_ends() {
+        let input = vec!["0 - - 0", "0 - - 0", "- - - -", "- - - -"];
+        let grid = Grid::parse(input.into_iter()).unwrap();
+
+        let Err(SolveError::NoSolution(Some(dead_end))) = grid.clone().solve() else {
+            panic!("the grid has no solution");
+        };
+
+        // The cells filled up to the contradiction break no rule yet
+        let partial = dead_end.grid();
+        assert!(partial.empty_cells().count() < grid.empty_cells().count());
+        assert!(partial.is_valid().is_ok());
+        assert!(partial[dead_end.index()].is_none());
+        assert!(partial
+            .givens()
+            .all(|(idx, cell)| partial[idx] == Some(cell)));
+
+        // Guesses take the search further than deductions alone
+        let input = vec![
+            "- 0 - - - 1",
+            "- - 1 - - 1",
+            "- - - - 0 -",
+            "- - - - - -",
+            "- - - - - -",
+            "- - - - 0 -",
+        ];
+        let mut deduced = Grid::parse(input.into_iter()).unwrap();
+        let config = SolverConfig::new().max_depth(0);
+        assert!(deduced.solve_with(&config).is_err());
+
+        let Err(SolveError::NoSolution(Some(dead_end))) = deduced.clone().solve() else {
+            panic!("the grid has no solution");
+        };
+
+        let partial = dead_end.grid();
+        assert!(partial.empty_cells().count() < deduced.empty_cells().count());
+        assert!(partial[dead_end.index()].is_none());
+        assert_eq!(
+            SolveError::NoSolution(Some(dead_end.clone())).to_string(),
+            std::format!("error: grid has no solution, stuck at {}", dead_end)
+        );
+    }
+
+    #[test]
+    fn search_limits() {
+        // Nothing can be deduced on an empty grid
+        let grid = Grid::parse(vec!["- - - -"; 4].into_iter()).unwrap();
+
+        let mut limited = grid.clone();
+        let config = SolverConfig::new().max_depth(0);
+        assert!(matches!(
+            limited.solve_with(&config),
+            Err(SolveError::LimitExceeded(Limit::Depth(0)))
+        ));
+
+        let mut limited = grid.clone();
+        let config = SolverConfig::new().max_nodes(0);
+        assert!(matches!(
+            limited.solve_with(&config),
+            Err(SolveError::LimitExceeded(Limit::Nodes(0)))
+        ));
+
+        // Guesses are undone when giving up
+        let mut limited = grid.clone();
+        let config = SolverConfig::new().max_time(Duration::ZERO);
+        assert!(matches!(
+            limited.solve_with(&config),
+            Err(SolveError::LimitExceeded(Limit::Time(Duration::ZERO)))
+        ));
+        assert_eq!(limited, grid);
+
+        let mut solved = grid.clone();
+        let config = SolverConfig::new().max_depth(16).max_nodes(1000);
+        assert!(solved.solve_with(&config).is_ok());
+        assert!(grid.verify_solution(&solved).is_ok());
+    }
+
+    #[test]
+    fn seeded_search() {
+        let grid = Grid::parse(vec!["- - - - - -"; 6].into_iter()).unwrap();
+        let solve = |seed| {
+            let mut grid = grid.clone();
+            grid.solve_with(&SolverConfig::new().seed(seed)).unwrap();
+            grid
+        };
+
+        assert_eq!(solve(1), solve(1));
+        assert!((2..10).any(|seed| solve(seed) != solve(1)));
+        assert!(grid.verify_solution(&solve(1)).is_ok());
+    }
+
+    #[test]
+    fn smallest_solution() {
+        let solve = |grid: &Grid, config: SolverConfig| {
+            let mut grid = grid.clone();
+            grid.solve_with(&config.smallest_solution(true)).unwrap();
+            grid
+        };
+
+        // Each line takes the smallest values left by the lines above
+        let grid = Grid::parse(vec!["- - - -"; 4].into_iter()).unwrap();
+        let smallest = Grid::parse(vec!["0 0 1 1", "0 1 0 1", "1 0 1 0", "1 1 0 0"].into_iter());
+        assert_eq!(solve(&grid, SolverConfig::new()), smallest.unwrap());
+
+        // Neither the seed nor the guess strategy change the solution
+        let grid = Grid::parse(vec!["- - - - - -"; 6].into_iter()).unwrap();
+        let first = solve(&grid, SolverConfig::new());
+        let strategy = SolverConfig::new().guess_strategy(GuessStrategy::MostConstrained);
+        assert_eq!(solve(&grid, strategy), first);
+        assert!((1..10).all(|seed| solve(&grid, SolverConfig::new().seed(seed)) == first));
+        assert_eq!(solve(&grid, SolverConfig::new().assume_unique(true)), first);
+        assert!(grid.verify_solution(&first).is_ok());
+    }
+
+    #[test]
+    fn solve_stats() {
+        let input = vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"];
+        let mut grid = Grid::parse(input.into_iter()).unwrap();
+
+        let stats = grid.solve_with_stats(&SolverConfig::new()).unwrap();
+        let filled: usize = Technique::iter()
+            .map(|technique| stats.filled(technique))
+            .sum();
+
+        assert_eq!(filled, 9);
+        assert_eq!(stats.guesses(), 0);
+        assert_eq!(stats.backtracks(), 0);
+        assert_eq!(stats.max_depth(), 0);
+        assert!(stats.propagations() > 0);
+        assert!(stats
+            .techniques()
+            .any(|technique| technique == Technique::Adjacency));
+
+        // Nothing can be deduced on an empty grid
+        let mut grid = Grid::parse(vec!["- - - -"; 4].into_iter()).unwrap();
+        let stats = grid.solve_with_stats(&SolverConfig::new()).unwrap();
+        assert!(stats.guesses() > 0);
+        assert!(stats.max_depth() > 0);
+
+        let mut total = stats.clone();
+        total.merge(&stats);
+        assert_eq!(total.guesses(), 2 * stats.guesses());
+        assert_eq!(total.max_depth(), stats.max_depth());
+    }
+
+    #[test]
+    fn completions() {
+        let mut input = vec!["- - - - - - - - - - - -"; 12];
+        input[0] = "0 - - 1 - - 0 - - - 0 -";
+        let mut grid = Grid::parse(input.into_iter()).unwrap();
+
+        // Both values are missing three times or more, yet every completion
+        // of the first line ends with a one
+        let config = SolverConfig::new().techniques([Technique::Completion]);
+        let mut solver = Solver::new(&mut grid, &config);
+        solver.deduce().unwrap();
+        assert_eq!(solver.into_stats().filled(Technique::Completion), 1);
+        assert_eq!(grid[(0, 11)], Some(Cell::One));
+    }
+
+    #[test]
+    fn unique_completions() {
+        let input = vec!["0 1 1 0", "- - 1 0", "- - - -", "- - - -"];
+        let grid = Grid::parse(input.into_iter()).unwrap();
+
+        // The second line would repeat the first one with a zero first
+        let steps = grid.explain().unwrap();
+        let deduction = steps.iter().find_map(|step| match step {
+            Step::Deduction(deduction) if deduction.technique() == Technique::UniqueCompletion => {
+                Some(deduction)
+            }
+            _ => None,
+        });
+
+        let deduction = deduction.unwrap();
+        assert_eq!(
+            (deduction.index(), deduction.value()),
+            (Index(1, 0), Cell::One)
+        );
+        assert_eq!(
+            deduction.premises(),
+            (0..4).map(|j| Index(0, j)).collect::<Vec<_>>()
+        );
+
+        let config = SolverConfig::new().techniques([Technique::UniqueCompletion]);
+        let stats = grid.clone().solve_with_stats(&config).unwrap();
+        assert!(stats.filled(Technique::UniqueCompletion) > 0);
+    }
+
+    #[test]
+    fn crossings() {
+        let mut input = vec!["- - - - - -"; 6];
+        input[0] = "0 - - - - -";
+        input[5] = "0 - - - - -";
+        let mut grid = Grid::parse(input.into_iter()).unwrap();
+
+        // A zero next to the first one of the column would leave three ones
+        // between the zeros, which its lines see when trying their completions
+        let config = SolverConfig::new().techniques([Technique::Crossing]);
+        let mut solver = Solver::new(&mut grid, &config);
+        solver.deduce().unwrap();
+        assert_eq!(solver.into_stats().filled(Technique::Crossing), 2);
+        assert_eq!(
+            (grid[(1, 0)], grid[(4, 0)]),
+            (Some(Cell::One), Some(Cell::One))
+        );
+    }
+
+    #[test]
+    fn lookahead() {
+        let input = vec![
+            "0 - 0 - 1 -",
+            "- - 1 1 - -",
+            "1 - - - 1 -",
+            "- 1 - 0 - -",
+            "- - 0 - - 1",
+            "- - - - 1 -",
+        ];
+        let grid = Grid::parse(input.into_iter()).unwrap();
+
+        // Every other technique stalls before the grid is complete
+        let config = SolverConfig::new().max_depth(0);
+        assert!(grid.clone().solve_with(&config).is_err());
+
+        // Trying values rules some out without guessing
+        let config = SolverConfig::new().lookahead(1).max_depth(0);
+        let stats = grid.clone().solve_with_stats(&config).unwrap();
+        assert!(stats.filled(Technique::Lookahead) > 0);
+        assert_eq!(stats.guesses(), 0);
+
+        // Unless the technique is left out
+        let techniques = Technique::iter().filter(|technique| *technique != Technique::Lookahead);
+        let config = config.techniques(techniques);
+        assert!(grid.clone().solve_with(&config).is_err());
+    }
+
+    #[test]
+    fn deadly_patterns() {
+        let input = vec![
+            "- - 1 - 0 - - - - 0",
+            "- - - - - 1 1 - - -",
+            "- 1 - - - - - 0 - -",
+            "- - - - - - - - - -",
+            "1 - - - - - - - - -",
+            "- - - - - - 0 - 1 -",
+            "- - - 0 - - - - - -",
+            "- - 1 - - 1 1 - - -",
+            "- - - 0 - - - - 0 0",
+            "- - - - - - - 0 0 -",
+        ];
+        let grid = Grid::parse(input.into_iter()).unwrap();
+
+        // Only used when asked to, as grids may have several solutions
+        let mut solution = grid.clone();
+        let stats = solution.solve_with_stats(&SolverConfig::new()).unwrap();
+        assert!(!stats.assumes_uniqueness());
+
+        // The puzzle has a single solution, which the assumption keeps
+        let config = SolverConfig::new()
+            .techniques([Technique::Adjacency, Technique::Balance])
+            .assume_unique(true);
+        let mut solved = grid.clone();
+        let stats = solved.solve_with_stats(&config).unwrap();
+        assert!(stats.filled(Technique::DeadlyPattern) > 0);
+        assert!(stats.assumes_uniqueness());
+        assert_eq!(solved, solution);
+
+        // Explanations tell which cells would be swapped
+        let mut solved = grid.clone();
+        let mut solver = Solver::new(&mut solved, &config).with_log();
+        solver.search().unwrap();
+        let deduction = solver
+            .into_log()
+            .into_iter()
+            .find_map(|step| match step {
+                Step::Deduction(deduction) if deduction.technique() == Technique::DeadlyPattern => {
+                    Some(deduction)
+                }
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(deduction.premises().len(), 3);
+        assert!(deduction
+            .explain()
+            .ends_with("give the puzzle a second solution"));
+    }
+
+    #[test]
+    fn restricted_techniques() {
+        let input = vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"];
+        let grid = Grid::parse(input.into_iter()).unwrap();
+
+        // Only the allowed deductions are made
+        let config = SolverConfig::new()
+            .techniques([Technique::Adjacency, Technique::Balance])
+            .max_depth(0);
+        let stats = grid.clone().solve_with_stats(&config).unwrap();
+        assert!(stats
+            .techniques()
+            .all(|technique| technique != Technique::Completion));
+
+        let config = SolverConfig::new()
+            .techniques([Technique::Uniqueness])
+            .max_depth(0);
+        assert!(matches!(
+            grid.clone().solve_with(&config),
+            Err(SolveError::LimitExceeded(Limit::Depth(0)))
+        ));
+
+        // Guesses make up for missing techniques
+        let mut solved = grid.clone();
+        let config = SolverConfig::new().techniques([]);
+        assert!(solved.solve_with(&config).is_ok());
+        assert!(grid.verify_solution(&solved).is_ok());
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn tracing_events() {
+        use std::fmt;
+        use std::sync::Mutex;
+        use tracing::field::{Field, Visit};
+        use tracing::span::{Attributes, Id, Record};
+        use tracing::{Event, Metadata, Subscriber};
+
+        // Keeps span names, and the fields of every event
+        #[derive(Default)]
+        struct Collector {
+            spans: Mutex<Vec<&'static str>>,
+            events: Mutex<Vec<BTreeMap<&'static str, String>>>,
+        }
+
+        struct Fields<'a>(&'a mut BTreeMap<&'static str, String>);
+
+        impl Visit for Fields<'_> {
+            fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+                self.0.insert(field.name(), format!("{:?}", value));
+            }
+        }
+
+        impl Subscriber for &'static Collector {
+            fn enabled(&self, _: &Metadata<'_>) -> bool {
+                true
+            }
+
+            fn new_span(&self, span: &Attributes<'_>) -> Id {
+                let mut spans = self.spans.lock().unwrap();
+                spans.push(span.metadata().name());
+                Id::from_u64(spans.len() as u64)
+            }
+
+            fn record(&self, _: &Id, _: &Record<'_>) {}
+
+            fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+            fn event(&self, event: &Event<'_>) {
+                let mut fields = BTreeMap::new();
+                event.record(&mut Fields(&mut fields));
+                self.events.lock().unwrap().push(fields);
+            }
+
+            fn enter(&self, _: &Id) {}
+
+            fn exit(&self, _: &Id) {}
+        }
+
+        let collector: &'static Collector = Box::leak(Box::default());
+        let input = vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"];
+        let mut grid = Grid::parse(input.into_iter()).unwrap();
+
+        let stats = tracing::subscriber::with_default(collector, || {
+            grid.solve_with_stats(&SolverConfig::new()).unwrap()
+        });
+
+        let spans = collector.spans.lock().unwrap();
+        for name in ["solve", "bruteforce", "fill_candidates", "fill_heuristics"] {
+            assert!(spans.contains(&name), "no {} span", name);
+        }
+
+        // Counters add up to what the statistics tell
+        let events = collector.events.lock().unwrap();
+        for technique in stats.techniques() {
+            let counter = events.iter().find(|fields| {
+                fields["message"] == "cells filled" && fields["technique"] == technique.to_string()
+            });
+
+            assert_eq!(
+                counter.map(|fields| fields["filled"].clone()),
+                Some(stats.filled(technique).to_string())
+            );
+        }
+    }
+}
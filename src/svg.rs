@@ -0,0 +1,318 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+use crate::cell::{Cell, CellState};
+use crate::deduction::Step;
+use crate::error::SolveError;
+use crate::grid::Grid;
+use crate::html::escape;
+use crate::index::Index;
+
+#[derive(Clone, Debug)]
+pub struct SvgStyle {
+    cell_size: usize,
+    background: String,
+    border: String,
+    given: String,
+    solved: String,
+    guessed: String,
+}
+
+impl SvgStyle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cell_size(mut self, size: usize) -> Self {
+        assert!(size > 0, "cells need a size");
+
+        self.cell_size = size;
+        self
+    }
+
+    pub fn background<S>(mut self, color: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.background = color.into();
+        self
+    }
+
+    pub fn border<S>(mut self, color: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.border = color.into();
+        self
+    }
+
+    pub fn given<S>(mut self, color: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.given = color.into();
+        self
+    }
+
+    pub fn solved<S>(mut self, color: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.solved = color.into();
+        self
+    }
+
+    // Color of guesses in animations
+    pub fn guessed<S>(mut self, color: S) -> Self
+    where
+        S: Into<String>,
+    {
+        self.guessed = color.into();
+        self
+    }
+}
+
+impl Default for SvgStyle {
+    fn default() -> Self {
+        Self {
+            cell_size: 32,
+            background: String::from("white"),
+            border: String::from("black"),
+            given: String::from("black"),
+            solved: String::from("steelblue"),
+            guessed: String::from("darkorange"),
+        }
+    }
+}
+
+impl Grid {
+    pub fn to_svg(&self, style: &SvgStyle) -> String {
+        let mut svg = self.svg_board(style, false);
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    // The givens first, then each step of the solver in turn. Guesses have
+    // their own color, and the cells they implied vanish on contradictions.
+    pub fn to_animated_svg(&self, style: &SvgStyle, delay: Duration) -> Result<String, SolveError> {
+        let steps = self.explain()?;
+        let mut svg = self.svg_board(style, true);
+        let (width, height) = (
+            self.width() * style.cell_size,
+            self.height() * style.cell_size,
+        );
+
+        // Cells shown so far, grouped by the guess they depend on
+        let mut frames: Vec<(Option<Index>, Vec<usize>)> = vec![(None, Vec::new())];
+        let mut cells: Vec<(Index, Cell, &str, f64, Option<f64>)> = Vec::new();
+        let mut contradictions = Vec::new();
+
+        for (k, step) in steps.iter().enumerate() {
+            let time = delay.as_secs_f64() * (k + 1) as f64;
+
+            let (idx, cell, color) = match step {
+                Step::Deduction(deduction) => {
+                    (deduction.index(), deduction.value(), style.solved.as_str())
+                }
+                Step::Guess(idx, cell) => {
+                    // Guessing again a cell means going back to its frame
+                    while frames[1..].iter().any(|(guess, _)| *guess == Some(*idx)) {
+                        for k in frames.pop().map(|(_, shown)| shown).unwrap_or_default() {
+                            cells[k].4 = Some(time);
+                        }
+                    }
+
+                    frames.push((Some(*idx), Vec::new()));
+                    (*idx, *cell, style.guessed.as_str())
+                }
+                Step::Backtrack => {
+                    if frames.len() > 1 {
+                        for k in frames.pop().map(|(_, shown)| shown).unwrap_or_default() {
+                            cells[k].4 = Some(time);
+                        }
+                    }
+
+                    contradictions.push(time);
+                    continue;
+                }
+            };
+
+            if let Some((_, shown)) = frames.last_mut() {
+                shown.push(cells.len());
+            }
+            cells.push((idx, cell, color, time, None));
+        }
+
+        for (idx, cell, color, shown, hidden) in cells {
+            let size = style.cell_size;
+            let (x, y) = (idx.1 * size, idx.0 * size);
+
+            // Writing to a string cannot fail
+            let _ = write!(
+                svg,
+                r#"<text x="{}" y="{}" font-family="sans-serif" font-size="{}" fill="{}" text-anchor="middle" dominant-baseline="central" opacity="0">{}"#,
+                x + size / 2,
+                y + size / 2,
+                size * 2 / 3,
+                color,
+                cell
+            );
+            let _ = write!(
+                svg,
+                r#"<set attributeName="opacity" to="1" begin="{:.3}s" fill="freeze"/>"#,
+                shown
+            );
+            if let Some(hidden) = hidden {
+                let _ = write!(
+                    svg,
+                    r#"<set attributeName="opacity" to="0" begin="{:.3}s" fill="freeze"/>"#,
+                    hidden
+                );
+            }
+            svg.push_str("</text>\n");
+        }
+
+        // The whole grid flashes on contradictions
+        for time in contradictions {
+            let _ = writeln!(
+                svg,
+                r#"<rect width="{}" height="{}" fill="red" opacity="0"><set attributeName="opacity" to="0.2" begin="{:.3}s" dur="{:.3}s"/></rect>"#,
+                width,
+                height,
+                time,
+                delay.as_secs_f64()
+            );
+        }
+
+        svg.push_str("</svg>\n");
+        Ok(svg)
+    }
+
+    // Opening tag, lines and cells, optionally only the givens
+    fn svg_board(&self, style: &SvgStyle, givens: bool) -> String {
+        let size = style.cell_size;
+        let (width, height) = (self.width() * size, self.height() * size);
+        let mut svg = String::new();
+
+        // Writing to a string cannot fail
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+            width, height
+        );
+        if let Some(title) = self.metadata().title() {
+            let _ = writeln!(svg, "<title>{}</title>", escape(title));
+        }
+        let _ = writeln!(
+            svg,
+            r#"<rect width="{}" height="{}" fill="{}" stroke="{}" stroke-width="2"/>"#,
+            width, height, style.background, style.border
+        );
+
+        for i in self.lines() {
+            for j in self.columns() {
+                let (x, y) = (j * size, i * size);
+
+                let _ = writeln!(
+                    svg,
+                    r#"<rect x="{}" y="{}" width="{2}" height="{2}" fill="none" stroke="gray"/>"#,
+                    x, y, size
+                );
+
+                let Some(cell) = self[(i, j)] else {
+                    continue;
+                };
+
+                // Givens stand out from the cells filled by the solver
+                let (weight, color) = match self.state(Index(i, j)) {
+                    Some(CellState::Given) => ("bold", &style.given),
+                    _ if givens => continue,
+                    _ => ("normal", &style.solved),
+                };
+
+                let _ = writeln!(
+                    svg,
+                    r#"<text x="{}" y="{}" font-family="sans-serif" font-size="{}" font-weight="{}" fill="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+                    x + size / 2,
+                    y + size / 2,
+                    size * 2 / 3,
+                    weight,
+                    color,
+                    match cell {
+                        Cell::Zero => 0,
+                        Cell::One => 1,
+                    }
+                );
+            }
+        }
+
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export() {
+        let input = vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"];
+        let mut grid = Grid::parse(input.into_iter()).unwrap();
+        grid.solve().unwrap();
+
+        let svg = grid.to_svg(&SvgStyle::default());
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches("<text").count(), 16);
+        assert_eq!(svg.matches(r#"font-weight="bold""#).count(), 7);
+        assert_eq!(svg.matches(r#"fill="steelblue""#).count(), 9);
+    }
+
+    #[test]
+    fn animation() {
+        let grid = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+        let svg = grid
+            .unwrap()
+            .to_animated_svg(&SvgStyle::default(), Duration::from_millis(500));
+        let svg = svg.unwrap();
+
+        assert!(svg.starts_with("<svg") && svg.ends_with("</svg>\n"));
+        assert_eq!(svg.matches(r#"font-weight="bold""#).count(), 7);
+        assert_eq!(svg.matches(r#"opacity="0">"#).count(), 9);
+        assert!(svg.contains(r#"begin="0.500s""#));
+        assert!(svg.contains(r#"begin="4.500s""#));
+
+        // Guesses are undone on contradictions
+        let grid = Grid::parse(vec!["- - - -"; 4].into_iter()).unwrap();
+        let steps = grid.explain().unwrap();
+        let guesses = steps
+            .iter()
+            .filter(|step| matches!(step, Step::Guess(..)))
+            .count();
+
+        let svg = grid.to_animated_svg(&SvgStyle::default(), Duration::from_secs(1));
+        let svg = svg.unwrap();
+        assert_eq!(svg.matches(r#"fill="darkorange""#).count(), guesses);
+        assert_eq!(
+            svg.matches(r#"fill="red""#).count(),
+            steps
+                .iter()
+                .filter(|step| **step == Step::Backtrack)
+                .count()
+        );
+    }
+
+    #[test]
+    fn style() {
+        let grid = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+        let style = SvgStyle::new()
+            .cell_size(10)
+            .background("#eee")
+            .given("red");
+
+        let svg = grid.unwrap().to_svg(&style);
+        assert!(svg.contains(r#"width="40" height="40""#));
+        assert!(svg.contains(r##"fill="#eee""##));
+        assert_eq!(svg.matches(r#"fill="red""#).count(), 7);
+    }
+}
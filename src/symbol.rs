@@ -0,0 +1,537 @@
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use core::mem;
+use core::ops;
+
+use crate::error::{ParseError, Rule, SolveError, Violation};
+use crate::index::{Index, Lane};
+use crate::parse::ParseOptions;
+use crate::solver::{Budget, SolveStats, SolverConfig};
+
+// Symbols are written as digits, which bounds the size of alphabets
+const MAX_SYMBOLS: u8 = 10;
+
+// A value among the symbols of an alphabet, counting from zero
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Symbol(u8);
+
+// Grids over alphabets of more than two symbols, such as the three of
+// Trinairo puzzles: each lane holds as many cells of every symbol, no three
+// identical cells next to each other, and no two identical lanes. Only these
+// rules are known, with their own deductions: the techniques and rules of
+// solver configurations are left to binary grids, only their limits apply
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SymbolGrid {
+    symbols: u8,
+    width: usize,
+    height: usize,
+    cells: Vec<Option<Symbol>>,
+}
+
+impl Symbol {
+    pub fn new(value: u8) -> Self {
+        assert!(value < MAX_SYMBOLS, "symbols are single digits");
+        Self(value)
+    }
+
+    pub fn value(&self) -> u8 {
+        self.0
+    }
+}
+
+impl SymbolGrid {
+    pub fn parse<I, S>(lines: I, symbols: u8) -> Result<SymbolGrid, ParseError>
+    where
+        I: Iterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self::parse_with(lines, symbols, &ParseOptions::default())
+    }
+
+    // Only the empty characters and comments of the options are used, symbols
+    // being digits
+    pub fn parse_with<I, S>(
+        lines: I,
+        symbols: u8,
+        options: &ParseOptions,
+    ) -> Result<SymbolGrid, ParseError>
+    where
+        I: Iterator<Item = S>,
+        S: AsRef<str>,
+    {
+        assert!(
+            (2..=MAX_SYMBOLS).contains(&symbols),
+            "alphabets have 2 to 10 symbols"
+        );
+
+        let mut lines_of_cells: Vec<Vec<Option<Symbol>>> = Vec::new();
+
+        for (n, line) in lines.enumerate().map(|(n, line)| (n + 1, line)) {
+            let content = options.strip_comment(line.as_ref());
+            let mut cells = Vec::new();
+
+            for (p, c) in content
+                .chars()
+                .enumerate()
+                .filter(|(_, c)| !c.is_whitespace())
+            {
+                let cell = match c.to_digit(10) {
+                    Some(value) if value < u32::from(symbols) => Some(Symbol(value as u8)),
+                    _ if options.is_empty_char(c) => None,
+                    _ => return Err(ParseError::invalid_char(c).at(n, p + 1)),
+                };
+
+                cells.push(cell);
+            }
+
+            if cells.is_empty() {
+                continue;
+            }
+
+            if let Some(first) = lines_of_cells.first() {
+                if cells.len() != first.len() {
+                    return Err(ParseError::WidthMismatch {
+                        line: n,
+                        expected: first.len(),
+                        found: cells.len(),
+                    });
+                }
+            }
+
+            lines_of_cells.push(cells);
+        }
+
+        let height = lines_of_cells.len();
+        let width = lines_of_cells.first().map_or(0, Vec::len);
+
+        if height == 0 {
+            return Err(ParseError::EmptyGrid);
+        }
+
+        // Lanes hold as many cells of each symbol
+        if !width.is_multiple_of(symbols.into()) || !height.is_multiple_of(symbols.into()) {
+            return Err(ParseError::RatioMismatch { width, height });
+        }
+
+        let grid = SymbolGrid {
+            symbols,
+            width,
+            height,
+            cells: lines_of_cells.into_iter().flatten().collect(),
+        };
+
+        grid.is_valid().map_err(ParseError::InvalidGrid)?;
+        Ok(grid)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    // Number of symbols of the alphabet
+    pub fn symbols(&self) -> u8 {
+        self.symbols
+    }
+
+    pub fn is_valid(&self) -> Result<(), Violation> {
+        let lanes = self.lanes();
+
+        for lane in &lanes {
+            let cells: Vec<_> = self.lane(*lane).collect();
+            let indexes = (0..cells.len()).map(|k| lane.index(k));
+
+            let triple = cells
+                .windows(3)
+                .position(|w| w[0].is_some() && w[0] == w[1] && w[1] == w[2]);
+
+            if let Some(k) = triple {
+                let run = indexes.skip(k).take(3).collect();
+                return Err(Violation::new(Rule::Adjacency, vec![*lane], run));
+            }
+
+            let quota = cells.len() / usize::from(self.symbols);
+
+            if let Some(s) = self.alphabet().find(|s| Self::count(&cells, *s) > quota) {
+                let excess = indexes.filter(|idx| self[*idx] == Some(s)).collect();
+                return Err(Violation::new(Rule::Balance, vec![*lane], excess));
+            }
+        }
+
+        // Only complete lanes can be identical
+        for (k, lane) in lanes.iter().enumerate() {
+            let cells: Vec<_> = self.lane(*lane).collect();
+
+            if let Some(other) = self.duplicate(*lane, &cells, &lanes[k + 1..]) {
+                let cells = [*lane, other]
+                    .into_iter()
+                    .flat_map(|lane| (0..self.lane_len(lane)).map(move |k| lane.index(k)));
+
+                let lanes = vec![*lane, other];
+                return Err(Violation::new(Rule::Uniqueness, lanes, cells.collect()));
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn solve(&mut self) -> Result<(), SolveError> {
+        self.solve_with_stats(&SolverConfig::default()).map(|_| ())
+    }
+
+    // Search within the node, depth and time limits of a configuration
+    pub fn solve_with_stats(&mut self, config: &SolverConfig) -> Result<SolveStats, SolveError> {
+        self.is_valid().map_err(SolveError::InvalidGrid)?;
+
+        let mut grid = self.clone();
+        let mut budget = Budget::new(config);
+
+        if grid.explore(1, 0, &mut Vec::new(), &mut budget)? == 0 {
+            return Err(SolveError::NoSolution(None));
+        }
+
+        *self = grid;
+        Ok(budget.into_stats())
+    }
+
+    pub fn count_solutions(&self, limit: usize) -> Result<usize, SolveError> {
+        self.count_solutions_with(limit, &SolverConfig::default())
+    }
+
+    pub fn count_solutions_with(
+        &self,
+        limit: usize,
+        config: &SolverConfig,
+    ) -> Result<usize, SolveError> {
+        self.is_valid().map_err(SolveError::InvalidGrid)?;
+
+        let mut budget = Budget::new(config);
+        self.clone().explore(limit, 0, &mut Vec::new(), &mut budget)
+    }
+
+    fn alphabet(&self) -> impl Iterator<Item = Symbol> {
+        (0..self.symbols).map(Symbol)
+    }
+
+    fn lanes(&self) -> Vec<Lane> {
+        let lines = (0..self.height).map(Lane::Line);
+        lines.chain((0..self.width).map(Lane::Column)).collect()
+    }
+
+    fn lane_len(&self, lane: Lane) -> usize {
+        match lane {
+            Lane::Line(_) => self.width,
+            Lane::Column(_) => self.height,
+        }
+    }
+
+    fn lane(&self, lane: Lane) -> impl Iterator<Item = Option<Symbol>> + '_ {
+        (0..self.lane_len(lane)).map(move |k| self[lane.index(k)])
+    }
+
+    fn count(cells: &[Option<Symbol>], symbol: Symbol) -> usize {
+        cells.iter().filter(|cell| **cell == Some(symbol)).count()
+    }
+
+    // One of the other lanes that the complete cells of a lane are the same as
+    fn duplicate(&self, lane: Lane, cells: &[Option<Symbol>], others: &[Lane]) -> Option<Lane> {
+        if cells.iter().any(Option::is_none) {
+            return None;
+        }
+
+        others
+            .iter()
+            .filter(|other| **other != lane)
+            .filter(|other| mem::discriminant(*other) == mem::discriminant(&lane))
+            .find(|other| self.lane(**other).eq(cells.iter().copied()))
+            .copied()
+    }
+
+    fn check_move(&self, idx: Index, symbol: Symbol) -> Result<(), Rule> {
+        for lane in [Lane::Line(idx.0), Lane::Column(idx.1)] {
+            let k = match lane {
+                Lane::Line(_) => idx.1,
+                Lane::Column(_) => idx.0,
+            };
+
+            let mut cells: Vec<_> = self.lane(lane).collect();
+            cells[k] = Some(symbol);
+
+            // Only the triples going through the cell may be new
+            let start = k.saturating_sub(2);
+            let end = (k + 3).min(cells.len());
+
+            if cells[start..end]
+                .windows(3)
+                .any(|w| w.iter().all(|cell| *cell == Some(symbol)))
+            {
+                return Err(Rule::Adjacency);
+            }
+
+            if Self::count(&cells, symbol) > cells.len() / usize::from(self.symbols) {
+                return Err(Rule::Balance);
+            }
+
+            if self.duplicate(lane, &cells, &self.lanes()).is_some() {
+                return Err(Rule::Uniqueness);
+            }
+        }
+
+        Ok(())
+    }
+
+    // Fill the cells left with a single symbol, and the symbols left with
+    // just enough cells in a lane, keeping the filled cells on the trail
+    fn propagate(&mut self, trail: &mut Vec<usize>) -> Result<(), Rule> {
+        loop {
+            let mut changed = false;
+
+            let empty: Vec<_> = (0..self.cells.len())
+                .filter(|k| self.cells[*k].is_none())
+                .collect();
+
+            for offset in empty {
+                let idx = Index(offset / self.width, offset % self.width);
+                let mut rule = Rule::Balance;
+                let symbols: Vec<_> = self
+                    .alphabet()
+                    .filter(|s| match self.check_move(idx, *s) {
+                        Ok(()) => true,
+                        Err(broken) => {
+                            rule = broken;
+                            false
+                        }
+                    })
+                    .collect();
+
+                match symbols.as_slice() {
+                    [] => return Err(rule),
+                    [symbol] => {
+                        self.cells[offset] = Some(*symbol);
+                        trail.push(offset);
+                        changed = true;
+                    }
+                    _ => {}
+                }
+            }
+
+            for lane in self.lanes() {
+                let quota = self.lane_len(lane) / usize::from(self.symbols);
+
+                for symbol in self.alphabet() {
+                    let cells: Vec<_> = self.lane(lane).collect();
+                    let missing = quota - Self::count(&cells, symbol);
+                    let places: Vec<_> = (0..cells.len())
+                        .map(|k| lane.index(k))
+                        .filter(|idx| self[*idx].is_none())
+                        .filter(|idx| self.check_move(*idx, symbol).is_ok())
+                        .collect();
+
+                    if places.len() < missing {
+                        return Err(Rule::Balance);
+                    }
+
+                    if missing > 0 && places.len() == missing {
+                        for idx in places {
+                            let offset = self.offset(idx);
+                            self.cells[offset] = Some(symbol);
+                            trail.push(offset);
+                        }
+
+                        changed = true;
+                    }
+                }
+            }
+
+            if !changed {
+                return Ok(());
+            }
+        }
+    }
+
+    // Guess on the cell with the fewest symbols left, until enough solutions
+    // are found: the grid is left on the last one, and guesses are undone
+    // with the trail of the cells they filled
+    fn explore(
+        &mut self,
+        limit: usize,
+        depth: usize,
+        trail: &mut Vec<usize>,
+        budget: &mut Budget,
+    ) -> Result<usize, SolveError> {
+        let mark = trail.len();
+        budget.propagate();
+
+        if limit == 0 || self.propagate(trail).is_err() || self.is_valid().is_err() {
+            self.undo(trail, mark);
+            return Ok(0);
+        }
+
+        let guess = (0..self.cells.len())
+            .filter(|k| self.cells[*k].is_none())
+            .map(|offset| {
+                let idx = Index(offset / self.width, offset % self.width);
+                let symbols: Vec<_> = self
+                    .alphabet()
+                    .filter(|s| self.check_move(idx, *s).is_ok())
+                    .collect();
+
+                (offset, symbols)
+            })
+            .min_by_key(|(_, symbols)| symbols.len());
+
+        let Some((offset, symbols)) = guess else {
+            return Ok(1);
+        };
+
+        let mut found = 0;
+
+        for symbol in symbols {
+            budget.guess(depth)?;
+
+            let guessed = trail.len();
+            self.cells[offset] = Some(symbol);
+            trail.push(offset);
+
+            found += self.explore(limit - found, depth + 1, trail, budget)?;
+
+            if found >= limit {
+                return Ok(found);
+            }
+
+            self.undo(trail, guessed);
+            budget.backtrack();
+        }
+
+        self.undo(trail, mark);
+        Ok(found)
+    }
+
+    // Empty the cells filled since the mark
+    fn undo(&mut self, trail: &mut Vec<usize>, mark: usize) {
+        for offset in trail.drain(mark..) {
+            self.cells[offset] = None;
+        }
+    }
+
+    fn offset(&self, idx: Index) -> usize {
+        idx.0 * self.width + idx.1
+    }
+}
+
+impl<I> ops::Index<I> for SymbolGrid
+where
+    I: Into<Index>,
+{
+    type Output = Option<Symbol>;
+
+    fn index(&self, idx: I) -> &Self::Output {
+        &self.cells[self.offset(idx.into())]
+    }
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "{}", self.0)
+    }
+}
+
+impl fmt::Display for SymbolGrid {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        for i in 0..self.height {
+            if i > 0 {
+                writeln!(fmt)?;
+            }
+
+            for j in 0..self.width {
+                if j > 0 {
+                    write!(fmt, " ")?;
+                }
+
+                match self[(i, j)] {
+                    Some(symbol) => write!(fmt, "{}", symbol)?,
+                    None => write!(fmt, "-")?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Limit;
+
+    #[test]
+    fn ternary() {
+        // Lanes of three cells hold each symbol once, as in Latin squares
+        let grid = SymbolGrid::parse(["- - -"; 3].iter(), 3).unwrap();
+        assert_eq!(grid.count_solutions(usize::MAX).unwrap(), 12);
+
+        let input = [
+            "0 - - - - 2",
+            "- - 1 - - -",
+            "- 2 - - - -",
+            "- - - - 0 -",
+            "- - - 1 - -",
+            "1 - - - - 0",
+        ];
+        let grid = SymbolGrid::parse(input.iter(), 3).unwrap();
+        assert_eq!(grid.to_string().lines().next(), Some("0 - - - - 2"));
+
+        let mut solution = grid.clone();
+        solution.solve().unwrap();
+        assert!(solution.is_valid().is_ok());
+        assert!(solution.cells.iter().all(Option::is_some));
+        assert_eq!(solution[(0, 0)], Some(Symbol::new(0)));
+
+        assert!(matches!(
+            SymbolGrid::parse(["0 0 0 - - -"; 6].iter(), 3),
+            Err(ParseError::InvalidGrid(violation)) if violation.rule() == Rule::Adjacency
+        ));
+        assert!(matches!(
+            SymbolGrid::parse(["- - - -"; 4].iter(), 3),
+            Err(ParseError::RatioMismatch { .. })
+        ));
+        assert!(matches!(
+            SymbolGrid::parse(["3 - -"; 3].iter(), 3),
+            Err(ParseError::InvalidChar {
+                found: '3',
+                position: Some((1, 1))
+            })
+        ));
+    }
+
+    #[test]
+    fn limits() {
+        let grid = SymbolGrid::parse(["- - - - - -"; 6].iter(), 3).unwrap();
+
+        let config = SolverConfig::new().max_nodes(2);
+        assert!(matches!(
+            grid.count_solutions_with(usize::MAX, &config),
+            Err(SolveError::LimitExceeded(Limit::Nodes(2)))
+        ));
+
+        // The grid is only changed once solved
+        let config = SolverConfig::new().max_depth(0);
+        let mut limited = grid.clone();
+        assert!(matches!(
+            limited.solve_with_stats(&config),
+            Err(SolveError::LimitExceeded(Limit::Depth(0)))
+        ));
+        assert_eq!(limited, grid);
+
+        let mut solution = grid.clone();
+        let stats = solution.solve_with_stats(&SolverConfig::new()).unwrap();
+        assert!(stats.guesses() > 0);
+        assert!(stats.max_depth() <= stats.guesses());
+        assert!(solution.is_valid().is_ok());
+        assert!(solution.cells.iter().all(Option::is_some));
+    }
+}
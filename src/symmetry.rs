@@ -0,0 +1,37 @@
+use alloc::vec;
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+use crate::index::Index;
+
+// Clue patterns that look the same after the matching transformation
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Symmetry {
+    #[default]
+    None,
+    Rotational,
+    Horizontal,
+    Vertical,
+}
+
+impl Symmetry {
+    // Cells that must be clues together to keep the symmetry
+    pub(crate) fn orbit(&self, idx: Index, width: usize, height: usize) -> Vec<Index> {
+        let Index(i, j) = idx;
+
+        let other = match self {
+            Self::None => idx,
+            Self::Rotational => Index(height - 1 - i, width - 1 - j),
+            Self::Horizontal => Index(i, width - 1 - j),
+            Self::Vertical => Index(height - 1 - i, j),
+        };
+
+        if other == idx {
+            vec![idx]
+        } else {
+            vec![idx, other]
+        }
+    }
+}
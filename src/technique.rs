@@ -0,0 +1,102 @@
+use core::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::Rule;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Technique {
+    // No more than two identical cells next to each other
+    Adjacency,
+    // A lane already holds all its cells of one value
+    Balance,
+    // A complete lane cannot be repeated
+    Uniqueness,
+    // Every completion of a lane gives the cell the same value
+    Completion,
+    // A sign ties the cell to a filled neighbor
+    Sign,
+    // The other completions of a lane would repeat a complete one
+    #[serde(rename = "unique-completion")]
+    UniqueCompletion,
+    // The lanes crossing a lane leave it only some of its completions
+    Crossing,
+    // The other value leads to a contradiction within a few steps
+    Lookahead,
+    // The other value would let four cells swap values, giving the puzzle
+    // a second solution
+    #[serde(rename = "deadly-pattern")]
+    DeadlyPattern,
+}
+
+impl Technique {
+    pub fn iter() -> impl Iterator<Item = Technique> {
+        [
+            Self::Adjacency,
+            Self::Balance,
+            Self::Uniqueness,
+            Self::Completion,
+            Self::Sign,
+            Self::UniqueCompletion,
+            Self::Crossing,
+            Self::Lookahead,
+            Self::DeadlyPattern,
+        ]
+        .into_iter()
+    }
+
+    // Effort of filling a cell with the technique, when rating a grid
+    pub(crate) fn weight(&self) -> usize {
+        match self {
+            Self::Adjacency | Self::Balance | Self::Sign => 1,
+            Self::Uniqueness => 2,
+            Self::Completion | Self::UniqueCompletion => 3,
+            Self::Crossing | Self::DeadlyPattern => 4,
+            Self::Lookahead => 5,
+        }
+    }
+
+    // Whether the technique only holds for puzzles with a single solution,
+    // which is why solvers leave it out unless asked to
+    pub fn assumes_uniqueness(&self) -> bool {
+        matches!(self, Self::DeadlyPattern)
+    }
+
+    // Rule broken when the technique leaves a cell without any value
+    pub(crate) fn rule(&self) -> Rule {
+        match self {
+            Self::Adjacency => Rule::Adjacency,
+            Self::Balance | Self::Completion | Self::Crossing | Self::Lookahead => Rule::Balance,
+            Self::Uniqueness | Self::UniqueCompletion | Self::DeadlyPattern => Rule::Uniqueness,
+            Self::Sign => Rule::Sign,
+        }
+    }
+}
+
+impl From<Rule> for Technique {
+    fn from(rule: Rule) -> Self {
+        match rule {
+            Rule::Adjacency => Self::Adjacency,
+            Rule::Balance => Self::Balance,
+            Rule::Uniqueness => Self::Uniqueness,
+            Rule::Sign => Self::Sign,
+        }
+    }
+}
+
+impl fmt::Display for Technique {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Adjacency => write!(fmt, "adjacency"),
+            Self::Balance => write!(fmt, "balance"),
+            Self::Uniqueness => write!(fmt, "uniqueness"),
+            Self::Completion => write!(fmt, "completion"),
+            Self::Sign => write!(fmt, "sign"),
+            Self::UniqueCompletion => write!(fmt, "unique-completion"),
+            Self::Crossing => write!(fmt, "crossing"),
+            Self::Lookahead => write!(fmt, "lookahead"),
+            Self::DeadlyPattern => write!(fmt, "deadly-pattern"),
+        }
+    }
+}
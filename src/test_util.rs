@@ -0,0 +1,103 @@
+use proptest::collection;
+use proptest::prelude::*;
+
+use crate::grid::Grid;
+use crate::index::Index;
+
+// Complete grids following every rule, with even sides of up to max_size
+pub fn complete_grids(max_size: usize) -> impl Strategy<Value = Grid> {
+    assert!(max_size >= 2, "grids are at least 2x2");
+
+    let side = 1..=max_size / 2;
+
+    (side.clone(), side, any::<u64>()).prop_filter_map(
+        "too narrow for its lanes to be different",
+        |(width, height, seed)| Grid::random_complete(2 * width, 2 * height, seed).ok(),
+    )
+}
+
+// Puzzles along with one of their solutions, which may not be the only one
+pub fn puzzles(max_size: usize) -> impl Strategy<Value = (Grid, Grid)> {
+    complete_grids(max_size).prop_flat_map(|solution| {
+        let size = solution.width() * solution.height();
+
+        collection::vec(any::<bool>(), size).prop_map(move |kept| {
+            let mut puzzle = Grid::with_size(solution.width(), solution.height());
+
+            // Kept cells are clues, as they would be in a parsed puzzle
+            for (k, _) in kept.iter().enumerate().filter(|(_, kept)| **kept) {
+                let idx = Index(k / solution.width(), k % solution.width());
+                puzzle.set_clue(idx, solution[idx]);
+            }
+
+            (puzzle, solution.clone())
+        })
+    })
+}
+
+// The text format reads back the grid it was written from, headers included
+pub fn assert_display_round_trip(grid: &Grid) {
+    let text = grid.to_string();
+    let parsed = Grid::parse(text.lines()).expect("the text format is read back");
+
+    assert_eq!(&parsed, grid, "text format:\n{}", text);
+    assert_eq!(parsed.metadata(), grid.metadata(), "text format:\n{}", text);
+}
+
+// Every format able to hold any grid gives back the same cells, Janko
+// files are left out as they only keep the clues
+pub fn assert_encoding_round_trips(grid: &Grid) {
+    let encoded = grid.to_compact_string();
+    assert_eq!(
+        &Grid::from_compact_string(&encoded).unwrap(),
+        grid,
+        "{}",
+        encoded
+    );
+
+    let encoded = grid.to_json();
+    assert_eq!(&Grid::from_json(&encoded).unwrap(), grid, "{}", encoded);
+
+    let encoded = grid.to_csv();
+    assert_eq!(
+        &Grid::parse_csv(encoded.lines()).unwrap(),
+        grid,
+        "{}",
+        encoded
+    );
+
+    let encoded = grid.to_line_format();
+    assert_eq!(
+        &Grid::from_line_format(&encoded).unwrap(),
+        grid,
+        "{}",
+        encoded
+    );
+
+    let encoded = grid.to_binarypuzzle();
+    assert_eq!(
+        &Grid::from_binarypuzzle(&encoded).unwrap(),
+        grid,
+        "{}",
+        encoded
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn strategies((puzzle, solution) in puzzles(8)) {
+            prop_assert_eq!(solution.empty_cells().count(), 0);
+            prop_assert!(solution.is_valid().is_ok());
+            prop_assert!(puzzle.verify_solution(&solution).is_ok());
+
+            assert_display_round_trip(&puzzle);
+            assert_encoding_round_trips(&puzzle);
+        }
+    }
+}
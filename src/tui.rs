@@ -0,0 +1,387 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+
+use crate::cell::{Cell, CellState};
+use crate::grid::Grid;
+use crate::index::Index;
+use crate::session::Session;
+
+// The timer is redrawn at least this often
+const TICK: Duration = Duration::from_millis(250);
+
+const HELP: &str =
+    "arrows move, space/0/1 set, backspace clears, p pencil marks, c checks them, h hint, u/r undo and redo, s saves, q quits";
+
+// A puzzle being played, independently of the terminal
+struct Game {
+    session: Session,
+    // File the session is saved to, if any
+    path: Option<PathBuf>,
+    cursor: Index,
+    // Whether 0 and 1 toggle pencil marks instead of setting the cell
+    pencil: bool,
+    message: String,
+    // Start of this sitting, the session holds the time of the previous ones
+    start: Instant,
+    // Time taken, once the puzzle is solved
+    finished: Option<Duration>,
+}
+
+impl Game {
+    fn new(session: Session, path: Option<PathBuf>) -> Self {
+        let finished = session.is_solved().then(|| session.elapsed());
+
+        Self {
+            session,
+            path,
+            cursor: Index(0, 0),
+            pencil: false,
+            message: String::new(),
+            start: Instant::now(),
+            finished,
+        }
+    }
+
+    fn grid(&self) -> &Grid {
+        self.session.grid()
+    }
+
+    fn move_cursor(&mut self, di: isize, dj: isize) {
+        let Index(i, j) = self.cursor;
+
+        // The cursor wraps around the edges
+        let i = (i as isize + di).rem_euclid(self.grid().height() as isize) as usize;
+        let j = (j as isize + dj).rem_euclid(self.grid().width() as isize) as usize;
+
+        self.cursor = Index(i, j);
+    }
+
+    // Cycle through empty, zero and one
+    fn toggle(&mut self) {
+        let next = match self.grid()[self.cursor] {
+            None => Some(Cell::Zero),
+            Some(Cell::Zero) => Some(Cell::One),
+            Some(Cell::One) => None,
+        };
+
+        self.play(next);
+    }
+
+    fn play(&mut self, cell: Option<Cell>) {
+        if self.finished.is_some() {
+            return;
+        }
+
+        if self.grid().state(self.cursor) == Some(CellState::Given) {
+            self.message = "this cell is given".to_string();
+            return;
+        }
+
+        if self.grid().is_hole(self.cursor) {
+            self.message = "this cell is outside of the puzzle".to_string();
+            return;
+        }
+
+        self.session.grid_mut().set_cell(self.cursor, cell);
+        self.message.clear();
+        self.check();
+    }
+
+    fn mark(&mut self, cell: Cell) {
+        if self.finished.is_some()
+            || self.grid()[self.cursor].is_some()
+            || self.grid().is_hole(self.cursor)
+        {
+            return;
+        }
+
+        self.session.grid_mut().toggle_mark(self.cursor, cell);
+    }
+
+    fn check_marks(&mut self) {
+        self.message = match self.grid().check_marks() {
+            Ok(wrong) if wrong.is_empty() => "the marks are consistent".to_string(),
+            Ok(wrong) => {
+                let Index(i, j) = wrong[0];
+                format!(
+                    "{} marks rule out a possible value, first at line {}, column {}",
+                    wrong.len(),
+                    i + 1,
+                    j + 1
+                )
+            }
+            Err(_) => "marks cannot be checked, a cell is wrong".to_string(),
+        };
+    }
+
+    fn hint(&mut self) {
+        if self.finished.is_some() {
+            return;
+        }
+
+        match self.grid().hint() {
+            Ok(Some((idx, cell))) => {
+                self.session.grid_mut().set_cell(idx, Some(cell));
+                self.cursor = idx;
+                self.message = format!("line {}, column {}: {}", idx.0 + 1, idx.1 + 1, cell);
+                self.check();
+            }
+            Ok(None) => self.message = "the grid is already complete".to_string(),
+            Err(_) => self.message = "no hint, a cell is wrong".to_string(),
+        }
+    }
+
+    fn undo(&mut self) {
+        if self.finished.is_none() && !self.session.grid_mut().undo() {
+            self.message = "nothing to undo".to_string();
+        }
+    }
+
+    fn redo(&mut self) {
+        if self.finished.is_some() {
+            return;
+        }
+
+        if self.session.grid_mut().redo() {
+            self.check();
+        } else {
+            self.message = "nothing to redo".to_string();
+        }
+    }
+
+    fn check(&mut self) {
+        if self.session.is_solved() {
+            self.finished = Some(self.session.elapsed() + self.start.elapsed());
+            self.message = "solved!".to_string();
+        }
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.finished
+            .unwrap_or_else(|| self.session.elapsed() + self.start.elapsed())
+    }
+
+    // The session as it is now, with the time of this sitting
+    fn to_session(&self) -> Session {
+        let mut session = self.session.clone();
+        session.set_elapsed(self.elapsed());
+        session
+    }
+
+    fn save(&mut self) {
+        let Some(path) = &self.path else {
+            self.message = "no session file to save to".to_string();
+            return;
+        };
+
+        self.message = match fs::write(path, self.to_session().to_json()) {
+            Ok(()) => format!("saved to {}", path.display()),
+            Err(err) => format!("cannot save: {}", err),
+        };
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let lines: Vec<Line> = (0..self.grid().height())
+            .map(|i| {
+                let spans: Vec<Span> = (0..self.grid().width())
+                    .map(|j| self.cell(Index(i, j)))
+                    .collect();
+
+                Line::from(spans)
+            })
+            .collect();
+
+        let seconds = self.elapsed().as_secs();
+        let mode = if self.pencil { ", pencil marks" } else { "" };
+        let status = vec![
+            Line::from(format!(
+                "time {:02}:{:02}{}",
+                seconds / 60,
+                seconds % 60,
+                mode
+            )),
+            Line::from(self.message.as_str()),
+            Line::from(HELP).style(Style::new().fg(Color::DarkGray)),
+        ];
+
+        // The board keeps its own size, the status goes below
+        let height = self.grid().height() as u16 + 2;
+        let width = self.grid().width() as u16 * 3 + 2;
+        let [board, info] =
+            Layout::vertical([Constraint::Length(height), Constraint::Min(0)]).areas(frame.area());
+        let [board, _] =
+            Layout::horizontal([Constraint::Length(width), Constraint::Min(0)]).areas(board);
+
+        frame.render_widget(
+            Paragraph::new(lines).block(Block::bordered().title("binero")),
+            board,
+        );
+        frame.render_widget(Paragraph::new(status), info);
+    }
+
+    fn cell(&self, idx: Index) -> Span<'_> {
+        let mark = self.grid().marks(idx).and_then(|marks| marks.single());
+
+        let text = match (self.grid()[idx], mark) {
+            _ if self.grid().is_hole(idx) => "   ".to_string(),
+            (Some(cell), _) => format!(" {} ", cell),
+            (None, Some(Cell::Zero)) => " ₀ ".to_string(),
+            (None, Some(Cell::One)) => " ₁ ".to_string(),
+            (None, None) => " · ".to_string(),
+        };
+
+        let mut style = match self.grid().state(idx) {
+            Some(CellState::Given) => Style::new().add_modifier(Modifier::BOLD),
+            None if mark.is_some() => Style::new().fg(Color::DarkGray),
+            _ => Style::new().fg(Color::Cyan),
+        };
+
+        if self.grid().is_conflict(idx) {
+            style = style.fg(Color::Red);
+        }
+
+        if idx == self.cursor {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+
+        Span::styled(text, style)
+    }
+
+    // Returns false once the player quits
+    fn handle(&mut self, code: KeyCode) -> bool {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Up => self.move_cursor(-1, 0),
+            KeyCode::Down => self.move_cursor(1, 0),
+            KeyCode::Left => self.move_cursor(0, -1),
+            KeyCode::Right => self.move_cursor(0, 1),
+            KeyCode::Char(' ') | KeyCode::Enter => self.toggle(),
+            KeyCode::Char('0') if self.pencil => self.mark(Cell::Zero),
+            KeyCode::Char('1') if self.pencil => self.mark(Cell::One),
+            KeyCode::Char('0') => self.play(Some(Cell::Zero)),
+            KeyCode::Char('1') => self.play(Some(Cell::One)),
+            KeyCode::Char('p') => self.pencil = !self.pencil,
+            KeyCode::Char('c') => self.check_marks(),
+            KeyCode::Backspace | KeyCode::Delete => self.play(None),
+            KeyCode::Char('h') => self.hint(),
+            KeyCode::Char('u') => self.undo(),
+            KeyCode::Char('r') => self.redo(),
+            KeyCode::Char('s') => self.save(),
+            _ => {}
+        }
+
+        true
+    }
+}
+
+// Play a puzzle in the terminal, returning the grid as left by the player
+pub fn play(puzzle: Grid) -> io::Result<Grid> {
+    let game = play_game(Game::new(Session::new(puzzle), None))?;
+    Ok(game.grid().clone())
+}
+
+// Play a session in the terminal, saving it to a file when asked and when
+// the player quits, so it can be carried on later
+pub fn play_session(session: Session, path: &Path) -> io::Result<Session> {
+    let game = play_game(Game::new(session, Some(path.to_path_buf())))?;
+    let session = game.to_session();
+
+    fs::write(path, session.to_json())?;
+    Ok(session)
+}
+
+fn play_game(mut game: Game) -> io::Result<Game> {
+    let mut terminal = ratatui::try_init()?;
+    let result = run(&mut terminal, &mut game);
+
+    ratatui::restore();
+    result.map(|_| game)
+}
+
+fn run(terminal: &mut DefaultTerminal, game: &mut Game) -> io::Result<()> {
+    loop {
+        terminal.draw(|frame| game.draw(frame))?;
+
+        if !event::poll(TICK)? {
+            continue;
+        }
+
+        if let Event::Key(key) = event::read()? {
+            if key.kind == KeyEventKind::Press && !game.handle(key.code) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn playing() {
+        let puzzle = Grid::parse(vec!["1 1 - 0", "- 0 - -", "- - 0 -", "- 1 - 0"].into_iter());
+        let mut game = Game::new(Session::new(puzzle.unwrap()), None);
+
+        // Givens cannot be changed
+        game.toggle();
+        assert_eq!(game.grid()[(0, 0)], Some(Cell::One));
+
+        game.move_cursor(0, 2);
+        game.toggle();
+        game.toggle();
+        assert_eq!(game.grid()[(0, 2)], Some(Cell::One));
+        assert!(game.grid().is_conflict(Index(0, 2)));
+
+        game.toggle();
+        assert_eq!(game.grid()[(0, 2)], None);
+
+        // Pencil marks note the values left in a cell
+        game.handle(KeyCode::Char('p'));
+        game.handle(KeyCode::Char('0'));
+        assert_eq!(game.grid()[(0, 2)], None);
+        assert_eq!(
+            game.grid().marks((0, 2)).unwrap().single(),
+            Some(Cell::Zero)
+        );
+
+        game.handle(KeyCode::Char('c'));
+        assert_eq!(game.message, "the marks are consistent");
+        game.handle(KeyCode::Char('p'));
+
+        // Moves and marks can be taken back
+        game.handle(KeyCode::Char('1'));
+        game.handle(KeyCode::Char('u'));
+        assert_eq!(game.grid()[(0, 2)], None);
+        game.handle(KeyCode::Char('u'));
+        assert!(game.grid().marks((0, 2)).is_none());
+        game.handle(KeyCode::Char('r'));
+        assert!(game.grid().marks((0, 2)).is_some());
+
+        // Moving wraps around the grid
+        game.move_cursor(-1, 2);
+        assert_eq!(game.cursor, Index(3, 0));
+
+        while game.finished.is_none() {
+            game.hint();
+        }
+
+        assert!(game.grid().empty_cells().next().is_none());
+        assert_eq!(game.message, "solved!");
+
+        // A solved session is over once carried on
+        game.handle(KeyCode::Char('s'));
+        assert_eq!(game.message, "no session file to save to");
+        let game = Game::new(game.to_session(), None);
+        assert!(game.finished.is_some());
+    }
+}
@@ -0,0 +1,114 @@
+use serde::Deserialize;
+use serde_json::json;
+use wasm_bindgen::prelude::*;
+
+use crate::cell::Cell;
+use crate::generator::{Generator, GeneratorConfig};
+use crate::grid::Grid;
+use crate::rating::Difficulty;
+use crate::symmetry::Symmetry;
+
+type WasmResult = Result<String, Box<dyn std::error::Error>>;
+
+// Options of generated puzzles, as a JSON object
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct GenerateOptions {
+    width: usize,
+    height: usize,
+    #[serde(default)]
+    difficulty: Option<Difficulty>,
+    #[serde(default)]
+    symmetry: Symmetry,
+    #[serde(default)]
+    seed: Option<u64>,
+}
+
+// Grids go in and out in the JSON format, errors are thrown as exceptions
+
+#[wasm_bindgen]
+pub fn solve(json: &str) -> Result<String, JsError> {
+    solve_json(json).map_err(|err| JsError::new(&err.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn hint(json: &str) -> Result<String, JsError> {
+    hint_json(json).map_err(|err| JsError::new(&err.to_string()))
+}
+
+#[wasm_bindgen]
+pub fn generate(options: &str) -> Result<String, JsError> {
+    // Browsers have no clock the generator can use, the seed comes from them
+    let seed = (js_sys::Math::random() * (1u64 << 53) as f64) as u64;
+    generate_json(options, seed).map_err(|err| JsError::new(&err.to_string()))
+}
+
+fn solve_json(json: &str) -> WasmResult {
+    let mut grid = Grid::from_json(json)?;
+    grid.solve()?;
+
+    Ok(grid.to_json())
+}
+
+// The next cell to fill, or null once the grid is complete
+fn hint_json(json: &str) -> WasmResult {
+    let grid = Grid::from_json(json)?;
+
+    let hint = match grid.hint()? {
+        Some((idx, cell)) => {
+            let value = match cell {
+                Cell::Zero => 0,
+                Cell::One => 1,
+            };
+
+            json!({ "line": idx.0, "column": idx.1, "value": value })
+        }
+        None => serde_json::Value::Null,
+    };
+
+    Ok(hint.to_string())
+}
+
+fn generate_json(options: &str, seed: u64) -> WasmResult {
+    let options: GenerateOptions = serde_json::from_str(options)?;
+    let mut config = GeneratorConfig::new().symmetry(options.symmetry);
+
+    if let Some(difficulty) = options.difficulty {
+        config = config.difficulty(difficulty);
+    }
+
+    let puzzle = Generator::new(options.width, options.height)?
+        .config(config)
+        .seed(options.seed.unwrap_or(seed))
+        .generate();
+
+    Ok(puzzle.to_json())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exports() {
+        let puzzle = r#"{"width":4,"height":4,"cells":[[1,1,null,0],[null,0,null,null],[null,null,0,null],[null,1,null,0]]}"#;
+
+        let solution = Grid::from_json(&solve_json(puzzle).unwrap()).unwrap();
+        assert!(Grid::from_json(puzzle)
+            .unwrap()
+            .verify_solution(&solution)
+            .is_ok());
+        assert_eq!(hint_json(&solution.to_json()).unwrap(), "null");
+        assert!(hint_json(puzzle).unwrap().contains("\"line\""));
+
+        let options =
+            r#"{"width":6,"height":4,"difficulty":"easy","symmetry":"vertical","seed":5}"#;
+        let puzzle = Grid::from_json(&generate_json(options, 0).unwrap()).unwrap();
+        assert_eq!((puzzle.width(), puzzle.height()), (6, 4));
+        assert_eq!(puzzle.metadata().seed(), Some(5));
+
+        assert!(solve_json("{}").is_err());
+        assert!(generate_json(r#"{"width":3,"height":3}"#, 0).is_err());
+        assert!(generate_json(r#"{"width":4,"height":4,"colour":1}"#, 0).is_err());
+    }
+}
@@ -0,0 +1,107 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+// Run the binary on a grid given on the standard input
+fn binero(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_binero"))
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("the binary runs");
+
+    child
+        .stdin
+        .take()
+        .expect("the standard input is piped")
+        .write_all(input.as_bytes())
+        .expect("the grid is written");
+
+    child.wait_with_output().expect("the binary exits")
+}
+
+fn exit_code(args: &[&str], input: &str) -> i32 {
+    binero(args, input)
+        .status
+        .code()
+        .expect("the binary exits normally")
+}
+
+const EMPTY: &str = "- - - -\n- - - -\n- - - -\n- - - -\n";
+const NO_SOLUTION: &str = "0 - - 0\n0 - - 0\n- - - -\n- - - -\n";
+
+#[test]
+fn exit_codes() {
+    assert_eq!(exit_code(&["solve", "examples/easy.txt"], ""), 0);
+    assert_eq!(exit_code(&["solve", "examples/missing.txt"], ""), 1);
+    assert_eq!(exit_code(&["solve"], "1 - 0 x\n"), 2);
+    assert_eq!(exit_code(&["solve", "examples/error.txt"], ""), 3);
+    assert_eq!(exit_code(&["solve"], NO_SOLUTION), 4);
+
+    let limited = ["--no-heuristics", "--max-nodes", "1", "solve"];
+    assert_eq!(exit_code(&limited, include_str!("../examples/hard.txt")), 5);
+    assert_eq!(exit_code(&["solve", "--require-unique"], EMPTY), 6);
+
+    // The highest code is returned when several grids fail
+    let several = ["solve", "examples/error.txt", "examples/missing.txt"];
+    assert_eq!(exit_code(&several, ""), 3);
+}
+
+#[test]
+fn several_solutions() {
+    // Checking and solving with --require-unique report the same error
+    let check = binero(&["check"], EMPTY);
+    let solve = binero(&["solve", "--require-unique"], EMPTY);
+    assert_eq!(check.status.code(), Some(6));
+    assert_eq!(solve.status.code(), Some(6));
+
+    let json = binero(&["--error-format", "json", "check"], EMPTY);
+    let stderr = String::from_utf8(json.stderr).unwrap();
+    assert!(stderr.contains(r#""kind":"multiple_solutions""#));
+}
+
+#[test]
+fn error_positions() {
+    let json = |input: &str| {
+        let output = binero(&["--error-format", "json", "solve"], input);
+        String::from_utf8(output.stderr).unwrap()
+    };
+
+    let error = json("1 - 0 -\n- - 1 x\n");
+    assert!(error.contains(r#""kind":"invalid_char""#));
+    assert!(error.contains(r#""line":2"#) && error.contains(r#""column":7"#));
+
+    let error = json("1 - 0 -\n- - 1\n");
+    assert!(error.contains(r#""kind":"width_mismatch""#));
+    assert!(error.contains(r#""line":2"#) && error.contains(r#""column":null"#));
+}
+
+#[test]
+fn hint_tiers() {
+    let puzzle = "1 1 - 0\n- 0 - -\n- - 0 -\n- 1 - 0\n";
+    let hint = |tier: &str| {
+        let output = binero(&["hint", "--tier", tier], puzzle);
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    // Each tier tells a bit more about the same cell
+    assert_eq!(hint("1"), "look at line 1\n");
+    assert_eq!(hint("2"), "look at line 1, column 3\n");
+    assert_eq!(hint("3"), "r1c3 = 0 because r1c1 and r1c2 are both 1\n");
+    assert_eq!(exit_code(&["hint", "--tier", "4"], puzzle), 2);
+}
+
+#[test]
+fn line_endings() {
+    // A byte order mark, lone CRs and a final CR LF, as the parser reads them
+    let input = "\u{feff}1 1 - 0\r- 0 - -\r- - 0 -\r- 1 - 0\r\n";
+    let output = binero(&["solve", "--format", "line"], input);
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap().trim(),
+        "1100001110010110"
+    );
+}